@@ -1,90 +1,86 @@
 use rand::distributions::{Distribution, Uniform, Bernoulli};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+#[cfg(feature = "blas-reference")]
+extern crate MyBlasLib;
+
+#[cfg(any(feature = "proptest", feature = "quickcheck"))]
+mod arbitrary;
+mod autodiff;
+#[cfg(feature = "blas")]
+mod blas;
+mod comparison;
+mod display;
+pub mod graph;
+mod integer;
+mod io;
+mod layout;
+mod macros;
+mod modint;
+pub mod nn;
+mod random;
+mod rational;
+pub mod regression;
+mod scalar;
+mod simd;
+mod strassen;
+mod transpose;
+mod vector;
+#[cfg(feature = "proptest")]
+pub use arbitrary::matrix_strategy;
+#[cfg(feature = "quickcheck")]
+pub use arbitrary::arbitrary_matrix;
+pub use autodiff::{Gradients, Tape, Var};
+#[cfg(feature = "blas")]
+pub use blas::with_native;
+pub use display::{MatrixDisplay, PrintOptions, PrintStyle};
+pub use io::{BinaryElement, MatrixMarketElement, ParseError};
+pub use layout::Layout;
+pub use modint::ModInt;
+pub use scalar::{Field, Real, Scalar};
+pub use strassen::{strassen_threshold, with_strassen_threshold};
+pub use vector::Vector;
 
 /// A generic Matrix type that supports basic matrix operations.
 ///
 /// # Type Parameters
 ///
-/// - `T`: The type of data stored in the matrix. It must implement the `Add`, `Sub`, and `Mul`
-///        traits to support basic matrix operations, as well as the `Clone` and `Default` traits
-///        to allow creating and copying matrices.
+/// - `T`: The type of data stored in the matrix. Most operations require a `Scalar`, a copyable
+///        number type. The elimination methods only require a `Field`, which may be `Clone`
+///        but not `Copy` (e.g. `BigRational`).
 ///
 /// # Fields
 ///
 /// - `data`: A flattened 1D array of matrix data.
 /// - `rows`: The number of rows in the matrix.
 /// - `cols`: The number of columns in the matrix.
-///
-/// #[derive(Debug, PartialEq)] is a Rust attribute that automatically generates implementations
-/// of the Debug and PartialEq traits for a struct or an enum. Debug is a trait that allows you
-/// to print out a struct or an enum in a formatted way using the {:?} format specifier. It is
-/// useful for debugging purposes. PartialEq is a trait that defines the equality comparison
-/// operation between two values. It is used to check if two instances of a struct or an enum
-/// are equal or not
-#[derive(Debug, PartialEq, Clone)]
-pub struct Matrix<T>
-    where
-        T: std::ops::Add<Output=T> +
-        std::ops::Sub<Output=T> +
-        std::ops::Mul<Output=T> +
-        std::ops::Div<Output=T> +
-        std::clone::Clone +
-        std::marker::Copy +
-        std::default::Default +
-        num_traits::Zero +
-        num_traits::One
-{
+/// - `layout`: The order of the elements in `data`, row-major unless created otherwise (see
+///   `Layout`).
+///
+/// #[derive(Debug)] is a Rust attribute that automatically generates an implementation of the
+/// Debug trait for a struct or an enum. Debug is a trait that allows you to print out a struct
+/// or an enum in a formatted way using the {:?} format specifier. It is useful for debugging
+/// purposes. PartialEq is implemented by hand (see comparison.rs), so that matrices with the same
+/// elements are equal whatever their layouts.
+#[derive(Debug, Clone)]
+pub struct Matrix<T> {
     data: Vec<T>,
     rows: usize,
     cols: usize,
+    layout: Layout,
 }
 
 ///////////////////////////////////////////////////////////////////////////////////////////////////
 ////////////////////////////////////// STANDARD METHODS ///////////////////////////////////////////
 ///////////////////////////////////////////////////////////////////////////////////////////////////
 
-/// Implements the `fmt::Display` trait for the `Matrix` struct, allowing matrices to be
-/// printed using the `println!` macro. The matrix is printed row by row, with each element
-/// separated by a space character. A newline character is inserted after each row.
-///
-/// # Examples
-///
-/// ```
-/// let mat = Matrix::from_vec(2, 2, vec![1, 2, 3, 4]);
-/// println!("{}", mat);
-/// // Output:
-/// // 1 2
-/// // 3 4
-/// ```
-impl<T> std::fmt::Display for Matrix<T>
-    where
-        T:
-        std::ops::Add<Output=T> +
-        std::ops::Sub<Output=T> +
-        std::ops::Mul<Output=T> +
-        std::ops::Div<Output=T> +
-        std::fmt::Display +
-        std::default::Default +
-        std::fmt::Display +
-        std::clone::Clone +
-        std::marker::Copy +
-        num_traits::Zero +
-        num_traits::One
-{
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        for i in 0..self.rows {
-            for j in 0..self.cols {
-                write!(f, "{} ", self[(i, j)])?;
-            }
-            writeln!(f)?;
-        }
-        Ok(())
-    }
-}
-
-
-
 /// Implements the addition operation for matrices. The matrices must have the same dimensions.
 ///
+/// For `f32`, `f64` and `i32` elements the addition uses vectorized kernels (AVX2 when the CPU
+/// supports it). The `i32` kernels are only used in release builds, so overflow still panics in
+/// debug builds.
+///
 /// # Panics
 ///
 /// This function will panic if the dimensions of the matrices are not the same.
@@ -99,36 +95,20 @@ impl<T> std::fmt::Display for Matrix<T>
 /// A new `Matrix` object representing the result of the addition operation.
 impl<T> std::ops::Add for Matrix<T>
     where
-        T: std::ops::Add<Output=T> +
-        std::ops::Sub<Output=T> +
-        std::ops::Mul<Output=T> +
-        std::ops::Div<Output=T> +
+        T: Scalar +
         std::fmt::Display +
-        std::default::Default +
-        std::fmt::Display +
-        std::clone::Clone +
-        std::marker::Copy +
-        num_traits::Zero +
-        num_traits::One
+        'static
 {
     type Output = Self;
 
-    fn add(self, other: Self) -> Self {
+    fn add(mut self, other: Self) -> Self {
         // Check that the dimensions of both matrices match
         assert_eq!(self.rows, other.rows);
         assert_eq!(self.cols, other.cols);
 
-        // Create a new matrix with the same dimensions as self and other
-        let mut result = Matrix::new(self.rows, self.cols);
-
-        // Add the corresponding elements of self and other to get the elements of the result
-        for i in 0..self.rows {
-            for j in 0..self.cols {
-                let val = self[(i, j)].clone() + other[(i, j)].clone();
-                result[(i, j)] = val;
-            }
-        }
-        result
+        // Add the elements of other to those of self in place (vectorized for f32, f64 and i32)
+        simd::add(&mut self.data, &other.data_in(self.layout));
+        self
     }
 }
 
@@ -136,7 +116,8 @@ impl<T> std::ops::Add for Matrix<T>
 /// Implements the subtraction operator for matrices.
 ///
 /// Given two matrices `self` and `other`, this function returns a new matrix whose elements are
-/// the difference between the corresponding elements of `self` and `other`.
+/// the difference between the corresponding elements of `self` and `other`. As for addition,
+/// `f32`, `f64` and `i32` elements are subtracted with vectorized kernels.
 ///
 /// # Arguments
 ///
@@ -158,37 +139,21 @@ impl<T> std::ops::Add for Matrix<T>
 /// ```
 impl<T> std::ops::Sub for Matrix<T>
     where
-        T:
-        std::ops::Add<Output=T> +
-        std::ops::Sub<Output=T> +
-        std::ops::Mul<Output=T> +
-        std::ops::Div<Output=T> +
+        T: Scalar +
         std::fmt::Display +
-        std::default::Default +
-        std::fmt::Display +
-        std::clone::Clone +
-        std::marker::Copy +
-        num_traits::Zero +
-        num_traits::One
+        'static
 {
     type Output = Self;
 
-    fn sub(self, other: Self) -> Self {
+    fn sub(mut self, other: Self) -> Self {
         // Check that the dimensions of both matrices match
         assert_eq!(self.rows, other.rows);
         assert_eq!(self.cols, other.cols);
 
-        // Create a new matrix with the same dimensions as self and other
-        let mut result = Matrix::new(self.rows, self.cols);
-
-        // Subtract the corresponding elements of other from self to get the elements of the result
-        for i in 0..self.rows {
-            for j in 0..self.cols {
-                let val = self[(i, j)].clone() - other[(i, j)].clone();
-                result[(i, j)] = val;
-            }
-        }
-        result
+        // Subtract the elements of other from those of self in place (vectorized for f32, f64
+        // and i32)
+        simd::sub(&mut self.data, &other.data_in(self.layout));
+        self
     }
 }
 
@@ -219,24 +184,20 @@ impl<T> std::ops::Sub for Matrix<T>
 ///
 /// The above example multiplies a 2x3 matrix `a` by a 3x2 matrix `b` to get a 2x2 matrix `c`.
 ///
+/// Inside `with_strassen_threshold`, square matrices larger than the threshold are multiplied
+/// with the Strassen-Winograd algorithm (see `mul_strassen`); by default every product uses the
+/// classical triple loop. With the `blas` feature, other products of `f32` and `f64` matrices
+/// call `sgemm` and `dgemm` (see `with_native`).
+///
 /// # Panics
 ///
 /// This function will panic if the number of columns in the first matrix is not equal to the
 /// number of rows in the second matrix.
 impl<T> std::ops::Mul for Matrix<T>
     where
-        T:
-        std::ops::Add<Output=T> +
-        std::ops::Sub<Output=T> +
-        std::ops::Mul<Output=T> +
-        std::ops::Div<Output=T> +
-        std::fmt::Display +
-        std::default::Default +
+        T: Scalar +
         std::fmt::Display +
-        std::clone::Clone +
-        std::marker::Copy +
-        num_traits::Zero +
-        num_traits::One
+        'static
 {
     type Output = Self;
 
@@ -245,6 +206,14 @@ impl<T> std::ops::Mul for Matrix<T>
         // in the second matrix
         assert_eq!(self.cols, other.rows);
 
+        if let Some(result) = strassen::try_mul(&self, &other) {
+            return result;
+        }
+        #[cfg(feature = "blas")]
+        if let Some(result) = blas::multiply(&self, &other) {
+            return result;
+        }
+
         // Create a new matrix with the appropriate dimensions for the result of the multiplication
         let mut result = Matrix::new(self.rows, other.cols);
 
@@ -277,22 +246,11 @@ impl<T> std::ops::Mul for Matrix<T>
 /// let matrix = Matrix::new(2, 2, vec![1, 2, 3, 4]);
 /// assert_eq!(matrix[(0, 0)], 1);
 /// ```
-impl<T> std::ops::Index<(usize, usize)> for Matrix<T>
-    where
-        T: std::ops::Add<Output=T> +
-        std::ops::Sub<Output=T> +
-        std::ops::Mul<Output=T> +
-        std::ops::Div<Output=T> +
-        std::default::Default +
-        std::clone::Clone +
-        std::marker::Copy +
-        num_traits::Zero +
-        num_traits::One
-{
+impl<T> std::ops::Index<(usize, usize)> for Matrix<T> {
     type Output = T;
 
     fn index(&self, (i, j): (usize, usize)) -> &Self::Output {
-        let index = i * self.cols + j;
+        let index = self.offset(i, j);
         &self.data[index]
     }
 }
@@ -313,20 +271,9 @@ impl<T> std::ops::Index<(usize, usize)> for Matrix<T>
 /// matrix[(0, 0)] = 5;
 /// assert_eq!(matrix[(0, 0)], 5);
 /// ```
-impl<T> std::ops::IndexMut<(usize, usize)> for Matrix<T>
-    where
-        T: std::ops::Add<Output=T> +
-        std::ops::Sub<Output=T> +
-        std::ops::Mul<Output=T> +
-        std::ops::Div<Output=T> +
-        std::default::Default +
-        std::clone::Clone +
-        std::marker::Copy +
-        num_traits::Zero +
-        num_traits::One
-{
+impl<T> std::ops::IndexMut<(usize, usize)> for Matrix<T> {
     fn index_mut(&mut self, (i, j): (usize, usize)) -> &mut T {
-        let index = i * self.cols + j;
+        let index = self.offset(i, j);
         &mut self.data[index]
     }
 }
@@ -353,19 +300,11 @@ impl<T> std::ops::IndexMut<(usize, usize)> for Matrix<T>
 /// ```
 impl<T> Matrix<T>
     where
-        T: std::ops::Add<Output=T> +
-        std::ops::Sub<Output=T> +
-        std::ops::Mul<Output=T> +
-        std::ops::Div<Output=T> +
-        std::default::Default +
-        std::clone::Clone +
-        std::marker::Copy +
-        num_traits::Zero +
-        num_traits::One
+        T: Scalar
 {
     pub fn new(rows: usize, cols: usize) -> Self {
         let data_vector = vec![T::default(); rows * cols];
-        Matrix { rows: rows, cols: cols, data: data_vector }
+        Matrix { rows: rows, cols: cols, data: data_vector, layout: Layout::RowMajor }
     }
 }
 
@@ -391,18 +330,7 @@ impl<T> Matrix<T>
 /// let data = vec![1, 2, 3, 4, 5, 6];
 /// let mat = Matrix::from_vec(2, 3, data);
 /// ```
-impl<T> Matrix<T>
-    where
-        T: std::ops::Add<Output=T> +
-        std::ops::Sub<Output=T> +
-        std::ops::Mul<Output=T> +
-        std::ops::Div<Output=T> +
-        std::default::Default +
-        std::clone::Clone +
-        std::marker::Copy +
-        num_traits::Zero +
-        num_traits::One
-{
+impl<T> Matrix<T> {
     pub fn from_vec(rows: usize, cols: usize, data: Vec<T>) -> Self {
         assert_eq!(rows * cols, data.len(), "Data length does not match matrix dimensions");
 
@@ -410,52 +338,36 @@ impl<T> Matrix<T>
             rows,
             cols,
             data,
+            layout: Layout::RowMajor,
         }
     }
 }
 
-/// Prints the contents of the matrix to the standard output stream.
+/// Creates a new matrix from a nested array of rows, taking the dimensions from the array type.
 ///
-/// The matrix is printed row by row, with each row being displayed on a separate line.
-/// Elements are separated by a single space character.
-/// The formatting of each element is controlled by the `std::fmt::Display` trait, which must be
-/// implemented for the element type `T`.
+/// Since every row of the array has the same length `C`, the shape is checked at compile time.
+/// This is the constructor used by the `matrix!` macro.
 ///
 /// # Examples
 ///
 /// ```
-/// # use linear_algebra::Matrix;
-/// let mat = Matrix::from_vec(2, 3, vec![1, 2, 3, 4, 5, 6]);
-/// mat.print();
-/// // Output:
-/// // 1 2 3
-/// // 4 5 6
+/// let mat = Matrix::from_array([[1, 2, 3], [4, 5, 6]]);
+/// assert_eq!(mat, Matrix::from_vec(2, 3, vec![1, 2, 3, 4, 5, 6]));
 /// ```
 impl<T> Matrix<T>
     where
-        T: std::ops::Add<Output=T> +
-        std::ops::Sub<Output=T> +
-        std::ops::Mul<Output=T> +
-        std::ops::Div<Output=T> +
-        std::default::Default +
-        std::clone::Clone +
-        std::marker::Copy +
-        std::fmt::Display +
-        num_traits::Zero +
-        num_traits::One
+        T: Scalar
 {
-    /// Prints the matrix to the console.
-    pub fn print(&self) {
-        for i in 0..self.rows {
-            for j in 0..self.cols {
-                print!("{} ", self[(i, j)]);
-            }
-            println!("");
+    pub fn from_array<const R: usize, const C: usize>(data: [[T; C]; R]) -> Self {
+        Matrix {
+            rows: R,
+            cols: C,
+            data: data.iter().flatten().copied().collect(),
+            layout: Layout::RowMajor,
         }
     }
 }
 
-
 /// Returns a view of the specified row in the matrix as a slice of type `&[T]`.
 ///
 /// The `view_row` method takes a single argument, `row`, which specifies the
@@ -481,22 +393,17 @@ impl<T> Matrix<T>
 /// If the specified row index is out of bounds (i.e., less than 0 or greater
 /// than or equal to the number of rows in the matrix), the method panics with
 /// an error message indicating the out-of-bounds index and the dimensions of
-/// the matrix.
+/// the matrix. It also panics if the row is not contiguous in the data buffer,
+/// i.e. the matrix is column-major with more than one column (see `view_col`).
 impl<T> Matrix<T>
     where
-        T: std::ops::Add<Output = T>
-        + std::ops::Sub<Output = T>
-        + std::ops::Mul<Output = T>
-        + std::ops::Div<Output = T>
-        + std::clone::Clone
-        + std::marker::Copy
-        + std::default::Default
-        + num_traits::Zero
-        + num_traits::One,
+        T: Scalar,
 {
     pub fn view_row(&self, row: usize) -> &[T] {
         if row < self.rows {
-            let start = row * self.cols;
+            assert!(self.strides().1 == 1 || self.cols <= 1,
+                    "Rows of a column-major matrix are not contiguous, use get_row or to_layout");
+            let start = self.offset(row, 0);
             let end = start + self.cols;
             &self.data[start..end]
         } else {
@@ -532,21 +439,12 @@ impl<T> Matrix<T>
 /// ```
 impl<T> Matrix<T>
     where
-        T: std::ops::Add<Output = T>
-        + std::ops::Sub<Output = T>
-        + std::ops::Mul<Output = T>
-        + std::ops::Div<Output = T>
-        + std::clone::Clone
-        + std::marker::Copy
-        + std::default::Default
-        + num_traits::Zero
-        + num_traits::One,
+        T: Scalar,
 {
     pub fn get_row(&self, row: usize) -> Matrix<T> {
         if row < self.rows {
-            let start = row * self.cols;
-            let end = start + self.cols;
-            Matrix { rows: 1_usize, cols: self.cols, data: (&self.data[start..end]).clone().into_vec()}
+            let data = self.row_elements(row).collect();
+            Matrix { rows: 1_usize, cols: self.cols, data, layout: Layout::RowMajor }
         } else {
             panic!(
                 "Row index: {} out of bounds for matrix of dim {} x {}",
@@ -578,21 +476,12 @@ impl<T> Matrix<T>
 /// ```
 impl<T> Matrix<T>
     where
-        T: std::ops::Add<Output = T>
-        + std::ops::Sub<Output = T>
-        + std::ops::Mul<Output = T>
-        + std::ops::Div<Output = T>
-        + std::clone::Clone
-        + std::marker::Copy
-        + std::default::Default
-        + num_traits::Zero
-        + num_traits::One,
+        T: Scalar,
 {
     pub fn get_col(&self, col: usize) -> Matrix<T> {
         if col < self.cols {
-            let mut data = Vec::with_capacity(self.rows);
-            for i in 0..self.rows { data.push(self.data[i * self.cols + col]); }
-            Matrix { rows: self.rows, cols: 1_usize, data: data }
+            let data = self.col_elements(col).collect();
+            Matrix { rows: self.rows, cols: 1_usize, data, layout: Layout::RowMajor }
         } else {
             panic!(
                 "Column index: {} out of bounds for matrix of dim {} x {}",
@@ -635,19 +524,12 @@ impl<T> Matrix<T>
 /// A new `Matrix` of size `rows` by `cols` with all entries initialized to zero.
 impl<T> Matrix<T>
     where
-        T: std::ops::Add<Output=T> +
-        std::ops::Sub<Output=T> +
-        std::ops::Mul<Output=T> +
-        std::ops::Div<Output=T> +
-        std::default::Default +
-        std::clone::Clone +
-        std::marker::Copy +
-        num_traits::Zero +
-        num_traits::One
+        T: Clone +
+        num_traits::Zero
 {
     pub fn zeros(rows: usize, cols: usize) -> Self {
         let data_vector = vec![T::zero(); rows * cols];
-        Matrix { rows: rows, cols: cols, data: data_vector }
+        Matrix { rows: rows, cols: cols, data: data_vector, layout: Layout::RowMajor }
     }
 }
 
@@ -679,19 +561,11 @@ impl<T> Matrix<T>
 /// A new `Matrix` of size `rows` by `cols` with all entries initialized to one.
 impl<T> Matrix<T>
     where
-        T: std::ops::Add<Output=T> +
-        std::ops::Sub<Output=T> +
-        std::ops::Mul<Output=T> +
-        std::ops::Div<Output=T> +
-        std::default::Default +
-        std::clone::Clone +
-        std::marker::Copy +
-        num_traits::Zero +
-        num_traits::One
+        T: Scalar
 {
     pub fn ones(rows: usize, cols: usize) -> Self {
         let data_vector = vec![T::one(); rows * cols];
-        Matrix { rows: rows, cols: cols, data: data_vector }
+        Matrix { rows: rows, cols: cols, data: data_vector, layout: Layout::RowMajor }
     }
 }
 
@@ -736,22 +610,16 @@ impl<T> Matrix<T>
 /// - `Matrix::from_vec`: Constructs a new `Matrix` instance from a vector
 impl<T> Matrix<T>
     where
-        T: std::ops::Add<Output=T> +
-        std::ops::Sub<Output=T> +
-        std::ops::Mul<Output=T> +
-        std::ops::Div<Output=T> +
-        std::default::Default +
-        std::clone::Clone +
-        std::marker::Copy +
+        T: Clone +
         num_traits::Zero +
-        num_traits::One,
+        num_traits::One
 {
     pub fn identity(size: usize) -> Self {
         let mut data_vector = vec![T::zero(); size * size];
         for i in 0..size {
             data_vector[i * (size + 1)] = T::one();
         }
-        Matrix { rows: size, cols: size, data: data_vector }
+        Matrix { rows: size, cols: size, data: data_vector, layout: Layout::RowMajor }
     }
 }
 
@@ -760,7 +628,9 @@ impl<T> Matrix<T>
 /// Computes and returns the transpose of the matrix.
 ///
 /// Returns a new matrix with the columns and rows of the original matrix swapped.
-/// The values of the original matrix are cloned into the new matrix.
+/// The values of the original matrix are cloned into the new matrix, recursively halving the
+/// matrix into blocks that fit in cache. Use `transpose_into` or `transpose_in_place` to avoid
+/// allocating a new matrix.
 ///
 /// # Examples
 ///
@@ -779,28 +649,67 @@ impl<T> Matrix<T>
 /// ```
 impl<T> Matrix<T>
     where
-        T: std::ops::Add<Output=T> +
-        std::ops::Sub<Output=T> +
-        std::ops::Mul<Output=T> +
-        std::ops::Div<Output=T> +
-        std::default::Default +
-        std::clone::Clone +
-        std::marker::Copy +
-        num_traits::Zero +
-        num_traits::One
+        T: Scalar
 {
     pub fn transpose(&self) -> Matrix<T> {
-        let mut result = Matrix::new(self.cols, self.rows);
+        let (rows, cols) = self.buffer_shape();
+        Matrix { rows: self.cols, cols: self.rows, data: transpose::transposed(&self.data, rows, cols), layout: self.layout }
+    }
+}
 
-        for i in 0..self.rows {
-            for j in 0..self.cols {
-                result[(j, i)] = self[(i, j)].clone();
-            }
-        }
+/// Multiplies every element of the matrix by a scalar and returns the result as a new matrix.
+///
+/// # Examples
+///
+/// ```
+/// let a = Matrix::from_vec(2, 2, vec![1, 2, 3, 4]);
+/// assert_eq!(a.scale(3), Matrix::from_vec(2, 2, vec![3, 6, 9, 12]));
+/// ```
+impl<T> Matrix<T>
+    where
+        T: Scalar +
+        'static
+{
+    pub fn scale(&self, factor: T) -> Matrix<T> {
+        let mut result = self.clone();
+        simd::scale(&mut result.data, factor);
         result
     }
 }
 
+/// Applies a function to every element of the matrix (`map`), or multiplies two matrices of the
+/// same dimensions element by element (`hadamard`), and returns the result as a new matrix with
+/// the layout of `self`.
+///
+/// # Panics
+///
+/// `hadamard` will panic if the dimensions of the matrices are not the same.
+///
+/// # Examples
+///
+/// ```
+/// let a = Matrix::from_vec(2, 2, vec![1, 2, 3, 4]);
+/// assert_eq!(a.map(|x| x * x), Matrix::from_vec(2, 2, vec![1, 4, 9, 16]));
+/// assert_eq!(a.hadamard(&a), a.map(|x| x * x));
+/// ```
+impl<T> Matrix<T>
+    where
+        T: Scalar
+{
+    pub fn map<F: Fn(T) -> T>(&self, f: F) -> Matrix<T> {
+        Matrix { rows: self.rows, cols: self.cols, data: self.data.iter().map(|&x| f(x)).collect(), layout: self.layout }
+    }
+
+    pub fn hadamard(&self, other: &Matrix<T>) -> Matrix<T> {
+        assert!(self.rows == other.rows && self.cols == other.cols,
+                "Element-wise product requires matrices of the same dimensions, got {} x {} and {} x {}",
+                self.rows, self.cols, other.rows, other.cols);
+
+        let data = self.data.iter().zip(other.data_in(self.layout).iter()).map(|(&a, &b)| a * b).collect();
+        Matrix { rows: self.rows, cols: self.cols, data, layout: self.layout }
+    }
+}
+
 /// Generates a matrix of size `rows` by `cols` with random values sampled uniformly
 /// from the range `[lower_bound, upper_bound)`.
 ///
@@ -814,34 +723,51 @@ impl<T> Matrix<T>
 /// # Panics
 ///
 /// This function will panic if `upper_bound` is less than or equal to `lower_bound`.
+///
+/// # Reproducibility
+///
+/// `random_uniform` uses the thread-local generator `rand::thread_rng()`, so every call gives
+/// different values. Use `random_uniform_with_rng` to supply any `rand::Rng` (e.g. a shared
+/// seeded generator driving a whole Monte-Carlo run), or `random_uniform_seeded` to get the
+/// same matrix for the same `seed` on every run.
+///
+/// ```
+/// let a = Matrix::<f64>::random_uniform_seeded(3, 3, 0.0, 1.0, 42);
+/// let b = Matrix::<f64>::random_uniform_seeded(3, 3, 0.0, 1.0, 42);
+/// assert_eq!(a, b);
+/// ```
 impl<T> Matrix<T>
     where
-        T: std::ops::Add<Output=T> +
-        std::ops::Sub<Output=T> +
-        std::ops::Mul<Output=T> +
-        std::ops::Div<Output=T> +
-        std::default::Default +
-        std::clone::Clone +
-        std::marker::Copy +
-        num_traits::Zero +
-        num_traits::One +
+        T: Scalar +
         rand::distributions::uniform::SampleUniform +
         std::cmp::PartialOrd
 {
     pub fn random_uniform(rows: usize, cols: usize, lower_bound: T, upper_bound: T) -> Matrix<T> {
+        Self::random_uniform_with_rng(rows, cols, lower_bound, upper_bound, &mut rand::thread_rng())
+    }
+
+    /// Same as `random_uniform`, but draws the samples from the given random number generator.
+    pub fn random_uniform_with_rng<R>(rows: usize, cols: usize, lower_bound: T, upper_bound: T, rng: &mut R) -> Matrix<T>
+        where
+            R: rand::Rng + ?Sized
+    {
         if upper_bound <= lower_bound {
             panic!("Upper bound cannot be less than or equal to lower bound");
         }
 
         // Sample uniformly in [low, high)
         let distribution = Uniform::try_from(lower_bound..upper_bound).unwrap();
-        let mut rng = rand::thread_rng();
 
         let mut vec_data = vec![T::zero(); rows * cols];
         for idx in 0..rows*cols {
-            vec_data[idx] = distribution.sample(&mut rng);
+            vec_data[idx] = distribution.sample(rng);
         }
-        Matrix { rows: rows, cols: cols, data: vec_data }
+        Matrix { rows: rows, cols: cols, data: vec_data, layout: Layout::RowMajor }
+    }
+
+    /// Same as `random_uniform`, but draws the samples from a generator seeded with `seed`.
+    pub fn random_uniform_seeded(rows: usize, cols: usize, lower_bound: T, upper_bound: T, seed: u64) -> Matrix<T> {
+        Self::random_uniform_with_rng(rows, cols, lower_bound, upper_bound, &mut StdRng::seed_from_u64(seed))
     }
 }
 
@@ -864,37 +790,45 @@ impl<T> Matrix<T>
 ///
 /// A new matrix of dimensions `rows` by `cols`, where each entry is independently sampled
 /// from a Bernoulli distribution with success probability `probability`.
+///
+/// # Reproducibility
+///
+/// As with `random_uniform`, the `_with_rng` variant samples from a caller-supplied
+/// `rand::Rng` and the `_seeded` variant from a generator seeded with `seed`. Seeded matrices
+/// are identical across runs for a given version of the `rand` crate.
 impl<T> Matrix<T>
     where
-        T: std::ops::Add<Output=T> +
-        std::ops::Sub<Output=T> +
-        std::ops::Mul<Output=T> +
-        std::ops::Div<Output=T> +
-        std::default::Default +
-        std::clone::Clone +
-        std::marker::Copy +
-        num_traits::Zero +
-        num_traits::One +
+        T: Scalar +
         rand::distributions::uniform::SampleUniform +
         std::cmp::PartialOrd
 {
     pub fn random_bernoulli(rows: usize, cols: usize, probability: f64) -> Matrix<T> {
+        Self::random_bernoulli_with_rng(rows, cols, probability, &mut rand::thread_rng())
+    }
+
+    /// Same as `random_bernoulli`, but draws the samples from the given random number generator.
+    pub fn random_bernoulli_with_rng<R>(rows: usize, cols: usize, probability: f64, rng: &mut R) -> Matrix<T>
+        where
+            R: rand::Rng + ?Sized
+    {
         if (probability <  0.0) || (probability > 1.0) {
             panic!("Probability should be between 0.0 and 1.0");
         }
         let distribution = Bernoulli::new(probability).unwrap();
-        let mut rng = rand::thread_rng();
 
         let mut vec_data = vec![T::zero(); rows * cols];
         for idx in 0..rows*cols {
-            vec_data[idx] = if distribution.sample(&mut rng) { T::one() } else { T::zero() };
+            vec_data[idx] = if distribution.sample(rng) { T::one() } else { T::zero() };
         }
-        Matrix { rows: rows, cols: cols, data: vec_data }
+        Matrix { rows: rows, cols: cols, data: vec_data, layout: Layout::RowMajor }
     }
-}
 
+    /// Same as `random_bernoulli`, but draws the samples from a generator seeded with `seed`.
+    pub fn random_bernoulli_seeded(rows: usize, cols: usize, probability: f64, seed: u64) -> Matrix<T> {
+        Self::random_bernoulli_with_rng(rows, cols, probability, &mut StdRng::seed_from_u64(seed))
+    }
+}
 
-// TODO: implement partial pivoting LU decomposition (LUP) - see: https://courses.engr.illinois.edu/cs357/fa2019/references/ref-7-linsys/
 
 /// Performs LU decomposition on the input matrix `self` and returns a tuple
 /// containing the lower triangular matrix `L` and upper triangular matrix `U`.
@@ -925,16 +859,13 @@ impl<T> Matrix<T>
 /// This method creates a copy of the input matrix `self` to avoid mutating the original.
 impl<T> Matrix<T>
     where
-        T: std::ops::Add<Output=T> +
+        T: Clone +
+        num_traits::Zero +
+        num_traits::One +
         std::ops::Sub<Output=T> +
         std::ops::Mul<Output=T> +
         std::ops::Div<Output=T> +
-        std::cmp::PartialEq +
-        std::default::Default +
-        std::clone::Clone +
-        std::marker::Copy +
-        num_traits::Zero +
-        num_traits::One
+        std::cmp::PartialEq
 {
     #[allow(non_snake_case)]
     pub fn LU_decompose(&self) -> (Matrix<T>, Matrix<T>) {
@@ -949,8 +880,8 @@ impl<T> Matrix<T>
             }
         }
 
-        let mut L = Matrix::zeros(self.rows, self.cols);
-        let mut U = Matrix::zeros(self.rows, self.cols);
+        let mut L = Self::zeros(self.rows, self.cols);
+        let mut U = Self::zeros(self.rows, self.cols);
 
         for i in 0..self.rows {
 
@@ -959,10 +890,10 @@ impl<T> Matrix<T>
                 // Summation of L(i, j) * U(j, k)
                 let mut sum = T::zero();
                 for j in 0..i {
-                    sum = sum + L[(i, j)] * U[(j, k)];
+                    sum = sum + L[(i, j)].clone() * U[(j, k)].clone();
                 }
                 // Evaluating U(i, k)
-                U[(i, k)] = self[(i, k)] - sum;
+                U[(i, k)] = self[(i, k)].clone() - sum;
             }
 
             // Lower Triangular (L mat)
@@ -974,13 +905,1474 @@ impl<T> Matrix<T>
                     // Summation of L(k, j) * U(j, i)
                     let mut sum = T::zero();
                     for j in 0..i {
-                        sum = sum + L[(k, j)] * U[(j, i)];
+                        sum = sum + L[(k, j)].clone() * U[(j, i)].clone();
                     }
                     // Evaluating L(k, i)
-                    L[(k, i)] = (self[(k, i)] - sum) / U[(i, i)];
+                    L[(k, i)] = (self[(k, i)].clone() - sum) / U[(i, i)].clone();
                 }
             }
         }
         (L, U)
     }
 }
+
+/// Performs LU decomposition with partial pivoting on the input matrix `self` and returns a
+/// tuple `(L, U, P)` of a unit lower triangular matrix `L`, an upper triangular matrix `U` and
+/// a permutation matrix `P`, such that `P * A = L * U`.
+///
+/// # Panics
+///
+/// This method will panic if the matrix `self` is not square.
+///
+/// # Examples
+///
+/// ```
+/// let mat = Matrix::from_vec(3, 3, vec![0.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 10.0]);
+/// let (L, U, P) = mat.LUP_decompose();
+/// // P * mat == L * U
+/// ```
+///
+/// # Notes
+///
+/// At step `k` the row with the largest absolute value in column `k` (on or below the diagonal)
+/// is swapped into the pivot position, so every multiplier stored in `L` has magnitude at most
+/// one. Unlike `LU_decompose`, this works for matrices with zeros on the diagonal and is
+/// numerically stable in practice. A singular matrix decomposes without panicking, leaving a
+/// zero on the diagonal of `U`. For rational matrices the decomposition is exact. See:
+/// https://courses.engr.illinois.edu/cs357/fa2019/references/ref-7-linsys/
+impl<T> Matrix<T>
+    where
+        T: Field
+{
+    /// Factorises the matrix in place, returning the combined `L \ U` matrix (with the unit
+    /// diagonal of `L` implied) and the row permutation, where row `i` of `P * A` is row
+    /// `permutation[i]` of `A`.
+    fn lup_factor(&self) -> (Matrix<T>, Vec<usize>) {
+        // Has to be square matrix
+        assert_eq!(self.rows, self.cols);
+
+        #[cfg(feature = "blas")]
+        if let Some(factors) = blas::lu_factor(self) {
+            return factors;
+        }
+
+        let n = self.rows;
+        let mut lu = self.clone();
+        let mut permutation: Vec<usize> = (0..n).collect();
+
+        for k in 0..n {
+            // Find pivot row
+            let mut pivot = k;
+            for i in (k + 1)..n {
+                if lu[(i, k)].abs() > lu[(pivot, k)].abs() {
+                    pivot = i;
+                }
+            }
+            if pivot != k {
+                lu.swap_rows(k, pivot);
+                permutation.swap(k, pivot);
+            }
+            if lu[(k, k)] == T::zero() {
+                continue;
+            }
+
+            // Eliminate below the pivot
+            for i in (k + 1)..n {
+                let factor = lu[(i, k)].clone() / lu[(k, k)].clone();
+                for j in (k + 1)..n {
+                    lu[(i, j)] = lu[(i, j)].clone() - factor.clone() * lu[(k, j)].clone();
+                }
+                lu[(i, k)] = factor;
+            }
+        }
+        (lu, permutation)
+    }
+
+    #[allow(non_snake_case)]
+    pub fn LUP_decompose(&self) -> (Matrix<T>, Matrix<T>, Matrix<T>) {
+        let (lu, permutation) = self.lup_factor();
+        let n = self.rows;
+
+        let mut L = Matrix::identity(n);
+        let mut U = Matrix::zeros(n, n);
+        let mut P = Matrix::zeros(n, n);
+        for i in 0..n {
+            for j in 0..n {
+                if j < i {
+                    L[(i, j)] = lu[(i, j)].clone();
+                } else {
+                    U[(i, j)] = lu[(i, j)].clone();
+                }
+            }
+            P[(i, permutation[i])] = T::one();
+        }
+        (L, U, P)
+    }
+}
+
+
+/// Solves the linear system `A * X = B` for `X`, where `A` is `self`, using LU decomposition
+/// with partial pivoting followed by forward and backward substitution.
+///
+/// `B` may have any number of columns; each column is solved as a separate right-hand side.
+///
+/// # Panics
+///
+/// This method will panic if `self` is not square, if `B` does not have as many rows as `self`,
+/// or if `self` is singular.
+///
+/// With the `blas` feature, `f32` and `f64` systems are solved with LAPACK's `getrf` and
+/// `getrs`, as are the LU decompositions, `inverse` and `determinant`. `cholesky`,
+/// `QR_decompose` and `symmetric_eigen` use `potrf`, `geqrf` with `orgqr`, and `syev`.
+///
+/// # Examples
+///
+/// ```
+/// let a = Matrix::from_vec(2, 2, vec![2.0, 1.0, 1.0, 3.0]);
+/// let b = Matrix::from_vec(2, 1, vec![3.0, 5.0]);
+/// let x = a.solve(&b);
+/// assert_eq!(x, Matrix::from_vec(2, 1, vec![0.8, 1.4]));
+/// ```
+impl<T> Matrix<T>
+    where
+        T: Field
+{
+    pub fn solve(&self, b: &Matrix<T>) -> Matrix<T> {
+        assert_eq!(self.rows, b.rows, "Right-hand side has {} rows, expected {}", b.rows, self.rows);
+
+        #[cfg(feature = "blas")]
+        if self.rows == self.cols {
+            if let Some(x) = blas::solve(self, b) {
+                return x;
+            }
+        }
+
+        let (lu, permutation) = self.lup_factor();
+        let n = self.rows;
+        for k in 0..n {
+            if lu[(k, k)] == T::zero() {
+                panic!("Cannot solve linear system, matrix is singular.");
+            }
+        }
+
+        let mut x = Matrix::zeros(n, b.cols);
+        for col in 0..b.cols {
+            for i in 0..n {
+                x[(i, col)] = b[(permutation[i], col)].clone();
+            }
+        }
+        // L * y = P * b, then U * x = y
+        lu.forward_substitution(&mut x, true);
+        lu.back_substitution(&mut x);
+        x
+    }
+}
+
+
+/// Solves triangular systems in place, overwriting `x` (with one column per right-hand side) by
+/// the solution. Only the relevant triangle of `self` is read.
+///
+/// - `forward_substitution` solves `L * X = B` for the lower triangle `L` of `self`, with a
+///   unit diagonal if `unit_diagonal` is set (as in the `L` factor of `lup_factor`).
+/// - `back_substitution` solves `U * X = B` for the upper triangle `U` of `self`.
+impl<T> Matrix<T>
+    where
+        T: Field
+{
+    pub(crate) fn forward_substitution(&self, x: &mut Matrix<T>, unit_diagonal: bool) {
+        let n = self.rows;
+        for col in 0..x.cols {
+            for i in 0..n {
+                let mut sum = x[(i, col)].clone();
+                for j in 0..i {
+                    sum = sum - self[(i, j)].clone() * x[(j, col)].clone();
+                }
+                x[(i, col)] = if unit_diagonal { sum } else { sum / self[(i, i)].clone() };
+            }
+        }
+    }
+
+    pub(crate) fn back_substitution(&self, x: &mut Matrix<T>) {
+        let n = self.rows;
+        for col in 0..x.cols {
+            for i in (0..n).rev() {
+                let mut sum = x[(i, col)].clone();
+                for j in (i + 1)..n {
+                    sum = sum - self[(i, j)].clone() * x[(j, col)].clone();
+                }
+                x[(i, col)] = sum / self[(i, i)].clone();
+            }
+        }
+    }
+}
+
+
+/// Computes the inverse of a square matrix by solving `A * X = I`.
+///
+/// # Panics
+///
+/// This method will panic if the matrix `self` is not square or is singular.
+///
+/// # Examples
+///
+/// ```
+/// let a = Matrix::from_vec(2, 2, vec![4.0, 7.0, 2.0, 6.0]);
+/// let a_inv = a.inverse();
+/// // a * a_inv == I
+/// ```
+///
+/// # Notes
+///
+/// When the inverse is only needed to multiply another matrix, `solve` is both cheaper and
+/// more accurate.
+impl<T> Matrix<T>
+    where
+        T: Field
+{
+    pub fn inverse(&self) -> Matrix<T> {
+        self.solve(&Matrix::identity(self.rows))
+    }
+}
+
+
+/// Computes the determinant of a square matrix from its LU decomposition with partial pivoting,
+/// as the product of the diagonal of `U` times the sign of the row permutation.
+///
+/// The result is exact for rational matrices. For matrices of integers, see
+/// `determinant_bareiss`.
+///
+/// # Panics
+///
+/// This method will panic if the matrix `self` is not square.
+///
+/// # Examples
+///
+/// ```
+/// let a = Matrix::from_vec(2, 2, vec![4.0, 7.0, 2.0, 6.0]);
+/// assert!((a.determinant() - 10.0).abs() < 1e-12);
+/// ```
+impl<T> Matrix<T>
+    where
+        T: Field
+{
+    pub fn determinant(&self) -> T {
+        let (lu, mut permutation) = self.lup_factor();
+
+        // Undo the permutation with transpositions, flipping the sign for each
+        let mut det = T::one();
+        for i in 0..self.rows {
+            while permutation[i] != i {
+                let target = permutation[i];
+                permutation.swap(i, target);
+                det = -det;
+            }
+        }
+        for k in 0..self.rows {
+            det = det * lu[(k, k)].clone();
+        }
+        det
+    }
+}
+
+
+/// Gauss-Jordan elimination and the fundamental subspaces of a matrix.
+///
+/// - `rref` returns the reduced row echelon form, treating only exact zeros as zero.
+/// - `rref_with_tolerance` returns the reduced row echelon form, treating candidate pivots with
+///   absolute value at most `tolerance` as zero.
+/// - `pivot_columns` returns the indices of the pivot columns of the reduced row echelon form.
+/// - `rank` returns the number of pivots.
+/// - `null_space` returns a `cols x k` matrix whose columns are a basis of the null space
+///   `{x : A * x = 0}`, in echelon form: the basis vector of each free column has a one in that
+///   position, zeros in the other free positions and minus the reduced column in the pivot
+///   positions.
+/// - `column_space` returns the pivot columns of the original matrix, a basis of its column
+///   space.
+///
+/// Each pivot is the element of largest absolute value in its column (on or below the current
+/// row), every pivot is scaled to one and the rest of its column is eliminated. A column
+/// without a pivot is set to zero below the current row, so entries within the tolerance do
+/// not survive as noise. For rational matrices every result is exact (use a tolerance of zero).
+///
+/// # Notes
+///
+/// For floating point matrices a tolerance of zero is rarely what you want: rounding errors
+/// make almost every column a pivot column. A common choice is
+/// `max(rows, cols) * T::epsilon() * max|a_ij|`.
+///
+/// # Examples
+///
+/// ```
+/// let a = Matrix::from_vec(2, 3, vec![1.0, 2.0, 3.0, 2.0, 4.0, 7.0]);
+/// assert_eq!(a.rref(), Matrix::from_vec(2, 3, vec![1.0, 2.0, 0.0, 0.0, 0.0, 1.0]));
+/// assert_eq!(a.pivot_columns(1e-12), vec![0, 2]);
+/// assert_eq!(a.null_space(1e-12), Matrix::from_vec(3, 1, vec![-2.0, 1.0, 0.0]));
+/// ```
+impl<T> Matrix<T>
+    where
+        T: Field
+{
+    /// Returns the reduced row echelon form and its pivot columns.
+    fn reduce(&self, tolerance: T) -> (Matrix<T>, Vec<usize>) {
+        let mut result = self.clone();
+        let mut pivots = Vec::new();
+        let mut pivot_row = 0;
+        for col in 0..self.cols {
+            if pivot_row == self.rows {
+                break;
+            }
+            let mut pivot = pivot_row;
+            for i in (pivot_row + 1)..self.rows {
+                if result[(i, col)].abs() > result[(pivot, col)].abs() {
+                    pivot = i;
+                }
+            }
+            if result[(pivot, col)].abs() <= tolerance {
+                for i in pivot_row..self.rows {
+                    result[(i, col)] = T::zero();
+                }
+                continue;
+            }
+            result.swap_rows(pivot_row, pivot);
+
+            let pivot_value = result[(pivot_row, col)].clone();
+            for j in col..self.cols {
+                result[(pivot_row, j)] = result[(pivot_row, j)].clone() / pivot_value.clone();
+            }
+            for i in 0..self.rows {
+                let factor = result[(i, col)].clone();
+                if i != pivot_row && factor != T::zero() {
+                    result.add_row_multiple(i, pivot_row, -factor);
+                    result[(i, col)] = T::zero();
+                }
+            }
+            pivots.push(col);
+            pivot_row += 1;
+        }
+        (result, pivots)
+    }
+
+    pub fn rref(&self) -> Matrix<T> {
+        self.reduce(T::zero()).0
+    }
+
+    pub fn rref_with_tolerance(&self, tolerance: T) -> Matrix<T> {
+        self.reduce(tolerance).0
+    }
+
+    pub fn pivot_columns(&self, tolerance: T) -> Vec<usize> {
+        self.reduce(tolerance).1
+    }
+
+    pub fn rank(&self, tolerance: T) -> usize {
+        self.reduce(tolerance).1.len()
+    }
+
+    pub fn null_space(&self, tolerance: T) -> Matrix<T> {
+        let (reduced, pivots) = self.reduce(tolerance);
+        let free: Vec<usize> = (0..self.cols).filter(|col| !pivots.contains(col)).collect();
+
+        let mut basis = Matrix::zeros(self.cols, free.len());
+        for (k, &free_col) in free.iter().enumerate() {
+            basis[(free_col, k)] = T::one();
+            for (row, &pivot_col) in pivots.iter().enumerate() {
+                basis[(pivot_col, k)] = -reduced[(row, free_col)].clone();
+            }
+        }
+        basis
+    }
+
+    pub fn column_space(&self, tolerance: T) -> Matrix<T> {
+        let pivots = self.pivot_columns(tolerance);
+
+        let mut basis = Matrix::zeros(self.rows, pivots.len());
+        for (k, &col) in pivots.iter().enumerate() {
+            for i in 0..self.rows {
+                basis[(i, k)] = self[(i, col)].clone();
+            }
+        }
+        basis
+    }
+}
+
+
+/// Orthonormal bases of the fundamental subspaces, and non-trivial solutions of `A * x = 0`.
+///
+/// - `null_space_orthonormal` returns a `cols x k` matrix with orthonormal columns spanning the
+///   null space.
+/// - `column_space_orthonormal` returns a `rows x r` matrix with orthonormal columns spanning
+///   the column space, where `r` is the rank.
+/// - `solve_homogeneous` returns a unit length solution `x` (`cols x 1`) of `A * x = 0`, or
+///   `None` if only the trivial solution exists (the columns are linearly independent).
+///
+/// The bases are computed by orthonormalizing the echelon bases of `null_space` and
+/// `column_space` with a QR decomposition, so `tolerance` has the same meaning as there.
+///
+/// # Examples
+///
+/// ```
+/// let a = Matrix::from_vec(2, 3, vec![1.0, 1.0, 0.0, 0.0, 0.0, 1.0]);
+/// let x = a.solve_homogeneous(1e-12).unwrap();
+/// // x = ±(1, -1, 0) / sqrt(2)
+/// ```
+impl<T> Matrix<T>
+    where
+        T: Real
+{
+    /// Returns the first `basis.cols` columns of `Q` in the QR decomposition of `basis`.
+    fn orthonormalize(basis: &Matrix<T>) -> Matrix<T> {
+        let (q, _) = basis.QR_decompose();
+        let mut result = Matrix::zeros(basis.rows, basis.cols);
+        for i in 0..basis.rows {
+            for j in 0..basis.cols {
+                result[(i, j)] = q[(i, j)];
+            }
+        }
+        result
+    }
+
+    pub fn null_space_orthonormal(&self, tolerance: T) -> Matrix<T> {
+        Self::orthonormalize(&self.null_space(tolerance))
+    }
+
+    pub fn column_space_orthonormal(&self, tolerance: T) -> Matrix<T> {
+        Self::orthonormalize(&self.column_space(tolerance))
+    }
+
+    pub fn solve_homogeneous(&self, tolerance: T) -> Option<Matrix<T>> {
+        let basis = self.null_space(tolerance);
+        if basis.cols == 0 {
+            return None;
+        }
+
+        let mut x = Matrix::zeros(self.cols, 1);
+        let mut norm = T::zero();
+        for i in 0..self.cols {
+            x[(i, 0)] = basis[(i, 0)];
+            norm = norm + basis[(i, 0)] * basis[(i, 0)];
+        }
+        Some(x.scale(T::one() / norm.sqrt()))
+    }
+}
+
+
+/// Performs QR decomposition on the input matrix `self` using Householder reflections and
+/// returns a tuple `(Q, R)` of an orthogonal `rows x rows` matrix `Q` and an upper triangular
+/// (upper trapezoidal if not square) `rows x cols` matrix `R`, such that `A = Q * R`.
+///
+/// # Examples
+///
+/// ```
+/// let mat = Matrix::from_vec(3, 2, vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+/// let (Q, R) = mat.QR_decompose();
+/// // Q * R == mat, Q^T * Q == I
+/// ```
+///
+/// # Notes
+///
+/// Column `k` is reflected onto `±||x|| e_k`, with the sign chosen opposite to the diagonal
+/// element to avoid cancellation. The reflections are accumulated into `Q` explicitly. The
+/// decomposition is backward stable and also works for rank deficient matrices. With the `blas`
+/// feature, the factors are computed with LAPACK's `geqrf` and `orgqr`, which agree with the
+/// pure-Rust ones up to rounding (and the signs of columns that are already reduced).
+impl<T> Matrix<T>
+    where
+        T: Real
+{
+    #[allow(non_snake_case)]
+    pub fn QR_decompose(&self) -> (Matrix<T>, Matrix<T>) {
+        #[cfg(feature = "blas")]
+        if let Some(factors) = blas::qr(self) {
+            return factors;
+        }
+
+        let (m, n) = (self.rows, self.cols);
+        let two = T::one() + T::one();
+        let mut R = self.clone();
+        let mut Q = Matrix::identity(m);
+
+        for k in 0..n.min(m.saturating_sub(1)) {
+            // Householder vector for column k below the diagonal
+            let mut v: Vec<T> = (k..m).map(|i| R[(i, k)]).collect();
+            let norm_x = v.iter().fold(T::zero(), |sum, &x| sum + x * x).sqrt();
+            if norm_x == T::zero() {
+                continue;
+            }
+            let alpha = if R[(k, k)] > T::zero() { -norm_x } else { norm_x };
+            v[0] = v[0] - alpha;
+            let v_norm_squared = v.iter().fold(T::zero(), |sum, &x| sum + x * x);
+            if v_norm_squared == T::zero() {
+                continue;
+            }
+
+            // R = H * R
+            for j in k..n {
+                let dot = v.iter().enumerate().fold(T::zero(), |sum, (l, &vl)| sum + vl * R[(k + l, j)]);
+                let factor = two * dot / v_norm_squared;
+                for (l, &vl) in v.iter().enumerate() {
+                    R[(k + l, j)] = R[(k + l, j)] - factor * vl;
+                }
+            }
+            // Q = Q * H
+            for i in 0..m {
+                let dot = v.iter().enumerate().fold(T::zero(), |sum, (l, &vl)| sum + Q[(i, k + l)] * vl);
+                let factor = two * dot / v_norm_squared;
+                for (l, &vl) in v.iter().enumerate() {
+                    Q[(i, k + l)] = Q[(i, k + l)] - factor * vl;
+                }
+            }
+            for i in (k + 1)..m {
+                R[(i, k)] = T::zero();
+            }
+        }
+        (Q, R)
+    }
+}
+
+
+/// Performs Cholesky decomposition on a symmetric positive definite matrix `self` and returns
+/// the lower triangular matrix `L` with positive diagonal, such that `A = L * L^T`.
+///
+/// # Panics
+///
+/// This method will panic if the matrix `self` is not square or not positive definite.
+///
+/// # Examples
+///
+/// ```
+/// let mat = Matrix::from_vec(2, 2, vec![4.0, 2.0, 2.0, 3.0]);
+/// let L = mat.cholesky();
+/// assert_eq!(L, Matrix::from_vec(2, 2, vec![2.0, 0.0, 1.0, 2.0_f64.sqrt()]));
+/// ```
+///
+/// # Notes
+///
+/// Only the lower triangle of `self` is read. With the `blas` feature, the factorization is
+/// computed with LAPACK's `potrf`.
+impl<T> Matrix<T>
+    where
+        T: Real
+{
+    pub fn cholesky(&self) -> Matrix<T> {
+        // Has to be square matrix
+        assert_eq!(self.rows, self.cols);
+
+        #[cfg(feature = "blas")]
+        if let Some(l) = blas::cholesky(self) {
+            return l;
+        }
+
+        let n = self.rows;
+        let mut l = Matrix::zeros(n, n);
+        for j in 0..n {
+            let mut diagonal = self[(j, j)];
+            for k in 0..j {
+                diagonal = diagonal - l[(j, k)] * l[(j, k)];
+            }
+            if diagonal <= T::zero() || diagonal.is_nan() {
+                panic!("Cannot compute Cholesky decomposition, matrix is not positive definite.");
+            }
+            l[(j, j)] = diagonal.sqrt();
+
+            for i in (j + 1)..n {
+                let mut sum = self[(i, j)];
+                for k in 0..j {
+                    sum = sum - l[(i, k)] * l[(j, k)];
+                }
+                l[(i, j)] = sum / l[(j, j)];
+            }
+        }
+        l
+    }
+}
+
+
+/// Computes the eigenvalues and eigenvectors of a real symmetric matrix using the cyclic Jacobi
+/// eigenvalue algorithm.
+///
+/// Returns a tuple `(eigenvalues, eigenvectors)`, where `eigenvalues` is sorted in descending
+/// order and column `i` of `eigenvectors` is the unit eigenvector belonging to `eigenvalues[i]`.
+/// The eigenvectors are mutually orthogonal, so `A = V * diag(eigenvalues) * V^T`.
+///
+/// # Panics
+///
+/// This method will panic if the matrix `self` is not square, or with the `blas` feature if
+/// `syev` fails to converge (e.g. for non-finite elements).
+///
+/// # Examples
+///
+/// ```
+/// let mat = Matrix::from_vec(2, 2, vec![2.0, 1.0, 1.0, 2.0]);
+/// let (values, vectors) = mat.symmetric_eigen();
+/// // values ≈ [3.0, 1.0], vectors ≈ [[0.707, -0.707], [0.707, 0.707]]
+/// ```
+///
+/// # Notes
+///
+/// Only the symmetric part of `self` is meaningful; the result for a non-symmetric input is
+/// unspecified. Each sweep applies a Givens rotation to every off-diagonal pair, and iteration
+/// stops once the off-diagonal mass is negligible relative to the Frobenius norm of the matrix.
+/// With the `blas` feature, the decomposition is computed with LAPACK's `syev`, which only reads
+/// the lower triangle and whose eigenvectors may differ in sign from the pure-Rust ones.
+impl<T> Matrix<T>
+    where
+        T: Real
+{
+    pub fn symmetric_eigen(&self) -> (Vec<T>, Matrix<T>) {
+        // Has to be square matrix
+        assert_eq!(self.rows, self.cols);
+
+        #[cfg(feature = "blas")]
+        if let Some(decomposition) = blas::symmetric_eigen(self) {
+            return decomposition;
+        }
+
+        const MAX_SWEEPS: usize = 100;
+        let n = self.rows;
+        let mut a = self.clone();
+        let mut v = Matrix::identity(n);
+
+        let frobenius_squared = a.data.iter().fold(T::zero(), |acc, &x| acc + x * x);
+        let tolerance = T::epsilon() * T::epsilon() * frobenius_squared;
+
+        for _ in 0..MAX_SWEEPS {
+            let mut off_diagonal = T::zero();
+            for p in 0..n {
+                for q in (p + 1)..n {
+                    off_diagonal = off_diagonal + a[(p, q)] * a[(p, q)];
+                }
+            }
+            if off_diagonal <= tolerance {
+                break;
+            }
+
+            for p in 0..n {
+                for q in (p + 1)..n {
+                    if a[(p, q)] == T::zero() {
+                        continue;
+                    }
+                    // Rotation angle that annihilates a(p, q)
+                    let two = T::one() + T::one();
+                    let theta = (a[(q, q)] - a[(p, p)]) / (two * a[(p, q)]);
+                    let t = theta.signum() / (theta.abs() + (theta * theta + T::one()).sqrt());
+                    let c = T::one() / (t * t + T::one()).sqrt();
+                    let s = t * c;
+
+                    for k in 0..n {
+                        let (akp, akq) = (a[(k, p)], a[(k, q)]);
+                        a[(k, p)] = c * akp - s * akq;
+                        a[(k, q)] = s * akp + c * akq;
+                    }
+                    for k in 0..n {
+                        let (apk, aqk) = (a[(p, k)], a[(q, k)]);
+                        a[(p, k)] = c * apk - s * aqk;
+                        a[(q, k)] = s * apk + c * aqk;
+                    }
+                    for k in 0..n {
+                        let (vkp, vkq) = (v[(k, p)], v[(k, q)]);
+                        v[(k, p)] = c * vkp - s * vkq;
+                        v[(k, q)] = s * vkp + c * vkq;
+                    }
+                }
+            }
+        }
+
+        // Sort eigenpairs by descending eigenvalue
+        let mut order: Vec<usize> = (0..n).collect();
+        order.sort_by(|&i, &j| a[(j, j)].partial_cmp(&a[(i, i)]).unwrap_or(std::cmp::Ordering::Equal));
+
+        let eigenvalues = order.iter().map(|&i| a[(i, i)]).collect();
+        let mut eigenvectors = Matrix::zeros(n, n);
+        for (new_col, &old_col) in order.iter().enumerate() {
+            for row in 0..n {
+                eigenvectors[(row, new_col)] = v[(row, old_col)];
+            }
+        }
+        (eigenvalues, eigenvectors)
+    }
+}
+
+/// Returns the number of elements of a matrix that is used as a vector, i.e. a `1 x n` row
+/// vector (as returned by `get_row`) or an `n x 1` column vector (as returned by `get_col`).
+///
+/// # Panics
+///
+/// This method will panic if the matrix has more than one row and more than one column.
+impl<T> Matrix<T>
+    where
+        T: Scalar
+{
+    fn vector_len(&self) -> usize {
+        if self.rows == 1 || self.cols == 1 {
+            self.data.len()
+        } else {
+            panic!(
+                "Expected a row or column vector, got matrix of dim {} x {}",
+                self.rows, self.cols
+            );
+        }
+    }
+}
+
+
+/// Computes the dot (inner), outer and cross products of vectors.
+///
+/// Row vectors (`1 x n`) and column vectors (`n x 1`) are both accepted and may be mixed, so
+/// e.g. `m.get_row(0).dot(&m.get_col(0))` works directly.
+///
+/// - `dot` returns the scalar `sum_i a_i * b_i`.
+/// - `outer` returns the `n x m` matrix with entries `a_i * b_j`.
+/// - `cross` returns the 3-vector cross product `a x b`, with the same shape as `self`.
+///
+/// # Panics
+///
+/// These methods will panic if either operand is not a vector. `dot` panics if the vectors
+/// have different lengths, and `cross` panics unless both vectors have exactly three elements.
+///
+/// # Examples
+///
+/// ```
+/// let a = Matrix::from_vec(1, 3, vec![1, 2, 3]);
+/// let b = Matrix::from_vec(3, 1, vec![4, 5, 6]);
+/// assert_eq!(a.dot(&b), 32);
+/// assert_eq!(a.cross(&b), Matrix::from_vec(1, 3, vec![-3, 6, -3]));
+/// assert_eq!(a.outer(&b).get_row(0), Matrix::from_vec(1, 3, vec![4, 5, 6]));
+/// ```
+impl<T> Matrix<T>
+    where
+        T: Scalar +
+        'static
+{
+    pub fn dot(&self, other: &Matrix<T>) -> T {
+        assert_eq!(self.vector_len(), other.vector_len(), "Vectors must have the same length");
+
+        simd::dot(&self.data, &other.data)
+    }
+
+    pub fn outer(&self, other: &Matrix<T>) -> Matrix<T> {
+        let rows = self.vector_len();
+        let cols = other.vector_len();
+
+        let mut data = Vec::with_capacity(rows * cols);
+        for &a in self.data.iter() {
+            for &b in other.data.iter() {
+                data.push(a * b);
+            }
+        }
+        Matrix { rows, cols, data, layout: Layout::RowMajor }
+    }
+
+    pub fn cross(&self, other: &Matrix<T>) -> Matrix<T> {
+        assert_eq!(self.vector_len(), 3, "Cross product is only defined for 3-vectors");
+        assert_eq!(other.vector_len(), 3, "Cross product is only defined for 3-vectors");
+
+        let (a, b) = (&self.data, &other.data);
+        let data = vec![
+            a[1] * b[2] - a[2] * b[1],
+            a[2] * b[0] - a[0] * b[2],
+            a[0] * b[1] - a[1] * b[0],
+        ];
+        Matrix { rows: self.rows, cols: self.cols, data, layout: Layout::RowMajor }
+    }
+}
+
+
+/// Computes the Kronecker (tensor) product `A ⊗ B` of two matrices.
+///
+/// For an `m x n` matrix `A` and a `p x q` matrix `B` the result is the `mp x nq` block matrix
+/// whose `(i, j)` block is `A[(i, j)] * B`.
+///
+/// # Examples
+///
+/// ```
+/// let x = Matrix::from_vec(2, 2, vec![0, 1, 1, 0]);
+/// let i = Matrix::identity(2);
+/// let xi = x.kron(&i); // 4 x 4 operator acting on the first qubit
+/// assert_eq!(xi[(0, 2)], 1);
+/// ```
+impl<T> Matrix<T>
+    where
+        T: Scalar
+{
+    pub fn kron(&self, other: &Matrix<T>) -> Matrix<T> {
+        let mut result = Matrix::zeros(self.rows * other.rows, self.cols * other.cols);
+
+        for i in 0..self.rows {
+            for j in 0..self.cols {
+                let a = self[(i, j)];
+                for k in 0..other.rows {
+                    for l in 0..other.cols {
+                        result[(i * other.rows + k, j * other.cols + l)] = a * other[(k, l)];
+                    }
+                }
+            }
+        }
+        result
+    }
+}
+
+
+/// Extracts and builds diagonals, and computes the trace of a square matrix.
+///
+/// - `trace` returns the sum of the diagonal elements.
+/// - `diag` returns the main diagonal as a `min(rows, cols) x 1` column vector.
+/// - `from_diag` builds a square matrix with the elements of a row or column vector on the main
+///   diagonal and zeros elsewhere, so `Matrix::from_diag(&m.diag())` keeps only the diagonal of
+///   a square matrix `m`.
+///
+/// # Panics
+///
+/// `trace` will panic if the matrix is not square, and `from_diag` will panic if its argument
+/// is not a vector.
+///
+/// # Examples
+///
+/// ```
+/// let m = Matrix::from_vec(2, 2, vec![1, 2, 3, 4]);
+/// assert_eq!(m.trace(), 5);
+/// assert_eq!(m.diag(), Matrix::from_vec(2, 1, vec![1, 4]));
+/// assert_eq!(Matrix::from_diag(&m.diag()), Matrix::from_vec(2, 2, vec![1, 0, 0, 4]));
+/// ```
+impl<T> Matrix<T>
+    where
+        T: Scalar
+{
+    pub fn trace(&self) -> T {
+        // Has to be square matrix
+        assert_eq!(self.rows, self.cols);
+
+        (0..self.rows).fold(T::zero(), |sum, i| sum + self[(i, i)])
+    }
+
+    pub fn diag(&self) -> Matrix<T> {
+        let size = self.rows.min(self.cols);
+        let data = (0..size).map(|i| self[(i, i)]).collect();
+        Matrix { rows: size, cols: 1_usize, data, layout: Layout::RowMajor }
+    }
+
+    pub fn from_diag(values: &Matrix<T>) -> Matrix<T> {
+        let size = values.vector_len();
+        let mut result = Matrix::zeros(size, size);
+        for (i, &val) in values.data.iter().enumerate() {
+            result[(i, i)] = val;
+        }
+        result
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+////////////////////////////////////// STATISTICS METHODS /////////////////////////////////////////
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+
+/// Applies a slice reduction along one axis of the matrix.
+///
+/// Axis `0` reduces each column and returns a `1 x cols` row vector. Axis `1` reduces each row
+/// and returns a `rows x 1` column vector. Lines that are contiguous in the data buffer (the rows
+/// of a row-major matrix, the columns of a column-major one) are passed to `reduce` directly,
+/// the others are first copied into a buffer.
+///
+/// # Panics
+///
+/// This method will panic if `axis` is neither `0` nor `1`.
+impl<T> Matrix<T>
+    where
+        T: Scalar
+{
+    fn reduce_axis<U, F>(&self, axis: usize, reduce: F) -> Matrix<U>
+        where
+            U: Scalar,
+            F: Fn(&[T]) -> U
+    {
+        let (row_stride, col_stride) = self.strides();
+        let mut line = Vec::new();
+        let mut reduce_line = |start: usize, stride: usize, len: usize| {
+            if stride == 1 {
+                reduce(&self.data[start..start + len])
+            } else {
+                line.clear();
+                line.extend(self.data.iter().skip(start).step_by(stride.max(1)).take(len));
+                reduce(&line)
+            }
+        };
+        match axis {
+            0 => {
+                let data = (0..self.cols).map(|col| reduce_line(self.offset(0, col), row_stride, self.rows)).collect();
+                Matrix { rows: 1_usize, cols: self.cols, data, layout: Layout::RowMajor }
+            }
+            1 => {
+                let data = (0..self.rows).map(|row| reduce_line(self.offset(row, 0), col_stride, self.cols)).collect();
+                Matrix { rows: self.rows, cols: 1_usize, data, layout: Layout::RowMajor }
+            }
+            _ => panic!("Axis: {} out of bounds, expected 0 (columns) or 1 (rows)", axis),
+        }
+    }
+}
+
+
+/// Sums, multiplies and finds the extreme values of the matrix elements, either over the whole
+/// matrix or along an axis.
+///
+/// The methods without a suffix reduce every element of the matrix to a single value. The
+/// `_axis` variants reduce along axis `0` (down each column, giving a `1 x cols` row vector) or
+/// axis `1` (across each row, giving a `rows x 1` column vector), mirroring NumPy.
+///
+/// # Examples
+///
+/// ```
+/// let mat = Matrix::from_vec(2, 3, vec![1, 2, 3, 4, 5, 6]);
+/// assert_eq!(mat.sum(), 21);
+/// assert_eq!(mat.sum_axis(0), Matrix::from_vec(1, 3, vec![5, 7, 9]));
+/// assert_eq!(mat.max_axis(1), Matrix::from_vec(2, 1, vec![3, 6]));
+/// assert_eq!(mat.arg_max(), (1, 2));
+/// ```
+///
+/// # Panics
+///
+/// The extreme value methods (`max`, `min`, `arg_max`, `arg_min` and their `_axis` variants)
+/// panic if the reduced slice is empty. The `_axis` variants panic if `axis` is neither `0`
+/// nor `1`.
+impl<T> Matrix<T>
+    where
+        T: Scalar +
+        std::cmp::PartialOrd +
+        'static
+{
+    /// Returns the sum of all elements.
+    pub fn sum(&self) -> T {
+        simd::sum(&self.data)
+    }
+
+    /// Returns the sums along `axis`.
+    pub fn sum_axis(&self, axis: usize) -> Matrix<T> {
+        self.reduce_axis(axis, simd::sum)
+    }
+
+    /// Returns the product of all elements.
+    pub fn product(&self) -> T {
+        MyMathLib::product(&self.data)
+    }
+
+    /// Returns the products along `axis`.
+    pub fn product_axis(&self, axis: usize) -> Matrix<T> {
+        self.reduce_axis(axis, MyMathLib::product)
+    }
+
+    /// Returns the largest element.
+    pub fn max(&self) -> T {
+        MyMathLib::max(&self.data)
+    }
+
+    /// Returns the largest elements along `axis`.
+    pub fn max_axis(&self, axis: usize) -> Matrix<T> {
+        self.reduce_axis(axis, MyMathLib::max)
+    }
+
+    /// Returns the smallest element.
+    pub fn min(&self) -> T {
+        MyMathLib::min(&self.data)
+    }
+
+    /// Returns the smallest elements along `axis`.
+    pub fn min_axis(&self, axis: usize) -> Matrix<T> {
+        self.reduce_axis(axis, MyMathLib::min)
+    }
+
+    /// Returns the `(row, col)` index of the first occurrence of the largest element.
+    pub fn arg_max(&self) -> (usize, usize) {
+        let idx = MyMathLib::arg_max(&self.row_major());
+        (idx / self.cols, idx % self.cols)
+    }
+
+    /// Returns the index of the largest element within each column (axis `0`) or row (axis `1`).
+    pub fn arg_max_axis(&self, axis: usize) -> Matrix<usize> {
+        self.reduce_axis(axis, MyMathLib::arg_max)
+    }
+
+    /// Returns the `(row, col)` index of the first occurrence of the smallest element.
+    pub fn arg_min(&self) -> (usize, usize) {
+        let idx = MyMathLib::arg_min(&self.row_major());
+        (idx / self.cols, idx % self.cols)
+    }
+
+    /// Returns the index of the smallest element within each column (axis `0`) or row (axis `1`).
+    pub fn arg_min_axis(&self, axis: usize) -> Matrix<usize> {
+        self.reduce_axis(axis, MyMathLib::arg_min)
+    }
+}
+
+
+/// Finds the elements of largest absolute value, over the whole matrix or along an axis (see
+/// `sum_axis` for the axis convention).
+///
+/// `abs_max` and `abs_max_axis` return the largest absolute values, and `arg_abs_max` and
+/// `arg_abs_max_axis` the indices of the first elements attaining them. `abs_max` is defined for
+/// floating point elements and the `arg_` methods for signed elements.
+///
+/// # Examples
+///
+/// ```
+/// let mat = Matrix::from_vec(2, 3, vec![3.0, -7.0, 4.0, 1.0, -5.0, 9.0]);
+/// assert_eq!(mat.abs_max(), 9.0);
+/// assert_eq!(mat.abs_max_axis(1), Matrix::from_vec(2, 1, vec![7.0, 9.0]));
+/// assert_eq!(mat.arg_abs_max(), (1, 2));
+/// assert_eq!(mat.arg_abs_max_axis(0), Matrix::from_vec(1, 3, vec![0, 0, 1]));
+/// ```
+///
+/// # Panics
+///
+/// The `_axis` variants panic if `axis` is neither `0` nor `1`.
+impl<T> Matrix<T>
+    where
+        T: Real
+{
+    /// Returns the largest absolute value of the elements, or zero for an empty matrix.
+    pub fn abs_max(&self) -> T {
+        MyMathLib::abs_max(&self.data)
+    }
+
+    /// Returns the largest absolute values along `axis`.
+    pub fn abs_max_axis(&self, axis: usize) -> Matrix<T> {
+        self.reduce_axis(axis, MyMathLib::abs_max)
+    }
+}
+
+impl<T> Matrix<T>
+    where
+        T: Scalar +
+        num_traits::Signed +
+        std::cmp::PartialOrd
+{
+    /// Returns the `(row, col)` index of the first element of largest absolute value.
+    pub fn arg_abs_max(&self) -> (usize, usize) {
+        let idx = MyMathLib::arg_abs_max(&self.row_major());
+        (idx / self.cols, idx % self.cols)
+    }
+
+    /// Returns the index of the element of largest absolute value within each column (axis `0`)
+    /// or row (axis `1`).
+    pub fn arg_abs_max_axis(&self, axis: usize) -> Matrix<usize> {
+        self.reduce_axis(axis, MyMathLib::arg_abs_max)
+    }
+}
+
+
+/// Computes the arithmetic mean, the population variance and the population standard deviation
+/// of the matrix elements, either over the whole matrix or along an axis.
+///
+/// The variance divides by the number of elements `n` (not `n - 1`), i.e. it is the second
+/// central moment of the elements. The `_axis` variants follow the same axis convention as
+/// `sum_axis`.
+///
+/// # Examples
+///
+/// ```
+/// let mat = Matrix::from_vec(2, 2, vec![1.0, 2.0, 3.0, 4.0]);
+/// assert_eq!(mat.mean(), 2.5);
+/// assert_eq!(mat.variance(), 1.25);
+/// assert_eq!(mat.mean_axis(0), Matrix::from_vec(1, 2, vec![2.0, 3.0]));
+/// ```
+///
+/// # Notes
+///
+/// These methods are only available for floating point element types. The mean of an empty
+/// matrix is `NaN`.
+impl<T> Matrix<T>
+    where
+        T: Real
+{
+    fn slice_mean(slice: &[T]) -> T {
+        simd::sum(slice) / T::from(slice.len()).unwrap()
+    }
+
+    fn slice_variance(slice: &[T]) -> T {
+        let mean = Self::slice_mean(slice);
+        let squared_deviations = slice.iter().fold(T::zero(), |a, &b| a + (b - mean) * (b - mean));
+        squared_deviations / T::from(slice.len()).unwrap()
+    }
+
+    /// Returns the mean of all elements.
+    pub fn mean(&self) -> T {
+        Self::slice_mean(&self.data)
+    }
+
+    /// Returns the means along `axis`.
+    pub fn mean_axis(&self, axis: usize) -> Matrix<T> {
+        self.reduce_axis(axis, Self::slice_mean)
+    }
+
+    /// Returns the population variance of all elements.
+    pub fn variance(&self) -> T {
+        Self::slice_variance(&self.data)
+    }
+
+    /// Returns the population variances along `axis`.
+    pub fn variance_axis(&self, axis: usize) -> Matrix<T> {
+        self.reduce_axis(axis, Self::slice_variance)
+    }
+
+    /// Returns the population standard deviation of all elements.
+    pub fn std(&self) -> T {
+        self.variance().sqrt()
+    }
+
+    /// Returns the population standard deviations along `axis`.
+    pub fn std_axis(&self, axis: usize) -> Matrix<T> {
+        self.reduce_axis(axis, |slice| Self::slice_variance(slice).sqrt())
+    }
+}
+
+
+/// Computes the sample covariance and Pearson correlation matrices of a data matrix whose rows
+/// are observations and whose columns are variables.
+///
+/// The covariance matrix is `Xc^T * Xc / (n - 1)`, where `Xc` is the data matrix with each
+/// column centred on its mean and `n` is the number of observations. The correlation matrix
+/// normalises each covariance by the standard deviations of the two variables involved, so its
+/// diagonal is one and every entry lies in `[-1, 1]`.
+///
+/// # Examples
+///
+/// ```
+/// let data = Matrix::from_vec(3, 2, vec![1.0, 2.0, 2.0, 4.0, 3.0, 6.0]);
+/// assert_eq!(data.covariance(), Matrix::from_vec(2, 2, vec![1.0, 2.0, 2.0, 4.0]));
+/// assert_eq!(data.correlation(), Matrix::from_vec(2, 2, vec![1.0, 1.0, 1.0, 1.0]));
+/// ```
+///
+/// # Panics
+///
+/// These methods will panic if the data matrix has fewer than two rows.
+///
+/// # Notes
+///
+/// The correlation of a constant column with any other column is undefined and comes out
+/// as `NaN`.
+impl<T> Matrix<T>
+    where
+        T: Real +
+        std::fmt::Display
+{
+    /// Returns a copy of the matrix with the column means subtracted from every row, together
+    /// with those means as a `1 x cols` row vector.
+    fn center_columns(&self) -> (Matrix<T>, Matrix<T>) {
+        let means = self.mean_axis(0);
+        let mut centered = self.clone();
+        for i in 0..self.rows {
+            for j in 0..self.cols {
+                centered[(i, j)] = self[(i, j)] - means[(0, j)];
+            }
+        }
+        (centered, means)
+    }
+
+    pub fn covariance(&self) -> Matrix<T> {
+        assert!(self.rows >= 2, "Covariance requires at least two observations (rows), got {}", self.rows);
+
+        let (centered, _) = self.center_columns();
+        let mut covariance = centered.transpose() * centered;
+        let degrees_of_freedom = T::from(self.rows - 1).unwrap();
+        for val in covariance.data.iter_mut() {
+            *val = *val / degrees_of_freedom;
+        }
+        covariance
+    }
+
+    pub fn correlation(&self) -> Matrix<T> {
+        let covariance = self.covariance();
+        let mut correlation = covariance.clone();
+        for i in 0..self.cols {
+            for j in 0..self.cols {
+                correlation[(i, j)] = covariance[(i, j)] / (covariance[(i, i)] * covariance[(j, j)]).sqrt();
+            }
+        }
+        correlation
+    }
+}
+
+
+/// The result of a principal component analysis, as returned by `Matrix::pca`.
+///
+/// # Fields
+///
+/// - `components`: A `features x k` matrix whose columns are the principal axes, ordered by
+///   decreasing explained variance.
+/// - `explained_variance`: The variance of the data along each principal axis, i.e. the
+///   eigenvalues of the covariance matrix belonging to `components`.
+/// - `explained_variance_ratio`: The fraction of the total variance captured by each axis.
+/// - `mean`: The `1 x features` column means of the data the analysis was fitted on.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Pca<T>
+    where
+        T: Scalar
+{
+    pub components: Matrix<T>,
+    pub explained_variance: Vec<T>,
+    pub explained_variance_ratio: Vec<T>,
+    pub mean: Matrix<T>,
+}
+
+
+/// Projects data onto the principal axes found by `Matrix::pca`.
+///
+/// The data is centred with the means of the fitted data before projecting, so projecting the
+/// fitted data itself gives the principal component scores.
+///
+/// # Panics
+///
+/// This method will panic if `data` does not have the same number of columns as the data the
+/// analysis was fitted on.
+impl<T> Pca<T>
+    where
+        T: Real +
+        std::fmt::Display
+{
+    pub fn project(&self, data: &Matrix<T>) -> Matrix<T> {
+        assert_eq!(data.cols, self.mean.cols, "Data has {} features, but PCA was fitted on {}", data.cols, self.mean.cols);
+
+        let mut centered = data.clone();
+        for i in 0..data.rows {
+            for j in 0..data.cols {
+                centered[(i, j)] = data[(i, j)] - self.mean[(0, j)];
+            }
+        }
+        centered * self.components.clone()
+    }
+}
+
+
+/// Performs a principal component analysis of a data matrix whose rows are observations and
+/// whose columns are variables, keeping the `k` leading components.
+///
+/// The principal axes are the eigenvectors of the sample covariance matrix (see
+/// `Matrix::covariance`), computed with `Matrix::symmetric_eigen`.
+///
+/// # Examples
+///
+/// ```
+/// let data = Matrix::from_vec(4, 2, vec![1.0, 1.1, 2.0, 1.9, 3.0, 3.2, 4.0, 3.9]);
+/// let pca = data.pca(1);
+/// let scores = pca.project(&data); // 4 x 1
+/// assert!(pca.explained_variance_ratio[0] > 0.99);
+/// ```
+///
+/// # Panics
+///
+/// This method will panic if `k` is larger than the number of columns, or if the data matrix
+/// has fewer than two rows.
+impl<T> Matrix<T>
+    where
+        T: Real +
+        std::fmt::Display
+{
+    pub fn pca(&self, k: usize) -> Pca<T> {
+        assert!(k <= self.cols, "Cannot keep {} components of data with {} features", k, self.cols);
+
+        let (eigenvalues, eigenvectors) = self.covariance().symmetric_eigen();
+        let total_variance = MyMathLib::sum(&eigenvalues);
+
+        let mut components = Matrix::zeros(self.cols, k);
+        for i in 0..self.cols {
+            for j in 0..k {
+                components[(i, j)] = eigenvectors[(i, j)];
+            }
+        }
+        let explained_variance: Vec<T> = eigenvalues[..k].to_vec();
+        let explained_variance_ratio = explained_variance.iter().map(|&x| x / total_variance).collect();
+
+        Pca { components, explained_variance, explained_variance_ratio, mean: self.mean_axis(0) }
+    }
+}
+
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+////////////////////////////////////// MATRIX FUNCTIONS ///////////////////////////////////////////
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+
+/// Raises a square matrix to a non-negative integer power using repeated squaring.
+///
+/// Only `O(log n)` matrix multiplications are performed. `pow(0)` returns the identity matrix.
+///
+/// # Panics
+///
+/// This method will panic if the matrix `self` is not square.
+///
+/// # Examples
+///
+/// ```
+/// let fib = Matrix::from_vec(2, 2, vec![1, 1, 1, 0]);
+/// assert_eq!(fib.pow(10), Matrix::from_vec(2, 2, vec![89, 55, 55, 34]));
+/// ```
+impl<T> Matrix<T>
+    where
+        T: Scalar +
+        std::fmt::Display +
+        'static
+{
+    pub fn pow(&self, n: u32) -> Matrix<T> {
+        // Has to be square matrix
+        assert_eq!(self.rows, self.cols);
+
+        let mut result = Matrix::identity(self.rows);
+        let mut base = self.clone();
+        let mut exponent = n;
+        while exponent > 0 {
+            if exponent & 1 == 1 {
+                result = result * base.clone();
+            }
+            exponent >>= 1;
+            if exponent > 0 {
+                base = base.clone() * base;
+            }
+        }
+        result
+    }
+}
+
+
+/// Computes the matrix exponential, principal square root and principal logarithm of a square
+/// floating point matrix.
+///
+/// - `expm` uses the scaling-and-squaring method with a degree 13 Padé approximant (Higham,
+///   "The Scaling and Squaring Method for the Matrix Exponential Revisited", 2005). The matrix
+///   is scaled by `2^-s` until its 1-norm is below `θ13 ≈ 5.37`, the approximant is evaluated
+///   by solving a linear system, and the result is squared `s` times.
+/// - `sqrtm` uses the Denman-Beavers iteration, which converges quadratically to the principal
+///   square root `X` (with `X * X = A`, eigenvalues in the right half-plane).
+/// - `logm` uses inverse scaling and squaring: square roots are taken until the matrix is close
+///   to the identity, the logarithm is evaluated with the series
+///   `log(X) = 2 * sum_k Z^(2k+1) / (2k+1)` for `Z = (X - I)(X + I)^-1`, and the result is
+///   scaled back by `2^s`.
+///
+/// # Panics
+///
+/// These methods will panic if the matrix `self` is not square. `sqrtm` and `logm` will panic
+/// if an intermediate matrix is singular, which happens for singular input.
+///
+/// # Examples
+///
+/// ```
+/// let generator = Matrix::from_vec(2, 2, vec![0.0, 1.0, 0.0, 0.0]);
+/// assert_eq!(generator.expm(), Matrix::from_vec(2, 2, vec![1.0, 1.0, 0.0, 1.0]));
+///
+/// let a = Matrix::from_vec(2, 2, vec![4.0, 0.0, 0.0, 9.0]);
+/// // a.sqrtm() ≈ [[2, 0], [0, 3]], a.logm() ≈ [[ln 4, 0], [0, ln 9]]
+/// ```
+///
+/// # Notes
+///
+/// The principal square root and logarithm only exist when no eigenvalue lies on the closed
+/// negative real axis. For such input the iterations do not converge and the returned matrix
+/// is meaningless.
+impl<T> Matrix<T>
+    where
+        T: Real +
+        std::fmt::Display
+{
+    /// Returns the maximum absolute column sum of the matrix.
+    fn one_norm(&self) -> T {
+        let mut norm = T::zero();
+        for j in 0..self.cols {
+            let mut column_sum = T::zero();
+            for i in 0..self.rows {
+                column_sum = column_sum + self[(i, j)].abs();
+            }
+            norm = norm.max(column_sum);
+        }
+        norm
+    }
+
+    pub fn expm(&self) -> Matrix<T> {
+        // Has to be square matrix
+        assert_eq!(self.rows, self.cols);
+
+        const THETA_13: f64 = 5.371920351148152;
+        const PADE_13: [f64; 14] = [
+            64764752532480000.0, 32382376266240000.0, 7771770303897600.0, 1187353796428800.0,
+            129060195264000.0, 10559470521600.0, 670442572800.0, 33522128640.0, 1323241920.0,
+            40840800.0, 960960.0, 16380.0, 182.0, 1.0,
+        ];
+        let b: Vec<T> = PADE_13.iter().map(|&x| T::from(x).unwrap()).collect();
+
+        // Scale so the 1-norm is at most θ13
+        let norm = self.one_norm().to_f64().unwrap();
+        let squarings = if norm > THETA_13 { (norm / THETA_13).log2().ceil() as i32 } else { 0 };
+        let a = self.scale(T::from(2.0_f64.powi(-squarings)).unwrap());
+
+        let identity = Matrix::identity(self.rows);
+        let a2 = a.clone() * a.clone();
+        let a4 = a2.clone() * a2.clone();
+        let a6 = a4.clone() * a2.clone();
+
+        // Odd (u) and even (v) parts of the Padé numerator
+        let u = a6.clone() * (a6.scale(b[13]) + a4.scale(b[11]) + a2.scale(b[9]))
+            + a6.scale(b[7]) + a4.scale(b[5]) + a2.scale(b[3]) + identity.scale(b[1]);
+        let u = a * u;
+        let v = a6.clone() * (a6.scale(b[12]) + a4.scale(b[10]) + a2.scale(b[8]))
+            + a6.scale(b[6]) + a4.scale(b[4]) + a2.scale(b[2]) + identity.scale(b[0]);
+
+        // r = (v - u)^-1 (v + u), then undo the scaling
+        let mut result = (v.clone() - u.clone()).solve(&(v + u));
+        for _ in 0..squarings {
+            result = result.clone() * result;
+        }
+        result
+    }
+
+    pub fn sqrtm(&self) -> Matrix<T> {
+        // Has to be square matrix
+        assert_eq!(self.rows, self.cols);
+
+        const MAX_ITERATIONS: usize = 100;
+        let half = T::one() / (T::one() + T::one());
+        let tolerance = T::from(self.rows.max(1)).unwrap() * T::epsilon();
+
+        let mut y = self.clone();
+        let mut z = Matrix::identity(self.rows);
+        for _ in 0..MAX_ITERATIONS {
+            let y_next = (y.clone() + z.inverse()).scale(half);
+            let z_next = (z.clone() + y.inverse()).scale(half);
+            let change = (y_next.clone() - y).one_norm();
+            y = y_next;
+            z = z_next;
+            if change <= tolerance * y.one_norm() {
+                break;
+            }
+        }
+        y
+    }
+
+    pub fn logm(&self) -> Matrix<T> {
+        // Has to be square matrix
+        assert_eq!(self.rows, self.cols);
+
+        const MAX_SQUARE_ROOTS: usize = 64;
+        const MAX_SERIES_TERMS: usize = 200;
+        let quarter = T::from(0.25).unwrap();
+        let identity = Matrix::identity(self.rows);
+
+        // Take square roots until X is close to the identity
+        let mut x = self.clone();
+        let mut square_roots = 0;
+        while (x.clone() - identity.clone()).one_norm() > quarter && square_roots < MAX_SQUARE_ROOTS {
+            x = x.sqrtm();
+            square_roots += 1;
+        }
+
+        // log(X) = 2 * sum_k Z^(2k+1) / (2k+1) with Z = (X + I)^-1 (X - I)
+        let z = (x.clone() + identity.clone()).solve(&(x - identity));
+        let z2 = z.clone() * z.clone();
+        let mut power = z.clone();
+        let mut sum = z;
+        for k in 1..MAX_SERIES_TERMS {
+            power = power * z2.clone();
+            let term = power.scale(T::one() / T::from(2 * k + 1).unwrap());
+            let term_norm = term.one_norm();
+            sum = sum + term;
+            if term_norm <= T::epsilon() * sum.one_norm() {
+                break;
+            }
+        }
+        sum.scale(T::from(2.0_f64.powi(square_roots as i32 + 1)).unwrap())
+    }
+}
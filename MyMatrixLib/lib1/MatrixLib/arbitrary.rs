@@ -0,0 +1,117 @@
+use crate::{Matrix, Scalar};
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+////////////////////////////////////// PROPTEST SUPPORT ///////////////////////////////////////////
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+
+/// Returns a `proptest` strategy generating matrices with a number of rows in `rows`, a number
+/// of columns in `cols` and elements drawn from `element` (with the `proptest` feature).
+///
+/// When a test fails, the matrix is shrunk both in its dimensions and in its elements.
+///
+/// # Examples
+///
+/// ```
+/// proptest!(|(a in matrix_strategy(1..10, 1..10, -1e3..1e3))| {
+///     prop_assert_eq!(a.transpose().transpose(), a);
+/// });
+/// ```
+#[cfg(feature = "proptest")]
+pub fn matrix_strategy<S>(rows: impl Into<proptest::collection::SizeRange>,
+                          cols: impl Into<proptest::collection::SizeRange>,
+                          element: S) -> impl proptest::strategy::Strategy<Value=Matrix<S::Value>>
+    where
+        S: proptest::strategy::Strategy,
+        S::Value: Scalar
+{
+    use proptest::strategy::Strategy;
+
+    let (rows, cols) = (rows.into(), cols.into());
+    let element = std::sync::Arc::new(element);
+    (rows.start()..rows.end_excl(), cols.start()..cols.end_excl()).prop_flat_map(move |(rows, cols)| {
+        proptest::collection::vec(element.clone(), rows * cols)
+            .prop_map(move |data| Matrix::from_vec(rows, cols, data))
+    })
+}
+
+/// Implements `proptest::arbitrary::Arbitrary` for matrices (with the `proptest` feature), so
+/// `any::<Matrix<T>>()` generates matrices of 1 to 8 rows and columns. The parameters are those
+/// of the element type. Use `matrix_strategy` for other dimensions.
+#[cfg(feature = "proptest")]
+impl<T> proptest::arbitrary::Arbitrary for Matrix<T>
+    where
+        T: Scalar +
+        proptest::arbitrary::Arbitrary,
+        T::Strategy: 'static
+{
+    type Parameters = T::Parameters;
+    type Strategy = proptest::strategy::BoxedStrategy<Self>;
+
+    fn arbitrary_with(params: Self::Parameters) -> Self::Strategy {
+        use proptest::strategy::Strategy;
+
+        matrix_strategy(1..=8, 1..=8, proptest::arbitrary::any_with::<T>(params)).boxed()
+    }
+}
+
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+////////////////////////////////////// QUICKCHECK SUPPORT /////////////////////////////////////////
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+
+/// Generates a random matrix for `quickcheck` with a number of rows in `rows` and a number of
+/// columns in `cols` (with the `quickcheck` feature).
+///
+/// # Panics
+///
+/// This function will panic if either range is empty.
+///
+/// # Examples
+///
+/// ```
+/// let mut g = quickcheck::Gen::new(10);
+/// let mat: Matrix<i32> = arbitrary_matrix(&mut g, 2..=4, 3..=3);
+/// ```
+#[cfg(feature = "quickcheck")]
+pub fn arbitrary_matrix<T>(g: &mut quickcheck::Gen,
+                           rows: std::ops::RangeInclusive<usize>,
+                           cols: std::ops::RangeInclusive<usize>) -> Matrix<T>
+    where
+        T: Scalar +
+        quickcheck::Arbitrary
+{
+    assert!(!rows.is_empty() && !cols.is_empty(), "Dimension ranges must not be empty");
+
+    let mut pick = |range: &std::ops::RangeInclusive<usize>| {
+        range.start() + <usize as quickcheck::Arbitrary>::arbitrary(g) % (range.end() - range.start() + 1)
+    };
+    let (rows, cols) = (pick(&rows), pick(&cols));
+    let data = (0..rows * cols).map(|_| T::arbitrary(g)).collect();
+    Matrix::from_vec(rows, cols, data)
+}
+
+/// Implements `quickcheck::Arbitrary` for matrices (with the `quickcheck` feature).
+///
+/// Both dimensions are chosen between 1 and the square root of the generator size, so the number
+/// of elements grows like the size (a size of 100 gives at most `10 x 10` matrices). Shrinking
+/// keeps the dimensions and shrinks the elements.
+#[cfg(feature = "quickcheck")]
+impl<T> quickcheck::Arbitrary for Matrix<T>
+    where
+        T: Scalar +
+        quickcheck::Arbitrary
+{
+    fn arbitrary(g: &mut quickcheck::Gen) -> Self {
+        let max_dim = ((g.size() as f64).sqrt() as usize).max(1);
+        arbitrary_matrix(g, 1..=max_dim, 1..=max_dim)
+    }
+
+    fn shrink(&self) -> Box<dyn Iterator<Item=Self>> {
+        let (rows, cols, layout) = (self.rows, self.cols, self.layout);
+        Box::new(self.data.shrink()
+            .filter(move |data| data.len() == rows * cols)
+            .map(move |data| Matrix::from_vec_with_layout(rows, cols, data, layout)))
+    }
+}
@@ -0,0 +1,398 @@
+use std::cell::RefCell;
+
+use crate::{Matrix, Real};
+
+/// Maps the gradient of a node to the gradients of its parents, in the order of `parents`.
+type Backward<T> = Box<dyn Fn(&Matrix<T>) -> Vec<Matrix<T>>>;
+
+struct Node<T>
+    where
+        T: Real
+{
+    value: Matrix<T>,
+    parents: Vec<usize>,
+    backward: Option<Backward<T>>,
+}
+
+/// A tape recording matrix computations for reverse-mode automatic differentiation.
+///
+/// Every operation on a `Var` computes its value right away and appends a node to the tape,
+/// holding the value, the indices of its operands and a closure mapping the gradient of the
+/// result to the gradients of the operands. `Var::backward` then walks the tape once from the
+/// end, so the gradients of a scalar with respect to all variables cost about as much as
+/// computing the scalar itself.
+///
+/// A tape only grows, so a training loop records each step on a new tape. Variables are cheap
+/// `Copy` handles borrowing the tape, and variables of different tapes cannot be combined.
+///
+/// # Examples
+///
+/// ```
+/// // f(W) = sum(tanh(X * W)) and its gradient with respect to W
+/// let tape = Tape::new();
+/// let x = tape.var(Matrix::from_vec(2, 2, vec![1.0, 2.0, 3.0, 4.0]));
+/// let w = tape.var(Matrix::from_vec(2, 1, vec![0.1, -0.2]));
+/// let f = (x * w).tanh().sum();
+/// let gradients = f.backward();
+/// println!("f = {}, df/dW = {}", f.value(), gradients.wrt(w));
+/// ```
+pub struct Tape<T>
+    where
+        T: Real
+{
+    nodes: RefCell<Vec<Node<T>>>,
+}
+
+impl<T> Default for Tape<T>
+    where
+        T: Real
+{
+    fn default() -> Self {
+        Tape { nodes: RefCell::new(Vec::new()) }
+    }
+}
+
+impl<T> Tape<T>
+    where
+        T: Real
+{
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records an input variable (e.g. a parameter or a data matrix).
+    pub fn var(&self, value: Matrix<T>) -> Var<'_, T> {
+        self.push(value, Vec::new(), None)
+    }
+
+    fn push(&self, value: Matrix<T>, parents: Vec<usize>, backward: Option<Backward<T>>) -> Var<'_, T> {
+        let mut nodes = self.nodes.borrow_mut();
+        nodes.push(Node { value, parents, backward });
+        Var { tape: self, index: nodes.len() - 1 }
+    }
+}
+
+
+/// A matrix-valued variable recorded on a `Tape`.
+///
+/// The operators `+`, `-` (binary and unary) and `*` (the matrix product) and the methods below
+/// record new variables; `value` returns the computed matrix and `backward` the gradients.
+///
+/// # Panics
+///
+/// The operations panic if the dimensions of the operands do not match, as the corresponding
+/// `Matrix` operations do, or if the operands belong to different tapes.
+#[derive(Clone, Copy)]
+pub struct Var<'t, T>
+    where
+        T: Real
+{
+    tape: &'t Tape<T>,
+    index: usize,
+}
+
+/// The gradients computed by `Var::backward`, with one matrix for every variable of the tape
+/// (zero for variables that the output does not depend on).
+pub struct Gradients<T>
+    where
+        T: Real
+{
+    gradients: Vec<Matrix<T>>,
+}
+
+impl<T> Gradients<T>
+    where
+        T: Real
+{
+    /// Returns the gradient with respect to `var`, with the dimensions of its value.
+    pub fn wrt(&self, var: Var<'_, T>) -> &Matrix<T> {
+        &self.gradients[var.index]
+    }
+}
+
+impl<'t, T> Var<'t, T>
+    where
+        T: Real +
+        std::fmt::Display
+{
+    /// Returns a copy of the value of the variable.
+    pub fn value(&self) -> Matrix<T> {
+        self.tape.nodes.borrow()[self.index].value.clone()
+    }
+
+    fn unary<F>(self, value: Matrix<T>, backward: F) -> Var<'t, T>
+        where
+            F: Fn(&Matrix<T>) -> Matrix<T> + 'static
+    {
+        self.tape.push(value, vec![self.index], Some(Box::new(move |gradient| vec![backward(gradient)])))
+    }
+
+    fn binary<F>(self, other: Var<'t, T>, value: Matrix<T>, backward: F) -> Var<'t, T>
+        where
+            F: Fn(&Matrix<T>) -> (Matrix<T>, Matrix<T>) + 'static
+    {
+        assert!(std::ptr::eq(self.tape, other.tape), "Variables must belong to the same tape");
+        self.tape.push(value, vec![self.index, other.index], Some(Box::new(move |gradient| {
+            let (first, second) = backward(gradient);
+            vec![first, second]
+        })))
+    }
+
+    /// Computes the gradients of this variable, which must be `1 x 1` (e.g. a loss), with
+    /// respect to every variable of the tape.
+    ///
+    /// # Panics
+    ///
+    /// This method will panic if the variable is not `1 x 1`.
+    pub fn backward(&self) -> Gradients<T> {
+        let (rows, cols) = {
+            let nodes = self.tape.nodes.borrow();
+            (nodes[self.index].value.rows, nodes[self.index].value.cols)
+        };
+        assert!(rows == 1 && cols == 1, "backward requires a 1 x 1 variable, got {} x {}, use backward_with", rows, cols);
+        self.backward_with(Matrix::ones(1, 1))
+    }
+
+    /// Computes the gradients of `sum(seed ∘ self)` with respect to every variable of the tape,
+    /// i.e. propagates the gradient `seed` of this variable (a vector-Jacobian product).
+    ///
+    /// # Panics
+    ///
+    /// This method will panic if `seed` does not have the dimensions of the variable.
+    pub fn backward_with(&self, seed: Matrix<T>) -> Gradients<T> {
+        let nodes = self.tape.nodes.borrow();
+        let value = &nodes[self.index].value;
+        assert!(seed.rows == value.rows && seed.cols == value.cols,
+                "Seed of dim {} x {} does not match variable of dim {} x {}", seed.rows, seed.cols, value.rows, value.cols);
+
+        // Operands always precede their results on the tape, so one reverse pass suffices
+        let mut gradients: Vec<Option<Matrix<T>>> = (0..nodes.len()).map(|_| None).collect();
+        gradients[self.index] = Some(seed);
+        for index in (0..=self.index).rev() {
+            let node = &nodes[index];
+            let parent_gradients = match (&gradients[index], &node.backward) {
+                (Some(gradient), Some(backward)) => backward(gradient),
+                _ => continue,
+            };
+            for (&parent, parent_gradient) in node.parents.iter().zip(parent_gradients) {
+                gradients[parent] = Some(match gradients[parent].take() {
+                    Some(sum) => sum + parent_gradient,
+                    None => parent_gradient,
+                });
+            }
+        }
+
+        let gradients = gradients.into_iter().zip(nodes.iter())
+            .map(|(gradient, node)| gradient.unwrap_or_else(|| Matrix::zeros(node.value.rows, node.value.cols)))
+            .collect();
+        Gradients { gradients }
+    }
+
+    /// Returns the transpose.
+    pub fn transpose(self) -> Var<'t, T> {
+        let value = self.value().transpose();
+        self.unary(value, |gradient| gradient.transpose())
+    }
+
+    /// Multiplies every element by `factor`.
+    pub fn scale(self, factor: T) -> Var<'t, T> {
+        let value = self.value().scale(factor);
+        self.unary(value, move |gradient| gradient.scale(factor))
+    }
+
+    /// Multiplies two variables of the same dimensions element by element.
+    pub fn hadamard(self, other: Var<'t, T>) -> Var<'t, T> {
+        let (a, b) = (self.value(), other.value());
+        let value = a.hadamard(&b);
+        self.binary(other, value, move |gradient| (gradient.hadamard(&b), gradient.hadamard(&a)))
+    }
+
+    /// Adds the `1 x cols` row vector `row` to every row (e.g. a bias to a batch of outputs).
+    ///
+    /// # Panics
+    ///
+    /// This method will panic if `row` is not a `1 x cols` matrix.
+    pub fn add_row(self, row: Var<'t, T>) -> Var<'t, T> {
+        let (a, r) = (self.value(), row.value());
+        assert!(r.rows == 1 && r.cols == a.cols, "Expected a 1 x {} row vector, got {} x {}", a.cols, r.rows, r.cols);
+
+        let mut value = a;
+        for i in 0..value.rows {
+            for j in 0..value.cols {
+                value[(i, j)] = value[(i, j)] + r[(0, j)];
+            }
+        }
+        self.binary(row, value, |gradient| (gradient.clone(), gradient.sum_axis(0)))
+    }
+
+    /// Applies `f` to every element, where `df` is the derivative of `f`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let tape = Tape::new();
+    /// let x = tape.var(Matrix::from_vec(1, 2, vec![1.0, 2.0]));
+    /// let cube = x.map(|x| x * x * x, |x| 3.0 * x * x);
+    /// assert_eq!(cube.sum().backward().wrt(x), &Matrix::from_vec(1, 2, vec![3.0, 12.0]));
+    /// ```
+    pub fn map<F, D>(self, f: F, df: D) -> Var<'t, T>
+        where
+            F: Fn(T) -> T,
+            D: Fn(T) -> T + 'static
+    {
+        let input = self.value();
+        let value = input.map(f);
+        self.unary(value, move |gradient| gradient.hadamard(&input.map(&df)))
+    }
+
+    /// Applies the exponential function to every element.
+    pub fn exp(self) -> Var<'t, T> {
+        self.map(T::exp, T::exp)
+    }
+
+    /// Applies the natural logarithm to every element.
+    pub fn ln(self) -> Var<'t, T> {
+        self.map(T::ln, T::recip)
+    }
+
+    /// Applies the hyperbolic tangent to every element.
+    pub fn tanh(self) -> Var<'t, T> {
+        self.map(T::tanh, |x| T::one() - x.tanh() * x.tanh())
+    }
+
+    /// Applies the logistic sigmoid `1 / (1 + e^-x)` to every element.
+    pub fn sigmoid(self) -> Var<'t, T> {
+        self.map(sigmoid, |x| sigmoid(x) * (T::one() - sigmoid(x)))
+    }
+
+    /// Applies the rectified linear unit `max(x, 0)` to every element, with derivative `0`
+    /// at `0`.
+    pub fn relu(self) -> Var<'t, T> {
+        self.map(|x| x.max(T::zero()), |x| if x > T::zero() { T::one() } else { T::zero() })
+    }
+
+    /// Applies the softmax function to every row, so each row becomes a probability
+    /// distribution (e.g. over the classes of a classifier). The row maximum is subtracted
+    /// before exponentiating, so large inputs do not overflow.
+    pub fn softmax(self) -> Var<'t, T> {
+        let mut value = self.value();
+        for i in 0..value.rows {
+            let max = (0..value.cols).fold(T::neg_infinity(), |max, j| max.max(value[(i, j)]));
+            let mut sum = T::zero();
+            for j in 0..value.cols {
+                value[(i, j)] = (value[(i, j)] - max).exp();
+                sum = sum + value[(i, j)];
+            }
+            for j in 0..value.cols {
+                value[(i, j)] = value[(i, j)] / sum;
+            }
+        }
+
+        // For s = softmax(x) and output gradient g, each row of the gradient is s ∘ (g - g · s)
+        let softmax = value.clone();
+        self.unary(value, move |gradient| {
+            let mut result = softmax.hadamard(gradient);
+            for i in 0..result.rows {
+                let dot = (0..result.cols).fold(T::zero(), |sum, j| sum + result[(i, j)]);
+                for j in 0..result.cols {
+                    result[(i, j)] = result[(i, j)] - softmax[(i, j)] * dot;
+                }
+            }
+            result
+        })
+    }
+
+    /// Returns the sum of all elements as a `1 x 1` variable.
+    pub fn sum(self) -> Var<'t, T> {
+        let input = self.value();
+        let (rows, cols) = (input.rows, input.cols);
+        let value = Matrix::from_vec(1, 1, vec![input.sum()]);
+        self.unary(value, move |gradient| Matrix::ones(rows, cols).scale(gradient[(0, 0)]))
+    }
+
+    /// Returns the mean of all elements as a `1 x 1` variable.
+    pub fn mean(self) -> Var<'t, T> {
+        let len = {
+            let nodes = self.tape.nodes.borrow();
+            nodes[self.index].value.rows * nodes[self.index].value.cols
+        };
+        self.sum().scale(T::one() / T::from(len).unwrap())
+    }
+
+    /// Returns the sums of the columns (axis `0`, a `1 x cols` row vector) or of the rows
+    /// (axis `1`, a `rows x 1` column vector), as `Matrix::sum_axis`.
+    pub fn sum_axis(self, axis: usize) -> Var<'t, T> {
+        let input = self.value();
+        let (rows, cols) = (input.rows, input.cols);
+        let value = input.sum_axis(axis);
+        self.unary(value, move |gradient| {
+            let mut result = Matrix::zeros(rows, cols);
+            for i in 0..rows {
+                for j in 0..cols {
+                    result[(i, j)] = if axis == 0 { gradient[(0, j)] } else { gradient[(i, 0)] };
+                }
+            }
+            result
+        })
+    }
+}
+
+fn sigmoid<T: Real>(x: T) -> T {
+    T::one() / (T::one() + (-x).exp())
+}
+
+impl<'t, T> std::ops::Add for Var<'t, T>
+    where
+        T: Real +
+        std::fmt::Display
+{
+    type Output = Var<'t, T>;
+
+    fn add(self, other: Var<'t, T>) -> Var<'t, T> {
+        let value = self.value() + other.value();
+        self.binary(other, value, |gradient| (gradient.clone(), gradient.clone()))
+    }
+}
+
+impl<'t, T> std::ops::Sub for Var<'t, T>
+    where
+        T: Real +
+        std::fmt::Display
+{
+    type Output = Var<'t, T>;
+
+    fn sub(self, other: Var<'t, T>) -> Var<'t, T> {
+        let value = self.value() - other.value();
+        self.binary(other, value, |gradient| (gradient.clone(), gradient.scale(-T::one())))
+    }
+}
+
+impl<'t, T> std::ops::Neg for Var<'t, T>
+    where
+        T: Real +
+        std::fmt::Display
+{
+    type Output = Var<'t, T>;
+
+    fn neg(self) -> Var<'t, T> {
+        self.scale(-T::one())
+    }
+}
+
+/// The matrix product: for `C = A * B` with output gradient `G`, the gradients are `G * B^T`
+/// and `A^T * G`.
+impl<'t, T> std::ops::Mul for Var<'t, T>
+    where
+        T: Real +
+        std::fmt::Display
+{
+    type Output = Var<'t, T>;
+
+    fn mul(self, other: Var<'t, T>) -> Var<'t, T> {
+        let (a, b) = (self.value(), other.value());
+        let value = a.clone() * b.clone();
+        self.binary(other, value, move |gradient| {
+            (gradient.clone() * b.transpose(), a.transpose() * gradient.clone())
+        })
+    }
+}
@@ -0,0 +1,390 @@
+use std::any::Any;
+use std::cell::Cell;
+use std::os::raw::{c_char, c_int};
+
+use crate::{transpose, Layout, Matrix, Scalar};
+
+// The Fortran interface of BLAS and LAPACK, provided by the library linked into the final
+// binary (e.g. OpenBLAS, or the reference implementation of the `blas-reference` feature)
+extern "C" {
+    fn sgemm_(transa: *const c_char, transb: *const c_char, m: *const c_int, n: *const c_int, k: *const c_int,
+              alpha: *const f32, a: *const f32, lda: *const c_int, b: *const f32, ldb: *const c_int,
+              beta: *const f32, c: *mut f32, ldc: *const c_int);
+    fn dgemm_(transa: *const c_char, transb: *const c_char, m: *const c_int, n: *const c_int, k: *const c_int,
+              alpha: *const f64, a: *const f64, lda: *const c_int, b: *const f64, ldb: *const c_int,
+              beta: *const f64, c: *mut f64, ldc: *const c_int);
+    fn sgetrf_(m: *const c_int, n: *const c_int, a: *mut f32, lda: *const c_int, ipiv: *mut c_int, info: *mut c_int);
+    fn dgetrf_(m: *const c_int, n: *const c_int, a: *mut f64, lda: *const c_int, ipiv: *mut c_int, info: *mut c_int);
+    fn sgetrs_(trans: *const c_char, n: *const c_int, nrhs: *const c_int, a: *const f32, lda: *const c_int,
+               ipiv: *const c_int, b: *mut f32, ldb: *const c_int, info: *mut c_int);
+    fn dgetrs_(trans: *const c_char, n: *const c_int, nrhs: *const c_int, a: *const f64, lda: *const c_int,
+               ipiv: *const c_int, b: *mut f64, ldb: *const c_int, info: *mut c_int);
+    fn spotrf_(uplo: *const c_char, n: *const c_int, a: *mut f32, lda: *const c_int, info: *mut c_int);
+    fn dpotrf_(uplo: *const c_char, n: *const c_int, a: *mut f64, lda: *const c_int, info: *mut c_int);
+    fn sgeqrf_(m: *const c_int, n: *const c_int, a: *mut f32, lda: *const c_int, tau: *mut f32, work: *mut f32,
+               lwork: *const c_int, info: *mut c_int);
+    fn dgeqrf_(m: *const c_int, n: *const c_int, a: *mut f64, lda: *const c_int, tau: *mut f64, work: *mut f64,
+               lwork: *const c_int, info: *mut c_int);
+    fn sorgqr_(m: *const c_int, n: *const c_int, k: *const c_int, a: *mut f32, lda: *const c_int, tau: *const f32,
+               work: *mut f32, lwork: *const c_int, info: *mut c_int);
+    fn dorgqr_(m: *const c_int, n: *const c_int, k: *const c_int, a: *mut f64, lda: *const c_int, tau: *const f64,
+               work: *mut f64, lwork: *const c_int, info: *mut c_int);
+    fn ssyev_(jobz: *const c_char, uplo: *const c_char, n: *const c_int, a: *mut f32, lda: *const c_int, w: *mut f32,
+              work: *mut f32, lwork: *const c_int, info: *mut c_int);
+    fn dsyev_(jobz: *const c_char, uplo: *const c_char, n: *const c_int, a: *mut f64, lda: *const c_int, w: *mut f64,
+              work: *mut f64, lwork: *const c_int, info: *mut c_int);
+}
+
+thread_local! {
+    static NATIVE: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Runs `f` with the pure-Rust implementations instead of BLAS and LAPACK (with the `blas`
+/// feature), on the current thread only.
+///
+/// Useful to cross-check the two implementations, or to get the exact results of the pure-Rust
+/// code. Calls may be nested, and the previous setting is restored when `f` returns or panics.
+///
+/// # Examples
+///
+/// ```
+/// let a = Matrix::<f64>::random_uniform(50, 50, -1.0, 1.0);
+/// let native = with_native(|| a.clone() * a.clone());
+/// assert!((a.clone() * a).approx_eq(&native, 1e-12, 1e-12));
+/// ```
+pub fn with_native<R>(f: impl FnOnce() -> R) -> R {
+    struct Restore(bool);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            NATIVE.with(|native| native.set(self.0));
+        }
+    }
+
+    let _restore = Restore(NATIVE.with(|native| native.replace(true)));
+    f()
+}
+
+/// Converts a dimension to the integer type of the Fortran interface.
+fn int(value: usize) -> c_int {
+    c_int::try_from(value).expect("Matrix dimension is too large for BLAS")
+}
+
+/// Returns the `trans` flag and leading dimension that make `gemm` read the buffer of `m` as
+/// `m^T`.
+fn transposed_operand<T: Scalar>(m: &Matrix<T>) -> (c_char, c_int) {
+    match m.layout {
+        Layout::RowMajor => (b'N' as c_char, int(m.cols.max(1))),
+        Layout::ColumnMajor => (b'T' as c_char, int(m.rows.max(1))),
+    }
+}
+
+/// Moves a value of type `T` into type `U`, which must be the same type.
+fn cast<T: 'static, U: 'static>(value: T) -> U {
+    *(Box::new(value) as Box<dyn Any>).downcast::<U>().expect("Types must be the same")
+}
+
+/// Borrows a value of type `T` as type `U`, which must be the same type.
+fn cast_ref<T: 'static, U: 'static>(value: &T) -> &U {
+    (value as &dyn Any).downcast_ref::<U>().expect("Types must be the same")
+}
+
+/// The element types with BLAS and LAPACK routines: `f32` and `f64`.
+///
+/// The routines expect column-major storage, so they see a row-major buffer as the transpose of
+/// the matrix. Products compute the row-major result as `(A * B)^T = B^T * A^T`, passing each
+/// operand as is with the `trans` flag matching its layout, so no operand is copied. The
+/// factorizations convert their input to the layout they need and return row-major matrices.
+trait BlasElement: Scalar + num_traits::Float + 'static {
+    /// # Safety
+    ///
+    /// The arguments must satisfy the requirements of `?gemm`.
+    #[allow(clippy::too_many_arguments)]
+    unsafe fn gemm(transa: c_char, transb: c_char, m: c_int, n: c_int, k: c_int, a: *const Self, lda: c_int,
+                   b: *const Self, ldb: c_int, c: *mut Self, ldc: c_int);
+
+    /// # Safety
+    ///
+    /// The arguments must satisfy the requirements of `?getrf`.
+    unsafe fn getrf(n: c_int, a: *mut Self, ipiv: *mut c_int) -> c_int;
+
+    /// # Safety
+    ///
+    /// The arguments must satisfy the requirements of `?getrs`.
+    unsafe fn getrs(n: c_int, nrhs: c_int, a: *const Self, ipiv: *const c_int, b: *mut Self) -> c_int;
+
+    /// # Safety
+    ///
+    /// The arguments must satisfy the requirements of `?potrf`.
+    unsafe fn potrf(n: c_int, a: *mut Self) -> c_int;
+
+    /// # Safety
+    ///
+    /// The arguments must satisfy the requirements of `?geqrf`.
+    unsafe fn geqrf(m: c_int, n: c_int, a: *mut Self, tau: *mut Self, work: *mut Self, lwork: c_int) -> c_int;
+
+    /// # Safety
+    ///
+    /// The arguments must satisfy the requirements of `?orgqr`.
+    unsafe fn orgqr(m: c_int, n: c_int, k: c_int, a: *mut Self, tau: *const Self, work: *mut Self,
+                    lwork: c_int) -> c_int;
+
+    /// # Safety
+    ///
+    /// The arguments must satisfy the requirements of `?syev`.
+    unsafe fn syev(n: c_int, a: *mut Self, w: *mut Self, work: *mut Self, lwork: c_int) -> c_int;
+
+    /// Calls a LAPACK routine that needs a workspace, first with `lwork = -1` to query the size
+    /// of the workspace and then with a workspace of that size, and returns its `info`.
+    fn with_workspace(mut routine: impl FnMut(*mut Self, c_int) -> c_int) -> c_int {
+        let mut size = Self::zero();
+        let info = routine(&mut size, -1);
+        if info != 0 {
+            return info;
+        }
+        let mut work = vec![Self::zero(); size.to_usize().unwrap_or(1).max(1)];
+        routine(work.as_mut_ptr(), int(work.len()))
+    }
+
+    fn multiply(a: &Matrix<Self>, b: &Matrix<Self>) -> Matrix<Self> {
+        let (m, k, n) = (a.rows, a.cols, b.cols);
+        let mut c = vec![Self::zero(); m * n];
+        if m > 0 && n > 0 {
+            let (trans_b, ldb) = transposed_operand(b);
+            let (trans_a, lda) = transposed_operand(a);
+            // SAFETY: the buffers have the sizes given by the dimensions
+            unsafe {
+                Self::gemm(trans_b, trans_a, int(n), int(m), int(k), b.data.as_ptr(), ldb, a.data.as_ptr(), lda,
+                           c.as_mut_ptr(), int(n.max(1)));
+            }
+        }
+        Matrix { rows: m, cols: n, data: c, layout: Layout::RowMajor }
+    }
+
+    /// Returns the column-major LU factorization, the 1-based pivots of `getrf` and whether the
+    /// matrix is singular.
+    fn factor(a: &Matrix<Self>) -> (Vec<Self>, Vec<c_int>, bool) {
+        let n = a.rows;
+        let mut lu = a.data_in(Layout::ColumnMajor).into_owned();
+        let mut pivots = vec![0; n];
+        // SAFETY: `lu` is `n x n` and `pivots` has `n` elements
+        let info = unsafe { Self::getrf(int(n), lu.as_mut_ptr(), pivots.as_mut_ptr()) };
+        assert!(info >= 0, "Illegal argument {} to getrf", -info);
+        (lu, pivots, info > 0)
+    }
+
+    fn lu_factor(a: &Matrix<Self>) -> (Matrix<Self>, Vec<usize>) {
+        let n = a.rows;
+        let (lu, pivots, _) = Self::factor(a);
+        let mut permutation: Vec<usize> = (0..n).collect();
+        for (i, &pivot) in pivots.iter().enumerate() {
+            permutation.swap(i, pivot as usize - 1);
+        }
+        (Matrix { rows: n, cols: n, data: transpose::transposed(&lu, n, n), layout: Layout::RowMajor }, permutation)
+    }
+
+    fn solve(a: &Matrix<Self>, b: &Matrix<Self>) -> Matrix<Self> {
+        let (n, nrhs) = (a.rows, b.cols);
+        let (lu, pivots, singular) = Self::factor(a);
+        if singular {
+            panic!("Cannot solve linear system, matrix is singular.");
+        }
+        let mut x = b.data_in(Layout::ColumnMajor).into_owned();
+        if n > 0 && nrhs > 0 {
+            // SAFETY: `lu` is `n x n`, `pivots` has `n` elements and `x` is `n x nrhs`
+            let info = unsafe { Self::getrs(int(n), int(nrhs), lu.as_ptr(), pivots.as_ptr(), x.as_mut_ptr()) };
+            assert!(info >= 0, "Illegal argument {} to getrs", -info);
+        }
+        Matrix { rows: n, cols: nrhs, data: transpose::transposed(&x, nrhs, n), layout: Layout::RowMajor }
+    }
+
+    fn cholesky(a: &Matrix<Self>) -> Matrix<Self> {
+        let n = a.rows;
+        // The upper triangle of the column-major buffer is the lower triangle of the matrix, and
+        // the factor U of A^T = U^T * U is read row-major as L
+        let mut l = a.row_major().into_owned();
+        // SAFETY: `l` is `n x n`
+        let info = unsafe { Self::potrf(int(n), l.as_mut_ptr()) };
+        assert!(info >= 0, "Illegal argument {} to potrf", -info);
+        if info > 0 {
+            panic!("Cannot compute Cholesky decomposition, matrix is not positive definite.");
+        }
+        for i in 0..n {
+            for j in (i + 1)..n {
+                l[i * n + j] = Self::zero();
+            }
+        }
+        Matrix { rows: n, cols: n, data: l, layout: Layout::RowMajor }
+    }
+
+    fn qr(a: &Matrix<Self>) -> (Matrix<Self>, Matrix<Self>) {
+        let (m, n) = (a.rows, a.cols);
+        let k = m.min(n);
+        let mut r = a.data_in(Layout::ColumnMajor).into_owned();
+        let mut tau = vec![Self::zero(); k];
+        if k > 0 {
+            // SAFETY: `r` is `m x n`, `tau` has `min(m, n)` elements and the workspace has `lwork`
+            let info = Self::with_workspace(|work, lwork| unsafe {
+                Self::geqrf(int(m), int(n), r.as_mut_ptr(), tau.as_mut_ptr(), work, lwork)
+            });
+            assert!(info >= 0, "Illegal argument {} to geqrf", -info);
+        }
+
+        // The full `m x m` factor Q, from the reflectors below the diagonal of the first `k`
+        // columns
+        let mut q = vec![Self::zero(); m * m];
+        q[..m * k].copy_from_slice(&r[..m * k]);
+        if m > 0 {
+            // SAFETY: `q` is `m x m`, `tau` has `k <= m` elements and the workspace has `lwork`
+            let info = Self::with_workspace(|work, lwork| unsafe {
+                Self::orgqr(int(m), int(m), int(k), q.as_mut_ptr(), tau.as_ptr(), work, lwork)
+            });
+            assert!(info >= 0, "Illegal argument {} to orgqr", -info);
+        }
+        for j in 0..n {
+            for i in (j + 1)..m {
+                r[i + j * m] = Self::zero();
+            }
+        }
+        (Matrix { rows: m, cols: m, data: transpose::transposed(&q, m, m), layout: Layout::RowMajor },
+         Matrix { rows: m, cols: n, data: transpose::transposed(&r, n, m), layout: Layout::RowMajor })
+    }
+
+    fn symmetric_eigen(a: &Matrix<Self>) -> (Vec<Self>, Matrix<Self>) {
+        let n = a.rows;
+        let mut v = a.data_in(Layout::ColumnMajor).into_owned();
+        let mut values = vec![Self::zero(); n];
+        if n > 0 {
+            // SAFETY: `v` is `n x n`, `values` has `n` elements and the workspace has `lwork`
+            let info = Self::with_workspace(|work, lwork| unsafe {
+                Self::syev(int(n), v.as_mut_ptr(), values.as_mut_ptr(), work, lwork)
+            });
+            assert!(info >= 0, "Illegal argument {} to syev", -info);
+            if info > 0 {
+                panic!("Cannot compute eigenvalues, syev did not converge.");
+            }
+        }
+
+        // syev sorts the eigenvalues in ascending order, so reverse them and the eigenvectors
+        values.reverse();
+        let mut vectors = vec![Self::zero(); n * n];
+        for i in 0..n {
+            for j in 0..n {
+                vectors[i * n + j] = v[i + (n - 1 - j) * n];
+            }
+        }
+        (values, Matrix { rows: n, cols: n, data: vectors, layout: Layout::RowMajor })
+    }
+}
+
+macro_rules! impl_blas_element {
+    ($t:ty, $gemm:ident, $getrf:ident, $getrs:ident, $potrf:ident, $geqrf:ident, $orgqr:ident, $syev:ident) => {
+        impl BlasElement for $t {
+            unsafe fn gemm(transa: c_char, transb: c_char, m: c_int, n: c_int, k: c_int, a: *const Self, lda: c_int,
+                           b: *const Self, ldb: c_int, c: *mut Self, ldc: c_int) {
+                $gemm(&transa, &transb, &m, &n, &k, &1.0, a, &lda, b, &ldb, &0.0, c, &ldc);
+            }
+
+            unsafe fn getrf(n: c_int, a: *mut Self, ipiv: *mut c_int) -> c_int {
+                let mut info = 0;
+                $getrf(&n, &n, a, &n.max(1), ipiv, &mut info);
+                info
+            }
+
+            unsafe fn getrs(n: c_int, nrhs: c_int, a: *const Self, ipiv: *const c_int, b: *mut Self) -> c_int {
+                let mut info = 0;
+                $getrs(&(b'N' as c_char), &n, &nrhs, a, &n.max(1), ipiv, b, &n.max(1), &mut info);
+                info
+            }
+
+            unsafe fn potrf(n: c_int, a: *mut Self) -> c_int {
+                let mut info = 0;
+                $potrf(&(b'U' as c_char), &n, a, &n.max(1), &mut info);
+                info
+            }
+
+            unsafe fn geqrf(m: c_int, n: c_int, a: *mut Self, tau: *mut Self, work: *mut Self, lwork: c_int) -> c_int {
+                let mut info = 0;
+                $geqrf(&m, &n, a, &m.max(1), tau, work, &lwork, &mut info);
+                info
+            }
+
+            unsafe fn orgqr(m: c_int, n: c_int, k: c_int, a: *mut Self, tau: *const Self, work: *mut Self,
+                            lwork: c_int) -> c_int {
+                let mut info = 0;
+                $orgqr(&m, &n, &k, a, &m.max(1), tau, work, &lwork, &mut info);
+                info
+            }
+
+            unsafe fn syev(n: c_int, a: *mut Self, w: *mut Self, work: *mut Self, lwork: c_int) -> c_int {
+                let mut info = 0;
+                $syev(&(b'V' as c_char), &(b'L' as c_char), &n, a, &n.max(1), w, work, &lwork, &mut info);
+                info
+            }
+        }
+    };
+}
+
+impl_blas_element!(f32, sgemm_, sgetrf_, sgetrs_, spotrf_, sgeqrf_, sorgqr_, ssyev_);
+impl_blas_element!(f64, dgemm_, dgetrf_, dgetrs_, dpotrf_, dgeqrf_, dorgqr_, dsyev_);
+
+/// Evaluates `$body` with the type alias `$K` set to `$T` if `$T` has BLAS routines and they are
+/// not disabled by `with_native`, giving `Some` result, and gives `None` otherwise.
+macro_rules! dispatch {
+    ($T:ty, $K:ident => $body:expr) => {
+        if NATIVE.with(|native| native.get()) {
+            None
+        } else if std::any::TypeId::of::<$T>() == std::any::TypeId::of::<f64>() {
+            type $K = f64;
+            Some($body)
+        } else if std::any::TypeId::of::<$T>() == std::any::TypeId::of::<f32>() {
+            type $K = f32;
+            Some($body)
+        } else {
+            None
+        }
+    };
+}
+
+/// Returns `a * b` computed by `gemm`, or `None` for other element types.
+pub(crate) fn multiply<T: Scalar + 'static>(a: &Matrix<T>, b: &Matrix<T>) -> Option<Matrix<T>> {
+    dispatch!(T, K => cast(K::multiply(cast_ref(a), cast_ref(b))))
+}
+
+/// Returns the factorization of `lup_factor` computed by `getrf`, or `None` for other element
+/// types.
+pub(crate) fn lu_factor<T: 'static>(a: &Matrix<T>) -> Option<(Matrix<T>, Vec<usize>)> {
+    dispatch!(T, K => {
+        let (lu, permutation) = K::lu_factor(cast_ref(a));
+        (cast(lu), permutation)
+    })
+}
+
+/// Returns the solution of `a * x = b` computed by `getrf` and `getrs`, or `None` for other
+/// element types.
+pub(crate) fn solve<T: 'static>(a: &Matrix<T>, b: &Matrix<T>) -> Option<Matrix<T>> {
+    dispatch!(T, K => cast(K::solve(cast_ref(a), cast_ref(b))))
+}
+
+/// Returns the Cholesky factor computed by `potrf`, or `None` for other element types.
+pub(crate) fn cholesky<T: Scalar + 'static>(a: &Matrix<T>) -> Option<Matrix<T>> {
+    dispatch!(T, K => cast(K::cholesky(cast_ref(a))))
+}
+
+/// Returns the factors of `QR_decompose` computed by `geqrf` and `orgqr`, or `None` for other
+/// element types.
+pub(crate) fn qr<T: Scalar + 'static>(a: &Matrix<T>) -> Option<(Matrix<T>, Matrix<T>)> {
+    dispatch!(T, K => {
+        let (q, r) = K::qr(cast_ref(a));
+        (cast(q), cast(r))
+    })
+}
+
+/// Returns the eigenvalues and eigenvectors of `symmetric_eigen` computed by `syev`, or `None`
+/// for other element types.
+pub(crate) fn symmetric_eigen<T: Scalar + 'static>(a: &Matrix<T>) -> Option<(Vec<T>, Matrix<T>)> {
+    dispatch!(T, K => {
+        let (values, vectors) = K::symmetric_eigen(cast_ref(a));
+        (cast(values), cast(vectors))
+    })
+}
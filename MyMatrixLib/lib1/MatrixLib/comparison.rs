@@ -0,0 +1,135 @@
+use crate::{Matrix, Real};
+#[cfg(feature = "approx")]
+use crate::Scalar;
+
+/// Exact equality of matrices: two matrices are equal if they have the same dimensions and the
+/// same elements, whatever their layouts.
+///
+/// # Examples
+///
+/// ```
+/// let a = Matrix::from_vec(2, 2, vec![1, 2, 3, 4]);
+/// assert_eq!(a, a.to_layout(Layout::ColumnMajor));
+/// assert_ne!(a, a.transpose());
+/// ```
+impl<T> PartialEq for Matrix<T>
+    where
+        T: PartialEq
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.rows == other.rows && self.cols == other.cols && if self.layout == other.layout {
+            self.data == other.data
+        } else {
+            (0..self.rows).all(|i| (0..self.cols).all(|j| self[(i, j)] == other[(i, j)]))
+        }
+    }
+}
+
+/// Approximate equality of floating point matrices.
+///
+/// Two matrices are approximately equal if they have the same dimensions and every pair of
+/// corresponding elements `a` and `b` satisfies
+///
+/// `|a - b| <= max(abs_tol, rel_tol * max(|a|, |b|))`
+///
+/// so `abs_tol` decides for elements close to zero and `rel_tol` for large elements. Elements
+/// that are NaN are never equal, while infinities are equal only to themselves.
+///
+/// # Examples
+///
+/// ```
+/// let a = Matrix::from_vec(1, 2, vec![1.0, 1e6]);
+/// let b = Matrix::from_vec(1, 2, vec![1.0 + 1e-12, 1e6 + 1e-4]);
+/// assert!(a.approx_eq(&b, 1e-9, 1e-9));
+/// assert!(!a.approx_eq(&b, 1e-9, 0.0));
+/// ```
+impl<T> Matrix<T>
+    where
+        T: Real
+{
+    pub fn approx_eq(&self, other: &Matrix<T>, abs_tol: T, rel_tol: T) -> bool {
+        self.rows == other.rows && self.cols == other.cols &&
+            self.data.iter().zip(other.data_in(self.layout).iter()).all(|(&a, &b)| {
+                if a == b {
+                    return true;
+                }
+                if a.is_infinite() || b.is_infinite() {
+                    return false;
+                }
+                let tolerance = abs_tol.max(rel_tol * a.abs().max(b.abs()));
+                (a - b).abs() <= tolerance
+            })
+    }
+}
+
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+////////////////////////////////////// APPROX SUPPORT /////////////////////////////////////////////
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+
+/// Implements the `AbsDiffEq`, `RelativeEq` and `UlpsEq` traits of the `approx` crate (with the
+/// `approx` feature), so matrices can be compared with `assert_abs_diff_eq!`,
+/// `assert_relative_eq!` and `assert_ulps_eq!`. Matrices of different dimensions are never
+/// equal; otherwise the comparison is applied to each pair of elements.
+///
+/// # Examples
+///
+/// ```
+/// let a = Matrix::from_vec(2, 2, vec![1.0, 2.0, 3.0, 4.0]);
+/// let b = a.inverse().inverse();
+/// approx::assert_relative_eq!(a, b, epsilon = 1e-12);
+/// ```
+#[cfg(feature = "approx")]
+impl<T> approx::AbsDiffEq for Matrix<T>
+    where
+        T: Scalar +
+        approx::AbsDiffEq,
+        T::Epsilon: Copy
+{
+    type Epsilon = T::Epsilon;
+
+    fn default_epsilon() -> Self::Epsilon {
+        T::default_epsilon()
+    }
+
+    fn abs_diff_eq(&self, other: &Self, epsilon: Self::Epsilon) -> bool {
+        self.rows == other.rows && self.cols == other.cols &&
+            self.data.iter().zip(other.data_in(self.layout).iter()).all(|(a, b)| a.abs_diff_eq(b, epsilon))
+    }
+}
+
+#[cfg(feature = "approx")]
+impl<T> approx::RelativeEq for Matrix<T>
+    where
+        T: Scalar +
+        approx::RelativeEq,
+        T::Epsilon: Copy
+{
+    fn default_max_relative() -> Self::Epsilon {
+        T::default_max_relative()
+    }
+
+    fn relative_eq(&self, other: &Self, epsilon: Self::Epsilon, max_relative: Self::Epsilon) -> bool {
+        self.rows == other.rows && self.cols == other.cols &&
+            self.data.iter().zip(other.data_in(self.layout).iter())
+                .all(|(a, b)| a.relative_eq(b, epsilon, max_relative))
+    }
+}
+
+#[cfg(feature = "approx")]
+impl<T> approx::UlpsEq for Matrix<T>
+    where
+        T: Scalar +
+        approx::UlpsEq,
+        T::Epsilon: Copy
+{
+    fn default_max_ulps() -> u32 {
+        T::default_max_ulps()
+    }
+
+    fn ulps_eq(&self, other: &Self, epsilon: Self::Epsilon, max_ulps: u32) -> bool {
+        self.rows == other.rows && self.cols == other.cols &&
+            self.data.iter().zip(other.data_in(self.layout).iter()).all(|(a, b)| a.ulps_eq(b, epsilon, max_ulps))
+    }
+}
@@ -0,0 +1,229 @@
+use crate::Matrix;
+
+/// The layout used when formatting a matrix.
+///
+/// - `Plain`: Rows on separate lines with right-aligned columns separated by spaces.
+/// - `NumPy`: Bracketed rows in the style of NumPy's `print`, e.g. `[[1 2]\n [3 4]]`.
+/// - `LaTeX`: A `bmatrix` environment with `&` between columns and `\\` between rows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PrintStyle {
+    Plain,
+    NumPy,
+    LaTeX,
+}
+
+/// Options controlling how a matrix is formatted by `Matrix::display_with`.
+///
+/// # Fields
+///
+/// - `style`: The layout of the output, see `PrintStyle`.
+/// - `precision`: The number of decimals of each element. If `None`, the precision of the
+///   formatter is used (e.g. `{:.3}`), or the default formatting of `T` if there is none.
+/// - `edge_items`: The number of rows and columns shown at the start and end of each
+///   dimension when the matrix is elided.
+/// - `threshold`: The number of elements above which the matrix is elided, showing only the
+///   first and last `edge_items` rows and columns, separated by `...`.
+///
+/// The default options are the plain style with an `edge_items` of 3 and a `threshold` of 1000,
+/// the same as NumPy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PrintOptions {
+    pub style: PrintStyle,
+    pub precision: Option<usize>,
+    pub edge_items: usize,
+    pub threshold: usize,
+}
+
+impl Default for PrintOptions {
+    fn default() -> Self {
+        PrintOptions {
+            style: PrintStyle::Plain,
+            precision: None,
+            edge_items: 3,
+            threshold: 1000,
+        }
+    }
+}
+
+/// A matrix paired with the `PrintOptions` used to format it, returned by
+/// `Matrix::display_with`.
+pub struct MatrixDisplay<'a, T>
+    where
+        T: std::fmt::Display
+{
+    matrix: &'a Matrix<T>,
+    options: PrintOptions,
+}
+
+/// Returns the indices shown along a dimension of length `len`, with `None` marking the
+/// position of the elided entries.
+fn visible_indices(len: usize, edge_items: usize, elide: bool) -> Vec<Option<usize>> {
+    if elide && len > 2 * edge_items {
+        (0..edge_items).map(Some)
+            .chain(std::iter::once(None))
+            .chain((len - edge_items..len).map(Some))
+            .collect()
+    } else {
+        (0..len).map(Some).collect()
+    }
+}
+
+/// Formats a matrix according to `PrintOptions`.
+///
+/// Columns are right-aligned to the width of their widest element, or to the width of the
+/// formatter (e.g. `{:8}`) if that is larger. In the LaTeX style, elided entries are written as
+/// `\cdots`, `\vdots` and `\ddots`.
+///
+/// # Examples
+///
+/// ```
+/// let mat = Matrix::from_vec(2, 2, vec![1.0, -2.5, 30.0, 4.0]);
+/// let options = PrintOptions { style: PrintStyle::LaTeX, ..PrintOptions::default() };
+/// println!("{:.1}", mat.display_with(options));
+/// // Output:
+/// // \begin{bmatrix}
+/// //  1.0 & -2.5 \\
+/// // 30.0 &  4.0
+/// // \end{bmatrix}
+/// ```
+impl<'a, T> std::fmt::Display for MatrixDisplay<'a, T>
+    where
+        T: std::fmt::Display
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let matrix = self.matrix;
+        let style = self.options.style;
+        let precision = self.options.precision.or(f.precision());
+        let elide = matrix.rows * matrix.cols > self.options.threshold;
+        let rows = visible_indices(matrix.rows, self.options.edge_items, elide);
+        let cols = visible_indices(matrix.cols, self.options.edge_items, elide);
+
+        let (h_ellipsis, v_ellipsis, d_ellipsis) = match style {
+            PrintStyle::LaTeX => ("\\cdots", "\\vdots", "\\ddots"),
+            _ => ("...", "...", "..."),
+        };
+        let cells: Vec<Vec<String>> = rows.iter().map(|row| {
+            cols.iter().map(|col| match (row, col) {
+                (Some(i), Some(j)) => match precision {
+                    Some(p) => format!("{:.*}", p, matrix[(*i, *j)]),
+                    None => format!("{}", matrix[(*i, *j)]),
+                },
+                (Some(_), None) => h_ellipsis.to_string(),
+                (None, Some(_)) => v_ellipsis.to_string(),
+                (None, None) => d_ellipsis.to_string(),
+            }).collect()
+        }).collect();
+
+        let min_width = f.width().unwrap_or(0);
+        let widths: Vec<usize> = (0..cols.len())
+            .map(|j| cells.iter().map(|row| row[j].chars().count()).max().unwrap_or(0).max(min_width))
+            .collect();
+        let align = |row: &Vec<String>, separator: &str| -> String {
+            row.iter().zip(widths.iter())
+                .map(|(cell, width)| format!("{:>1$}", cell, width))
+                .collect::<Vec<String>>()
+                .join(separator)
+        };
+
+        match style {
+            PrintStyle::Plain => {
+                for (row, index) in cells.iter().zip(rows.iter()) {
+                    match index {
+                        Some(_) => writeln!(f, "{}", align(row, " "))?,
+                        None if !cols.is_empty() => writeln!(f, "...")?,
+                        None => {}
+                    }
+                }
+                Ok(())
+            }
+            PrintStyle::NumPy => {
+                write!(f, "[")?;
+                for (idx, (row, index)) in cells.iter().zip(rows.iter()).enumerate() {
+                    if idx > 0 {
+                        write!(f, "\n ")?;
+                    }
+                    match index {
+                        Some(_) => write!(f, "[{}]", align(row, " "))?,
+                        None => write!(f, "...")?,
+                    }
+                }
+                write!(f, "]")
+            }
+            PrintStyle::LaTeX => {
+                writeln!(f, "\\begin{{bmatrix}}")?;
+                for (idx, row) in cells.iter().enumerate() {
+                    let end = if idx + 1 < cells.len() { " \\\\" } else { "" };
+                    writeln!(f, "{}{}", align(row, " & "), end)?;
+                }
+                write!(f, "\\end{{bmatrix}}")
+            }
+        }
+    }
+}
+
+
+/// Implements the `fmt::Display` trait for the `Matrix` struct, allowing matrices to be
+/// printed using the `println!` macro.
+///
+/// The matrix is printed row by row with right-aligned columns, and a newline after each row.
+/// The precision of the formatter is applied to each element, so `{:.3}` prints floats with
+/// three decimals. Matrices with more than 1000 elements are elided, showing only the first and
+/// last three rows and columns. The alternate form `{:#}` prints the matrix with NumPy-style
+/// brackets instead. Use `Matrix::display_with` for other styles and options.
+///
+/// # Examples
+///
+/// ```
+/// let mat = Matrix::from_vec(2, 2, vec![1, -2, 30, 4]);
+/// println!("{}", mat);
+/// // Output:
+/// //  1 -2
+/// // 30  4
+/// println!("{:#}", mat);
+/// // Output:
+/// // [[ 1 -2]
+/// //  [30  4]]
+/// ```
+impl<T> std::fmt::Display for Matrix<T>
+    where
+        T: std::fmt::Display
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let style = if f.alternate() { PrintStyle::NumPy } else { PrintStyle::Plain };
+        self.display_with(PrintOptions { style, ..PrintOptions::default() }).fmt(f)
+    }
+}
+
+
+/// Formatting and printing of matrices.
+///
+/// - `display_with` returns a value implementing `Display` that formats the matrix with the
+///   given `PrintOptions`.
+/// - `print` writes the matrix to the standard output, formatted as with `{}`.
+///
+/// # Examples
+///
+/// ```
+/// let mat = Matrix::<f64>::random_uniform(100, 100, 0.0, 1.0);
+/// let options = PrintOptions { edge_items: 2, precision: Some(2), ..PrintOptions::default() };
+/// println!("{}", mat.display_with(options));
+/// // Output:
+/// // 0.41 0.87 ... 0.11 0.52
+/// // 0.95 0.02 ... 0.63 0.38
+/// // ...
+/// // 0.27 0.79 ... 0.08 0.66
+/// // 0.13 0.45 ... 0.91 0.30
+/// ```
+impl<T> Matrix<T>
+    where
+        T: std::fmt::Display
+{
+    pub fn display_with(&self, options: PrintOptions) -> MatrixDisplay<'_, T> {
+        MatrixDisplay { matrix: self, options }
+    }
+
+    /// Prints the matrix to the console.
+    pub fn print(&self) {
+        print!("{}", self);
+    }
+}
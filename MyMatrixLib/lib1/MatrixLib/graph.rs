@@ -0,0 +1,373 @@
+//! Graph algorithms on adjacency matrices.
+//!
+//! A square `n x n` matrix `A` is read as a directed graph on the vertices `0..n`, with an edge
+//! from `i` to `j` for every non-zero `A[(i, j)]`, whose weight is `A[(i, j)]`. An undirected
+//! graph is a symmetric matrix. 0/1 matrices such as those of `Matrix::random_bernoulli` are
+//! unweighted graphs.
+//!
+//! Neighbours are always visited in increasing order, so the traversal orders are
+//! deterministic.
+//!
+//! # Panics
+//!
+//! All functions panic if the matrix is not square, and those taking a start vertex if it is
+//! not a vertex of the graph.
+//!
+//! # Examples
+//!
+//! ```
+//! use MyMatrixLib::graph::{bfs, connected_components, transitive_closure};
+//!
+//! // The path 0 -> 1 -> 2 and the isolated vertex 3
+//! let a = Matrix::from_vec(4, 4, vec![0, 1, 0, 0,
+//!                                     0, 0, 1, 0,
+//!                                     0, 0, 0, 0,
+//!                                     0, 0, 0, 0]);
+//! assert_eq!(bfs(&a, 0), vec![0, 1, 2]);
+//! assert_eq!(connected_components(&a), vec![0, 0, 0, 1]);
+//! assert_eq!(transitive_closure(&a)[(0, 2)], 1);
+//! ```
+
+use std::collections::VecDeque;
+
+use crate::{Matrix, Real, Scalar};
+
+/// Returns the vertices reachable from `start` in breadth-first order, i.e. by increasing
+/// number of edges from `start`.
+pub fn bfs<T>(adjacency: &Matrix<T>, start: usize) -> Vec<usize>
+    where
+        T: Scalar +
+        PartialEq
+{
+    let n = vertices(adjacency);
+    assert!(start < n, "Start vertex {} out of bounds for a graph with {} vertices", start, n);
+
+    let mut visited = vec![false; n];
+    let mut order = Vec::new();
+    let mut queue = VecDeque::from([start]);
+    visited[start] = true;
+    while let Some(vertex) = queue.pop_front() {
+        order.push(vertex);
+        for neighbour in neighbours(adjacency, vertex) {
+            if !visited[neighbour] {
+                visited[neighbour] = true;
+                queue.push_back(neighbour);
+            }
+        }
+    }
+    order
+}
+
+/// Returns the vertices reachable from `start` in depth-first preorder.
+///
+/// The traversal uses an explicit stack, so deep graphs do not overflow the call stack.
+pub fn dfs<T>(adjacency: &Matrix<T>, start: usize) -> Vec<usize>
+    where
+        T: Scalar +
+        PartialEq
+{
+    let n = vertices(adjacency);
+    assert!(start < n, "Start vertex {} out of bounds for a graph with {} vertices", start, n);
+
+    let mut visited = vec![false; n];
+    let mut order = Vec::new();
+    let mut stack = vec![start];
+    while let Some(vertex) = stack.pop() {
+        if visited[vertex] {
+            continue;
+        }
+        visited[vertex] = true;
+        order.push(vertex);
+        // Pushed in reverse so the smallest neighbour is visited first
+        let unvisited: Vec<usize> = neighbours(adjacency, vertex).filter(|&v| !visited[v]).collect();
+        stack.extend(unvisited.into_iter().rev());
+    }
+    order
+}
+
+/// Labels the connected components of the graph, ignoring the direction of the edges (i.e.
+/// the weakly connected components of a directed graph).
+///
+/// Returns the component of every vertex, numbered from `0` in the order of their smallest
+/// vertex, so the number of components is one more than the largest label.
+pub fn connected_components<T>(adjacency: &Matrix<T>) -> Vec<usize>
+    where
+        T: Scalar +
+        PartialEq
+{
+    let n = vertices(adjacency);
+    let mut labels = vec![usize::MAX; n];
+    let mut count = 0;
+    for root in 0..n {
+        if labels[root] != usize::MAX {
+            continue;
+        }
+        labels[root] = count;
+        let mut stack = vec![root];
+        while let Some(vertex) = stack.pop() {
+            for other in 0..n {
+                let connected = adjacency[(vertex, other)] != T::zero() || adjacency[(other, vertex)] != T::zero();
+                if connected && labels[other] == usize::MAX {
+                    labels[other] = count;
+                    stack.push(other);
+                }
+            }
+        }
+        count += 1;
+    }
+    labels
+}
+
+/// Computes the boolean product of two matrices: element `(i, j)` is one if `a[(i, k)]` and
+/// `b[(k, j)]` are both non-zero for some `k`, and zero otherwise.
+///
+/// For adjacency matrices, the product has an edge from `i` to `j` whenever there is a path
+/// from `i` to `j` made of an edge of `a` followed by an edge of `b`.
+///
+/// # Panics
+///
+/// This function will panic if the columns of `a` do not match the rows of `b`.
+pub fn boolean_product<T>(a: &Matrix<T>, b: &Matrix<T>) -> Matrix<T>
+    where
+        T: Scalar +
+        PartialEq
+{
+    assert_eq!(a.cols, b.rows, "Cannot multiply a {} x {} matrix by a {} x {} matrix", a.rows, a.cols, b.rows, b.cols);
+
+    let b_rows: Vec<Vec<bool>> = (0..b.rows).map(|k| b.row_elements(k).map(|x| x != T::zero()).collect()).collect();
+    let mut result = Matrix::zeros(a.rows, b.cols);
+    let mut row = vec![false; b.cols];
+    for i in 0..a.rows {
+        row.fill(false);
+        for (k, x) in a.row_elements(i).enumerate() {
+            if x != T::zero() {
+                row.iter_mut().zip(&b_rows[k]).for_each(|(r, &y)| *r |= y);
+            }
+        }
+        for (j, &reachable) in row.iter().enumerate() {
+            if reachable {
+                result[(i, j)] = T::one();
+            }
+        }
+    }
+    result
+}
+
+/// Computes the transitive closure of the graph: a 0/1 matrix with a one at `(i, j)` if there is
+/// a path of at least one edge from `i` to `j`. The diagonal is one exactly for the vertices on
+/// a cycle.
+///
+/// The closure `R` starts from the edges of the graph and is updated to `R + R * R` with boolean
+/// products, which doubles the length of the paths it covers, until it no longer changes. This
+/// takes at most about `log2(n)` products of cost `O(n^3)` each.
+pub fn transitive_closure<T>(adjacency: &Matrix<T>) -> Matrix<T>
+    where
+        T: Scalar +
+        PartialEq
+{
+    let n = vertices(adjacency);
+    // The product with the identity replaces the weights of the edges by ones
+    let mut closure = boolean_product(adjacency, &Matrix::identity(n));
+    loop {
+        let squared = boolean_product(&closure, &closure);
+        let mut changed = false;
+        for i in 0..n {
+            for j in 0..n {
+                if squared[(i, j)] != T::zero() && closure[(i, j)] == T::zero() {
+                    closure[(i, j)] = T::one();
+                    changed = true;
+                }
+            }
+        }
+        if !changed {
+            return closure;
+        }
+    }
+}
+
+/// Computes the all-pairs shortest path distances with the Floyd–Warshall algorithm, in
+/// `O(n^3)`.
+///
+/// Element `(i, j)` of the result is the smallest total weight of a path from `i` to `j`, `0`
+/// on the diagonal and infinity if `j` is not reachable from `i`. Weights may be negative.
+/// Zero (and infinite) elements of `weights` are not edges, so the weights of an unweighted
+/// 0/1 matrix count the edges of the paths.
+///
+/// Returns `None` if the graph has a cycle of negative total weight, for which shortest paths
+/// are not defined.
+///
+/// # Examples
+///
+/// ```
+/// let weights = Matrix::from_vec(3, 3, vec![0.0, 4.0, 1.0,
+///                                           0.0, 0.0, 0.0,
+///                                           0.0, 2.0, 0.0]);
+/// let distances = floyd_warshall(&weights).unwrap();
+/// assert_eq!(distances[(0, 1)], 3.0);
+/// assert_eq!(distances[(1, 0)], f64::INFINITY);
+/// ```
+pub fn floyd_warshall<T>(weights: &Matrix<T>) -> Option<Matrix<T>>
+    where
+        T: Real
+{
+    let n = vertices(weights);
+    let mut distances = Matrix::from_vec(n, n, vec![T::infinity(); n * n]);
+    for i in 0..n {
+        for (j, weight) in weights.row_elements(i).enumerate() {
+            if weight != T::zero() {
+                distances[(i, j)] = weight;
+            }
+        }
+        distances[(i, i)] = distances[(i, i)].min(T::zero());
+    }
+
+    for k in 0..n {
+        for i in 0..n {
+            let through = distances[(i, k)];
+            if through == T::infinity() {
+                continue;
+            }
+            for j in 0..n {
+                let candidate = through + distances[(k, j)];
+                if candidate < distances[(i, j)] {
+                    distances[(i, j)] = candidate;
+                }
+            }
+        }
+    }
+
+    if (0..n).any(|i| distances[(i, i)] < T::zero()) {
+        None
+    } else {
+        Some(distances)
+    }
+}
+
+/// Returns the diagonal matrix of the (weighted) out-degrees of the vertices, i.e. of the row
+/// sums of the adjacency matrix. For an undirected graph these are the degrees.
+///
+/// The degrees are summed in the element type `S` of the result, into which the weights are
+/// converted, so a `Matrix<u8>` of a large graph can give degrees in `u32` or `i32` instead of
+/// overflowing. Use `S = T` to keep the type of the adjacency matrix.
+pub fn degree_matrix<T, S>(adjacency: &Matrix<T>) -> Matrix<S>
+    where
+        T: Scalar +
+        PartialEq,
+        S: Scalar +
+        From<T>
+{
+    let n = vertices(adjacency);
+    let degrees = (0..n).map(|i| adjacency.row_elements(i).fold(S::zero(), |sum, x| sum + S::from(x))).collect();
+    Matrix::from_diag(&Matrix::from_vec(n, 1, degrees))
+}
+
+/// Returns the Laplacian matrix `L = D - A`, where `D` is the `degree_matrix` of `A`.
+///
+/// The off-diagonal entries of `L` are negative, so it is returned in a signed element type
+/// `S` into which the weights are converted, e.g. `i32` for the `Matrix<u8>` of
+/// `Matrix::random_bernoulli`. For an undirected graph, `L` is symmetric positive semidefinite
+/// and the multiplicity of its eigenvalue zero is the number of connected components.
+///
+/// # Examples
+///
+/// ```
+/// use MyMatrixLib::graph::laplacian;
+///
+/// let a = Matrix::<u8>::from_vec(2, 2, vec![0, 1, 1, 0]);
+/// let l: Matrix<i32> = laplacian(&a);
+/// assert_eq!(l, Matrix::from_vec(2, 2, vec![1, -1, -1, 1]));
+/// ```
+pub fn laplacian<T, S>(adjacency: &Matrix<T>) -> Matrix<S>
+    where
+        T: Scalar +
+        PartialEq,
+        S: Scalar +
+        num_traits::Signed +
+        From<T>
+{
+    let n = vertices(adjacency);
+    let mut laplacian = degree_matrix(adjacency);
+    for i in 0..n {
+        for j in 0..n {
+            laplacian[(i, j)] = laplacian[(i, j)] - S::from(adjacency[(i, j)]);
+        }
+    }
+    laplacian
+}
+
+/// Computes the PageRank of every vertex by power iteration, returning an `n x 1` probability
+/// vector.
+///
+/// A random surfer follows an out-edge of its vertex with probability `damping` (usually
+/// `0.85`), chosen in proportion to the edge weights, and otherwise jumps to a uniformly random
+/// vertex, as it always does from a vertex without out-edges. The ranks are the stationary
+/// distribution of this walk, iterated from the uniform distribution until the sum of the
+/// absolute changes falls below `tolerance`, or for at most `max_iterations` iterations.
+///
+/// # Panics
+///
+/// This function will panic if `damping` is not in `[0, 1]` or if a weight is negative.
+///
+/// # Examples
+///
+/// ```
+/// // Every vertex links to vertex 0
+/// let a = Matrix::from_vec(3, 3, vec![0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 1.0, 0.0, 0.0]);
+/// let ranks = pagerank(&a, 0.85, 1e-12, 100);
+/// assert!(ranks[(0, 0)] > ranks[(1, 0)]);
+/// ```
+pub fn pagerank<T>(adjacency: &Matrix<T>, damping: T, tolerance: T, max_iterations: usize) -> Matrix<T>
+    where
+        T: Real +
+        std::fmt::Display
+{
+    let n = vertices(adjacency);
+    assert!(damping >= T::zero() && damping <= T::one(), "Damping must be in [0, 1]");
+    assert!(adjacency.as_slice().iter().all(|&x| x >= T::zero()), "PageRank requires non-negative weights");
+    if n == 0 {
+        return Matrix::zeros(0, 1);
+    }
+
+    // Transposed transition matrix: column i holds the out-edges of vertex i, normalized
+    let out_degrees = degree_matrix::<T, T>(adjacency).diag();
+    let mut transition = Matrix::zeros(n, n);
+    for i in 0..n {
+        if out_degrees[(i, 0)] > T::zero() {
+            for (j, weight) in adjacency.row_elements(i).enumerate() {
+                transition[(j, i)] = weight / out_degrees[(i, 0)];
+            }
+        }
+    }
+
+    let size = T::from(n).unwrap();
+    let mut ranks = Matrix::from_vec(n, 1, vec![T::one() / size; n]);
+    for _ in 0..max_iterations {
+        let dangling = (0..n).filter(|&i| out_degrees[(i, 0)] == T::zero())
+            .fold(T::zero(), |sum, i| sum + ranks[(i, 0)]);
+        let teleport = (T::one() - damping + damping * dangling) / size;
+        let next = (transition.clone() * ranks.clone()).scale(damping) + Matrix::from_vec(n, 1, vec![teleport; n]);
+        let change = (0..n).fold(T::zero(), |sum, i| sum + (next[(i, 0)] - ranks[(i, 0)]).abs());
+        ranks = next;
+        if change < tolerance {
+            break;
+        }
+    }
+    ranks
+}
+
+fn vertices<T>(adjacency: &Matrix<T>) -> usize
+    where
+        T: Scalar
+{
+    assert_eq!(adjacency.rows, adjacency.cols,
+               "Adjacency matrix must be square, got {} x {}", adjacency.rows, adjacency.cols);
+    adjacency.rows
+}
+
+fn neighbours<T>(adjacency: &Matrix<T>, vertex: usize) -> impl Iterator<Item=usize> + '_
+    where
+        T: Scalar +
+        PartialEq
+{
+    adjacency.row_elements(vertex).enumerate().filter(|(_, x)| *x != T::zero()).map(|(j, _)| j)
+}
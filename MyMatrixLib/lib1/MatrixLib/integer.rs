@@ -0,0 +1,322 @@
+use crate::{Matrix, Scalar};
+
+/// Elementary row and column operations used by the exact elimination algorithms.
+impl<T> Matrix<T>
+    where
+        T: Clone +
+        num_traits::Zero +
+        std::ops::Sub<Output=T> +
+        std::ops::Mul<Output=T>
+{
+    pub(crate) fn swap_rows(&mut self, a: usize, b: usize) {
+        if a != b {
+            for j in 0..self.cols {
+                let (x, y) = (self.offset(a, j), self.offset(b, j));
+                self.data.swap(x, y);
+            }
+        }
+    }
+
+    pub(crate) fn swap_cols(&mut self, a: usize, b: usize) {
+        if a != b {
+            for i in 0..self.rows {
+                let (x, y) = (self.offset(i, a), self.offset(i, b));
+                self.data.swap(x, y);
+            }
+        }
+    }
+
+    /// Adds `factor` times row `source` to row `target`.
+    pub(crate) fn add_row_multiple(&mut self, target: usize, source: usize, factor: T) {
+        for j in 0..self.cols {
+            let val = self[(source, j)].clone();
+            self[(target, j)] = self[(target, j)].clone() + factor.clone() * val;
+        }
+    }
+
+    /// Adds `factor` times column `source` to column `target`.
+    pub(crate) fn add_col_multiple(&mut self, target: usize, source: usize, factor: T) {
+        for i in 0..self.rows {
+            let val = self[(i, source)].clone();
+            self[(i, target)] = self[(i, target)].clone() + factor.clone() * val;
+        }
+    }
+
+    /// Negates every element of row `row`.
+    fn negate_row(&mut self, row: usize) {
+        for j in 0..self.cols {
+            self[(row, j)] = T::zero() - self[(row, j)].clone();
+        }
+    }
+}
+
+/// Integer division rounding to the nearest integer, so the remainder is at most half the
+/// divisor in absolute value.
+fn round_div<T>(value: T, divisor: T) -> T
+    where
+        T: num_traits::PrimInt + num_traits::Signed
+{
+    let quotient = value / divisor;
+    let remainder = value - quotient * divisor;
+    if remainder.abs() + remainder.abs() > divisor.abs() {
+        if (remainder < T::zero()) == (divisor < T::zero()) { quotient + T::one() } else { quotient - T::one() }
+    } else {
+        quotient
+    }
+}
+
+/// Integer division rounding towards negative infinity, for a positive `divisor`.
+fn floor_div<T>(value: T, divisor: T) -> T
+    where
+        T: num_traits::PrimInt + num_traits::Signed
+{
+    let quotient = value / divisor;
+    if value % divisor < T::zero() { quotient - T::one() } else { quotient }
+}
+
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+////////////////////////////////////// INTEGER MATRIX METHODS /////////////////////////////////////
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+
+/// Exact algorithms for matrices of signed integers (`i8` to `i128`).
+///
+/// Unlike `LU_decompose`, which uses truncating division on integer types, these methods only
+/// use exact integer arithmetic.
+///
+/// - `determinant_bareiss` computes the determinant with Bareiss' fraction-free elimination, in
+///   which every division is exact and the intermediate values are minors of the matrix, so
+///   they stay as small as the determinant allows.
+/// - `hermite_normal_form` returns `(H, U)` with `U * A = H`, where `U` is unimodular (an
+///   integer matrix with determinant ±1) and `H` is in row-style Hermite normal form: in row
+///   echelon form with positive pivots, the entries above each pivot in `0..pivot` and the zero
+///   rows at the bottom.
+/// - `smith_normal_form` returns `(U, S, V)` with `U * A * V = S`, where `U` and `V` are
+///   unimodular and `S` is diagonal with non-negative entries `d1, d2, ...` such that each
+///   divides the next.
+///
+/// # Panics
+///
+/// `determinant_bareiss` will panic if the matrix is not square. All methods may overflow if
+/// the entries of the results do not fit in `T`. Note that the transforms `U` and `V` are not
+/// unique, and their entries can be much larger than those of the matrix (in particular the
+/// rows of `U` spanning the left null space of a matrix with more rows than columns), so use a
+/// wide type such as `i128` for larger matrices.
+///
+/// # Examples
+///
+/// ```
+/// let a = Matrix::from_vec(2, 2, vec![2_i64, 4, 6, 8]);
+/// assert_eq!(a.determinant_bareiss(), -8);
+/// let (u, s, v) = a.smith_normal_form();
+/// assert_eq!(s, Matrix::from_vec(2, 2, vec![2, 0, 0, 4]));
+/// assert_eq!(u * a * v, s);
+/// ```
+impl<T> Matrix<T>
+    where
+        T: Scalar +
+        num_traits::PrimInt +
+        num_traits::Signed
+{
+    pub fn determinant_bareiss(&self) -> T {
+        assert_eq!(self.rows, self.cols, "Determinant requires a square matrix");
+
+        let n = self.rows;
+        if n == 0 {
+            return T::one();
+        }
+        let mut m = self.clone();
+        let mut sign = T::one();
+        let mut previous_pivot = T::one();
+        for k in 0..n - 1 {
+            if m[(k, k)].is_zero() {
+                match ((k + 1)..n).find(|&i| !m[(i, k)].is_zero()) {
+                    Some(pivot) => {
+                        m.swap_rows(k, pivot);
+                        sign = -sign;
+                    }
+                    None => return T::zero(),
+                }
+            }
+            for i in (k + 1)..n {
+                for j in (k + 1)..n {
+                    m[(i, j)] = (m[(i, j)] * m[(k, k)] - m[(i, k)] * m[(k, j)]) / previous_pivot;
+                }
+            }
+            previous_pivot = m[(k, k)];
+        }
+        sign * m[(n - 1, n - 1)]
+    }
+
+    pub fn hermite_normal_form(&self) -> (Matrix<T>, Matrix<T>) {
+        let mut h = self.clone();
+        let mut u = Matrix::identity(self.rows);
+        let mut pivot_row = 0;
+        for col in 0..self.cols {
+            if pivot_row == self.rows {
+                break;
+            }
+            // Euclid's algorithm on the column: move the smallest non-zero entry up and reduce
+            // the entries below it until only the pivot (their gcd) is left
+            loop {
+                let smallest = (pivot_row..self.rows)
+                    .filter(|&i| !h[(i, col)].is_zero())
+                    .min_by_key(|&i| h[(i, col)].abs());
+                let Some(smallest) = smallest else {
+                    break;
+                };
+                h.swap_rows(pivot_row, smallest);
+                u.swap_rows(pivot_row, smallest);
+
+                let pivot = h[(pivot_row, col)];
+                let mut done = true;
+                for i in (pivot_row + 1)..self.rows {
+                    let q = round_div(h[(i, col)], pivot);
+                    if !q.is_zero() {
+                        h.add_row_multiple(i, pivot_row, -q);
+                        u.add_row_multiple(i, pivot_row, -q);
+                    }
+                    done &= h[(i, col)].is_zero();
+                }
+                if done {
+                    break;
+                }
+            }
+            let pivot = h[(pivot_row, col)];
+            if pivot.is_zero() {
+                continue;
+            }
+            if pivot < T::zero() {
+                h.negate_row(pivot_row);
+                u.negate_row(pivot_row);
+            }
+
+            // Reduce the entries above the pivot
+            let pivot = h[(pivot_row, col)];
+            for i in 0..pivot_row {
+                let q = floor_div(h[(i, col)], pivot);
+                if !q.is_zero() {
+                    h.add_row_multiple(i, pivot_row, -q);
+                    u.add_row_multiple(i, pivot_row, -q);
+                }
+            }
+            pivot_row += 1;
+        }
+        (h, u)
+    }
+
+    pub fn smith_normal_form(&self) -> (Matrix<T>, Matrix<T>, Matrix<T>) {
+        let (m, n) = (self.rows, self.cols);
+        let mut s = self.clone();
+        let mut u = Matrix::identity(m);
+        let mut v = Matrix::identity(n);
+
+        for t in 0..m.min(n) {
+            loop {
+                // Move the smallest non-zero entry of the remaining block to (t, t)
+                let mut smallest = None;
+                for i in t..m {
+                    for j in t..n {
+                        let val = s[(i, j)].abs();
+                        if !val.is_zero() && smallest.is_none_or(|(_, _, min)| val < min) {
+                            smallest = Some((i, j, val));
+                        }
+                    }
+                }
+                let Some((i, j, _)) = smallest else {
+                    return (u, s, v);
+                };
+                s.swap_rows(t, i);
+                u.swap_rows(t, i);
+                s.swap_cols(t, j);
+                v.swap_cols(t, j);
+
+                // Reduce the pivot row and column; non-zero remainders are smaller than the pivot
+                let pivot = s[(t, t)];
+                let mut done = true;
+                for i in (t + 1)..m {
+                    let q = round_div(s[(i, t)], pivot);
+                    s.add_row_multiple(i, t, -q);
+                    u.add_row_multiple(i, t, -q);
+                    done &= s[(i, t)].is_zero();
+                }
+                for j in (t + 1)..n {
+                    let q = round_div(s[(t, j)], pivot);
+                    s.add_col_multiple(j, t, -q);
+                    v.add_col_multiple(j, t, -q);
+                    done &= s[(t, j)].is_zero();
+                }
+                if !done {
+                    continue;
+                }
+
+                // The pivot must divide every remaining entry; if not, bring that row in
+                let not_divisible = (t + 1..m).find(|&i| (t + 1..n).any(|j| !(s[(i, j)] % pivot).is_zero()));
+                match not_divisible {
+                    Some(i) => {
+                        s.add_row_multiple(t, i, T::one());
+                        u.add_row_multiple(t, i, T::one());
+                    }
+                    None => break,
+                }
+            }
+            if s[(t, t)] < T::zero() {
+                s.negate_row(t);
+                u.negate_row(t);
+            }
+        }
+        (u, s, v)
+    }
+}
+
+
+/// Returns the rank of the matrix over GF(2), taking each element modulo 2 (its lowest bit).
+///
+/// The rows are packed into 64-bit words and eliminated with XOR, so large binary matrices
+/// (e.g. parity check matrices of codes) are handled efficiently.
+///
+/// # Examples
+///
+/// ```
+/// // The parity check matrix of the [7, 4] Hamming code
+/// let h = Matrix::from_vec(3, 7, vec![1_u8, 0, 1, 0, 1, 0, 1,
+///                                     0, 1, 1, 0, 0, 1, 1,
+///                                     0, 0, 0, 1, 1, 1, 1]);
+/// assert_eq!(h.rank_gf2(), 3);
+/// ```
+impl<T> Matrix<T>
+    where
+        T: Scalar +
+        num_traits::PrimInt
+{
+    pub fn rank_gf2(&self) -> usize {
+        let words = self.cols.div_ceil(64);
+        let mut rows: Vec<Vec<u64>> = (0..self.rows).map(|i| {
+            let mut packed = vec![0_u64; words];
+            for (j, val) in self.row_elements(i).enumerate() {
+                if (val & T::one()) == T::one() {
+                    packed[j / 64] |= 1 << (j % 64);
+                }
+            }
+            packed
+        }).collect();
+
+        let mut rank = 0;
+        for col in 0..self.cols {
+            let (word, bit) = (col / 64, 1_u64 << (col % 64));
+            let Some(pivot) = (rank..self.rows).find(|&i| rows[i][word] & bit != 0) else {
+                continue;
+            };
+            rows.swap(rank, pivot);
+            let (upper, lower) = rows.split_at_mut(rank + 1);
+            for row in lower.iter_mut().filter(|row| row[word] & bit != 0) {
+                for (val, pivot_val) in row[word..].iter_mut().zip(upper[rank][word..].iter()) {
+                    *val ^= pivot_val;
+                }
+            }
+            rank += 1;
+        }
+        rank
+    }
+}
@@ -0,0 +1,757 @@
+use crate::{Layout, Matrix, Scalar};
+use std::io::{BufRead, Read, Write};
+
+/// An element type with a fixed binary layout that can be stored in the NumPy `.npy` and compact
+/// binary formats.
+///
+/// Implemented for `i8`, `i16`, `i32`, `i64`, `u8`, `u16`, `u32`, `u64`, `f32` and `f64`.
+///
+/// # Associated constants
+///
+/// - `SIZE`: The number of bytes of one element.
+/// - `NPY_DESCR`: The NumPy dtype descriptor of the little-endian element, e.g. `"<f8"`.
+/// - `TYPE_CODE`: The tag identifying the element type in the compact binary format.
+pub trait BinaryElement: Sized {
+    const SIZE: usize;
+    const NPY_DESCR: &'static str;
+    const TYPE_CODE: u8;
+
+    /// Appends the little-endian bytes of the element to `buffer`.
+    fn write_le(&self, buffer: &mut Vec<u8>);
+
+    /// Reads an element from exactly `Self::SIZE` little-endian bytes.
+    fn read_le(bytes: &[u8]) -> Self;
+}
+
+macro_rules! impl_binary_element {
+    ($($t:ty => $descr:expr, $code:expr);* $(;)?) => {
+        $(
+            impl BinaryElement for $t {
+                const SIZE: usize = std::mem::size_of::<$t>();
+                const NPY_DESCR: &'static str = $descr;
+                const TYPE_CODE: u8 = $code;
+
+                fn write_le(&self, buffer: &mut Vec<u8>) {
+                    buffer.extend_from_slice(&self.to_le_bytes());
+                }
+
+                fn read_le(bytes: &[u8]) -> Self {
+                    <$t>::from_le_bytes(bytes.try_into().unwrap())
+                }
+            }
+        )*
+    };
+}
+
+impl_binary_element! {
+    i8 => "|i1", 1;
+    i16 => "<i2", 2;
+    i32 => "<i4", 3;
+    i64 => "<i8", 4;
+    u8 => "|u1", 5;
+    u16 => "<u2", 6;
+    u32 => "<u4", 7;
+    u64 => "<u8", 8;
+    f32 => "<f4", 9;
+    f64 => "<f8", 10;
+}
+
+/// An element type that can be written in the Matrix Market exchange format.
+///
+/// Implemented for `i8`, `i16`, `i32`, `i64`, `u8`, `u16`, `u32`, `u64`, `f32` and `f64`.
+///
+/// # Associated constants
+///
+/// - `MTX_FIELD`: The Matrix Market field of the element type, `"integer"` or `"real"`.
+pub trait MatrixMarketElement {
+    const MTX_FIELD: &'static str;
+}
+
+macro_rules! impl_matrix_market_element {
+    ($field:expr => $($t:ty),*) => {
+        $(
+            impl MatrixMarketElement for $t {
+                const MTX_FIELD: &'static str = $field;
+            }
+        )*
+    };
+}
+
+impl_matrix_market_element!("integer" => i8, i16, i32, i64, u8, u16, u32, u64);
+impl_matrix_market_element!("real" => f32, f64);
+
+fn invalid_data(message: String) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidData, message)
+}
+
+/// Reads exactly `len` bytes from `reader`. The buffer grows with the bytes actually read, so
+/// a bogus length taken from a file header cannot allocate more than the file holds.
+fn read_bytes<R: Read>(reader: &mut R, len: usize) -> std::io::Result<Vec<u8>> {
+    let mut bytes = Vec::new();
+    reader.take(len as u64).read_to_end(&mut bytes)?;
+    if bytes.len() != len {
+        return Err(std::io::Error::new(std::io::ErrorKind::UnexpectedEof,
+                                       format!("Expected {} bytes, found {}", len, bytes.len())));
+    }
+    Ok(bytes)
+}
+
+/// Reads `rows * cols` little-endian elements from `reader`.
+fn read_elements<T, R>(reader: &mut R, rows: usize, cols: usize) -> std::io::Result<Vec<T>>
+    where
+        T: BinaryElement,
+        R: Read
+{
+    let len = rows.checked_mul(cols)
+        .and_then(|count| count.checked_mul(T::SIZE))
+        .ok_or_else(|| invalid_data(format!("Matrix dimensions {} x {} are too large", rows, cols)))?;
+    let bytes = read_bytes(reader, len)?;
+    Ok(bytes.chunks_exact(T::SIZE).map(T::read_le).collect())
+}
+
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+////////////////////////////////////// NUMPY .NPY FORMAT //////////////////////////////////////////
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+
+const NPY_MAGIC: &[u8] = b"\x93NUMPY";
+
+/// Extracts the value following `'key':` in a NumPy header dictionary, up to the next
+/// top-level comma or the closing brace.
+fn npy_header_value<'a>(header: &'a str, key: &str) -> Option<&'a str> {
+    let start = header.find(&format!("'{}'", key))? + key.len() + 2;
+    let rest = header[start..].trim_start().strip_prefix(':')?.trim_start();
+    let mut depth = 0;
+    for (idx, ch) in rest.char_indices() {
+        match ch {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            ',' | '}' if depth == 0 => return Some(rest[..idx].trim()),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Writes and reads matrices in the NumPy `.npy` format (version 1.0), so they can be exchanged
+/// with Python via `numpy.save` and `numpy.load`.
+///
+/// A matrix is written as a 2-D array of shape `(rows, cols)`, C-ordered for row-major and
+/// Fortran-ordered for column-major matrices, so the buffer is written as is. When reading,
+/// C- and Fortran-ordered arrays are both accepted and become row-major and column-major
+/// matrices respectively, and a 1-D array of shape `(n,)` becomes a `1 x n` row vector. The
+/// dtype stored in the file must match `T` exactly (e.g. `<f8` for `f64`); no conversion
+/// between element types is performed.
+///
+/// # Errors
+///
+/// Reading returns an error of kind `InvalidData` if the file is not a valid `.npy` file, has a
+/// dtype other than `T::NPY_DESCR`, does not have one or two dimensions or has dimensions that
+/// overflow, and of kind `UnexpectedEof` if the file is shorter than its header announces. Any
+/// error of the underlying reader or writer is passed on.
+///
+/// # Examples
+///
+/// ```
+/// let mat = Matrix::from_vec(2, 2, vec![1.0, 2.0, 3.0, 4.0]);
+/// mat.save_npy("mat.npy")?;
+/// let loaded = Matrix::<f64>::load_npy("mat.npy")?;
+/// assert_eq!(mat, loaded);
+/// ```
+impl<T> Matrix<T>
+    where
+        T: Scalar +
+        BinaryElement
+{
+    pub fn write_npy<W: Write>(&self, mut writer: W) -> std::io::Result<()> {
+        let mut header = format!(
+            "{{'descr': '{}', 'fortran_order': {}, 'shape': ({}, {}), }}",
+            T::NPY_DESCR, if self.layout == Layout::ColumnMajor { "True" } else { "False" }, self.rows, self.cols
+        );
+        // Pad with spaces so the data starts on a 64 byte boundary, terminated by a newline
+        let unpadded = NPY_MAGIC.len() + 2 + 2 + header.len() + 1;
+        header.push_str(&" ".repeat((64 - unpadded % 64) % 64));
+        header.push('\n');
+
+        let mut buffer = Vec::with_capacity(NPY_MAGIC.len() + 4 + header.len() + self.data.len() * T::SIZE);
+        buffer.extend_from_slice(NPY_MAGIC);
+        buffer.extend_from_slice(&[1, 0]);
+        buffer.extend_from_slice(&(header.len() as u16).to_le_bytes());
+        buffer.extend_from_slice(header.as_bytes());
+        for val in self.data.iter() {
+            val.write_le(&mut buffer);
+        }
+        writer.write_all(&buffer)
+    }
+
+    pub fn read_npy<R: Read>(mut reader: R) -> std::io::Result<Matrix<T>> {
+        let mut preamble = [0_u8; 8];
+        reader.read_exact(&mut preamble)?;
+        if &preamble[..6] != NPY_MAGIC {
+            return Err(invalid_data("Not a .npy file: missing magic string".to_string()));
+        }
+        let header_len = match preamble[6] {
+            1 => {
+                let mut len = [0_u8; 2];
+                reader.read_exact(&mut len)?;
+                u16::from_le_bytes(len) as usize
+            }
+            2 | 3 => {
+                let mut len = [0_u8; 4];
+                reader.read_exact(&mut len)?;
+                u32::from_le_bytes(len) as usize
+            }
+            version => return Err(invalid_data(format!("Unsupported .npy format version {}", version))),
+        };
+        let header = read_bytes(&mut reader, header_len)?;
+        let header = String::from_utf8(header).map_err(|_| invalid_data("Header is not valid text".to_string()))?;
+
+        let descr = npy_header_value(&header, "descr")
+            .ok_or_else(|| invalid_data("Header has no 'descr' entry".to_string()))?
+            .trim_matches(|c| c == '\'' || c == '"');
+        // Single byte types are written with either byte order marker
+        let expected = T::NPY_DESCR;
+        if descr != expected && !(T::SIZE == 1 && descr.get(1..) == expected.get(1..) && descr.starts_with(['<', '>', '|'])) {
+            return Err(invalid_data(format!("Expected dtype '{}', found '{}'", expected, descr)));
+        }
+
+        let fortran_order = match npy_header_value(&header, "fortran_order") {
+            Some("True") => true,
+            Some("False") => false,
+            _ => return Err(invalid_data("Header has no valid 'fortran_order' entry".to_string())),
+        };
+
+        let shape: Vec<usize> = npy_header_value(&header, "shape")
+            .ok_or_else(|| invalid_data("Header has no 'shape' entry".to_string()))?
+            .trim_matches(|c| c == '(' || c == ')')
+            .split(',')
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(|s| s.parse::<usize>().map_err(|_| invalid_data(format!("Invalid dimension '{}' in shape", s))))
+            .collect::<std::io::Result<_>>()?;
+        let (rows, cols) = match shape[..] {
+            [n] => (1, n),
+            [rows, cols] => (rows, cols),
+            _ => return Err(invalid_data(format!("Expected a 1-D or 2-D array, found {} dimensions", shape.len()))),
+        };
+
+        let data = read_elements(&mut reader, rows, cols)?;
+        let layout = if fortran_order { Layout::ColumnMajor } else { Layout::RowMajor };
+        Ok(Matrix { rows, cols, data, layout })
+    }
+
+    pub fn save_npy<P: AsRef<std::path::Path>>(&self, path: P) -> std::io::Result<()> {
+        self.write_npy(std::io::BufWriter::new(std::fs::File::create(path)?))
+    }
+
+    pub fn load_npy<P: AsRef<std::path::Path>>(path: P) -> std::io::Result<Matrix<T>> {
+        Self::read_npy(std::io::BufReader::new(std::fs::File::open(path)?))
+    }
+}
+
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+////////////////////////////////////// COMPACT BINARY FORMAT //////////////////////////////////////
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+
+const BINARY_MAGIC: &[u8] = b"MMAT";
+const BINARY_VERSION: u8 = 1;
+
+/// Writes and reads matrices in a compact little-endian binary format, for caching
+/// intermediate results on disk.
+///
+/// The layout is a 22 byte header followed by the elements in row-major order:
+///
+/// | Offset | Size | Content                                       |
+/// |--------|------|-----------------------------------------------|
+/// | 0      | 4    | Magic bytes `MMAT`                            |
+/// | 4      | 1    | Format version (currently `1`)                |
+/// | 5      | 1    | Element type code (`BinaryElement::TYPE_CODE`) |
+/// | 6      | 8    | Number of rows, `u64` little-endian           |
+/// | 14     | 8    | Number of columns, `u64` little-endian        |
+/// | 22     | ...  | `rows * cols` little-endian elements          |
+///
+/// # Errors
+///
+/// Reading returns an error of kind `InvalidData` if the magic bytes, version or element type
+/// do not match or the dimensions overflow, and of kind `UnexpectedEof` if the file holds fewer
+/// elements than its dimensions. Any error of the underlying reader or writer is passed on.
+///
+/// # Examples
+///
+/// ```
+/// let mat = Matrix::<i32>::identity(3);
+/// let mut bytes = Vec::new();
+/// mat.write_binary(&mut bytes)?;
+/// assert_eq!(Matrix::<i32>::read_binary(&bytes[..])?, mat);
+/// ```
+impl<T> Matrix<T>
+    where
+        T: Scalar +
+        BinaryElement
+{
+    pub fn write_binary<W: Write>(&self, mut writer: W) -> std::io::Result<()> {
+        let mut buffer = Vec::with_capacity(22 + self.data.len() * T::SIZE);
+        buffer.extend_from_slice(BINARY_MAGIC);
+        buffer.push(BINARY_VERSION);
+        buffer.push(T::TYPE_CODE);
+        buffer.extend_from_slice(&(self.rows as u64).to_le_bytes());
+        buffer.extend_from_slice(&(self.cols as u64).to_le_bytes());
+        for val in self.row_major().iter() {
+            val.write_le(&mut buffer);
+        }
+        writer.write_all(&buffer)
+    }
+
+    pub fn read_binary<R: Read>(mut reader: R) -> std::io::Result<Matrix<T>> {
+        let mut header = [0_u8; 22];
+        reader.read_exact(&mut header)?;
+        if &header[..4] != BINARY_MAGIC {
+            return Err(invalid_data("Not a matrix file: missing magic bytes".to_string()));
+        }
+        if header[4] != BINARY_VERSION {
+            return Err(invalid_data(format!("Unsupported matrix file version {}", header[4])));
+        }
+        if header[5] != T::TYPE_CODE {
+            return Err(invalid_data(format!("Expected element type code {}, found {}", T::TYPE_CODE, header[5])));
+        }
+        let dimension = |bytes: &[u8]| {
+            let value = u64::from_le_bytes(bytes.try_into().unwrap());
+            usize::try_from(value).map_err(|_| invalid_data(format!("Dimension {} is too large", value)))
+        };
+        let (rows, cols) = (dimension(&header[6..14])?, dimension(&header[14..22])?);
+
+        let data = read_elements(&mut reader, rows, cols)?;
+        Ok(Matrix { rows, cols, data, layout: Layout::RowMajor })
+    }
+
+    pub fn save_binary<P: AsRef<std::path::Path>>(&self, path: P) -> std::io::Result<()> {
+        self.write_binary(std::io::BufWriter::new(std::fs::File::create(path)?))
+    }
+
+    pub fn load_binary<P: AsRef<std::path::Path>>(path: P) -> std::io::Result<Matrix<T>> {
+        Self::read_binary(std::io::BufReader::new(std::fs::File::open(path)?))
+    }
+}
+
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+////////////////////////////////////// TEXT FORMATS ///////////////////////////////////////////////
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+
+/// The error returned when reading a matrix from a text format (CSV or Matrix Market) fails.
+///
+/// Line and column numbers are 1-based. Columns count delimited fields in CSV files and
+/// whitespace separated tokens in Matrix Market files.
+#[derive(Debug)]
+pub enum ParseError {
+    /// The underlying reader failed.
+    Io(std::io::Error),
+    /// A row has a different number of fields than the rows before it (or the header).
+    RaggedRow { line: usize, expected: usize, found: usize },
+    /// A field could not be parsed as the element type of the matrix.
+    InvalidValue { line: usize, column: usize, value: String },
+    /// The file structure is invalid, e.g. a malformed Matrix Market header or size line.
+    InvalidFormat { line: usize, message: String },
+}
+
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            ParseError::Io(err) => write!(f, "{}", err),
+            ParseError::RaggedRow { line, expected, found } =>
+                write!(f, "line {}: expected {} columns, found {}", line, expected, found),
+            ParseError::InvalidValue { line, column, value } =>
+                write!(f, "line {}, column {}: cannot parse '{}'", line, column, value),
+            ParseError::InvalidFormat { line, message } =>
+                write!(f, "line {}: {}", line, message),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ParseError::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for ParseError {
+    fn from(err: std::io::Error) -> Self {
+        ParseError::Io(err)
+    }
+}
+
+fn parse_value<T: std::str::FromStr>(token: &str, line: usize, column: usize) -> Result<T, ParseError> {
+    token.parse::<T>().map_err(|_| ParseError::InvalidValue { line, column, value: token.to_string() })
+}
+
+
+/// Reads and writes matrices as delimited text (CSV), one matrix row per line.
+///
+/// - `from_csv` reads a matrix, skipping the first line if `has_header` is `true`.
+/// - `from_csv_with_header` treats the first line as a header and returns its fields together
+///   with the matrix.
+/// - `to_csv` and `to_csv_with_header` write the matrix, optionally preceded by a header line.
+///
+/// Fields are separated by `delimiter` and surrounding whitespace is ignored, so `1, 2, 3` and
+/// `1,2,3` are equivalent. Blank lines are skipped. Header fields may be enclosed in double
+/// quotes, which are removed; quoting is not supported for values.
+///
+/// # Errors
+///
+/// Reading returns `ParseError::RaggedRow` if a row has a different number of fields than the
+/// first row (or the header), and `ParseError::InvalidValue` if a field cannot be parsed as
+/// `T`, both with the offending line and column.
+///
+/// # Panics
+///
+/// `to_csv_with_header` will panic if the header does not have one field per column.
+///
+/// # Examples
+///
+/// ```
+/// let text = "x;y\n1.5;2\n3;4.25\n";
+/// let (header, mat) = Matrix::<f64>::from_csv_with_header(text.as_bytes(), ';')?;
+/// assert_eq!(header, vec!["x", "y"]);
+/// assert_eq!(mat, Matrix::from_vec(2, 2, vec![1.5, 2.0, 3.0, 4.25]));
+/// ```
+impl<T> Matrix<T>
+    where
+        T: Scalar +
+        std::fmt::Display +
+        std::str::FromStr
+{
+    pub fn from_csv<R: Read>(reader: R, delimiter: char, has_header: bool) -> Result<Matrix<T>, ParseError> {
+        Self::read_csv(reader, delimiter, has_header).map(|(_, matrix)| matrix)
+    }
+
+    pub fn from_csv_with_header<R: Read>(reader: R, delimiter: char) -> Result<(Vec<String>, Matrix<T>), ParseError> {
+        Self::read_csv(reader, delimiter, true)
+    }
+
+    fn read_csv<R: Read>(reader: R, delimiter: char, has_header: bool) -> Result<(Vec<String>, Matrix<T>), ParseError> {
+        let mut header = Vec::new();
+        let mut expected_cols = None;
+        let mut rows = 0;
+        let mut data = Vec::new();
+
+        for (idx, line) in std::io::BufReader::new(reader).lines().enumerate() {
+            let line = line?;
+            let line_number = idx + 1;
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            if has_header && expected_cols.is_none() && header.is_empty() {
+                header = line.split(delimiter).map(|field| field.trim().trim_matches('"').to_string()).collect();
+                expected_cols = Some(header.len());
+                continue;
+            }
+
+            let mut found = 0;
+            for (col, field) in line.split(delimiter).enumerate() {
+                data.push(parse_value(field.trim(), line_number, col + 1)?);
+                found += 1;
+            }
+            match expected_cols {
+                None => expected_cols = Some(found),
+                Some(expected) if expected != found => {
+                    return Err(ParseError::RaggedRow { line: line_number, expected, found });
+                }
+                Some(_) => {}
+            }
+            rows += 1;
+        }
+
+        let cols = if rows == 0 { 0 } else { expected_cols.unwrap_or(0) };
+        Ok((header, Matrix { rows, cols, data, layout: Layout::RowMajor }))
+    }
+
+    pub fn to_csv<W: Write>(&self, writer: W, delimiter: char) -> std::io::Result<()> {
+        self.write_csv(writer, delimiter, None)
+    }
+
+    pub fn to_csv_with_header<W: Write>(&self, writer: W, delimiter: char, header: &[&str]) -> std::io::Result<()> {
+        assert_eq!(header.len(), self.cols, "Header has {} fields, but the matrix has {} columns", header.len(), self.cols);
+
+        self.write_csv(writer, delimiter, Some(header))
+    }
+
+    fn write_csv<W: Write>(&self, writer: W, delimiter: char, header: Option<&[&str]>) -> std::io::Result<()> {
+        let mut writer = std::io::BufWriter::new(writer);
+        let separator = delimiter.to_string();
+        if let Some(header) = header {
+            writeln!(writer, "{}", header.join(&separator))?;
+        }
+        for i in 0..self.rows {
+            let fields: Vec<String> = self.row_elements(i).map(|val| val.to_string()).collect();
+            writeln!(writer, "{}", fields.join(&separator))?;
+        }
+        writer.flush()
+    }
+
+    pub fn load_csv<P: AsRef<std::path::Path>>(path: P, delimiter: char, has_header: bool) -> Result<Matrix<T>, ParseError> {
+        Self::from_csv(std::fs::File::open(path)?, delimiter, has_header)
+    }
+
+    pub fn save_csv<P: AsRef<std::path::Path>>(&self, path: P, delimiter: char) -> std::io::Result<()> {
+        self.to_csv(std::fs::File::create(path)?, delimiter)
+    }
+}
+
+
+/// Reads and writes matrices in the Matrix Market exchange format (`.mtx`).
+///
+/// `read_matrix_market` accepts both variants of the format:
+///
+/// - `coordinate` (sparse): a list of `row col value` entries with 1-based indices; entries
+///   that are not listed are zero. The `pattern` field (entries without values) gives ones.
+/// - `array` (dense): all values in column-major order.
+///
+/// The `real`, `double` and `integer` fields are supported, as are the `general`, `symmetric`,
+/// `skew-symmetric` and (for real data) `hermitian` symmetries, for which only the lower
+/// triangle is stored and the upper triangle is mirrored on reading.
+///
+/// `write_matrix_market` writes a dense `array general` file, and
+/// `write_matrix_market_coordinate` writes a sparse `coordinate general` file listing only the
+/// non-zero entries. The field is `integer` or `real` depending on `T`.
+///
+/// # Errors
+///
+/// Reading returns `ParseError::InvalidFormat` for a malformed header or size line, sizes too
+/// large for a dense matrix to be allocated, an unsupported field (e.g. `complex`), an out of
+/// range index or a wrong number of entries, and `ParseError::InvalidValue` if an index or value
+/// cannot be parsed.
+///
+/// # Examples
+///
+/// ```
+/// let text = "%%MatrixMarket matrix coordinate real symmetric\n% comment\n2 2 2\n1 1 4.0\n2 1 -1.0\n";
+/// let mat = Matrix::<f64>::read_matrix_market(text.as_bytes())?;
+/// assert_eq!(mat, Matrix::from_vec(2, 2, vec![4.0, -1.0, -1.0, 0.0]));
+/// ```
+impl<T> Matrix<T>
+    where
+        T: Scalar +
+        std::cmp::PartialEq +
+        std::fmt::Display +
+        std::str::FromStr +
+        MatrixMarketElement
+{
+    pub fn read_matrix_market<R: Read>(reader: R) -> Result<Matrix<T>, ParseError> {
+        let mut lines = std::io::BufReader::new(reader).lines().enumerate().map(|(idx, line)| (idx + 1, line));
+
+        // Header: %%MatrixMarket matrix <format> <field> <symmetry>
+        let header = match lines.next() {
+            Some((_, line)) => line?.to_lowercase(),
+            None => return Err(ParseError::InvalidFormat { line: 1, message: "File is empty".to_string() }),
+        };
+        let tokens: Vec<&str> = header.split_whitespace().collect();
+        if tokens.len() != 5 || tokens[0] != "%%matrixmarket" || tokens[1] != "matrix" {
+            return Err(ParseError::InvalidFormat { line: 1, message: "Expected '%%MatrixMarket matrix <format> <field> <symmetry>'".to_string() });
+        }
+        let coordinate = match tokens[2] {
+            "coordinate" => true,
+            "array" => false,
+            format => return Err(ParseError::InvalidFormat { line: 1, message: format!("Unknown format '{}'", format) }),
+        };
+        let pattern = match tokens[3] {
+            "real" | "double" | "integer" => false,
+            "pattern" if coordinate => true,
+            field => return Err(ParseError::InvalidFormat { line: 1, message: format!("Unsupported field '{}'", field) }),
+        };
+        let (symmetric, skew) = match tokens[4] {
+            "general" => (false, false),
+            "symmetric" | "hermitian" => (true, false),
+            "skew-symmetric" => (true, true),
+            symmetry => return Err(ParseError::InvalidFormat { line: 1, message: format!("Unknown symmetry '{}'", symmetry) }),
+        };
+
+        // Remaining non-comment lines, split into tokens
+        let mut content = lines.filter_map(|(line_number, line)| match line {
+            Ok(line) if line.trim().is_empty() || line.trim_start().starts_with('%') => None,
+            Ok(line) => Some(Ok((line_number, line.split_whitespace().map(str::to_string).collect::<Vec<String>>()))),
+            Err(err) => Some(Err(ParseError::Io(err))),
+        });
+
+        let (size_line, size_tokens) = match content.next() {
+            Some(entry) => entry?,
+            None => return Err(ParseError::InvalidFormat { line: 1, message: "Missing size line".to_string() }),
+        };
+        let expected_sizes = if coordinate { 3 } else { 2 };
+        if size_tokens.len() != expected_sizes {
+            return Err(ParseError::InvalidFormat { line: size_line, message: format!("Expected {} sizes, found {}", expected_sizes, size_tokens.len()) });
+        }
+        let sizes: Vec<usize> = size_tokens.iter().enumerate()
+            .map(|(col, token)| parse_value(token, size_line, col + 1))
+            .collect::<Result<_, _>>()?;
+        let (rows, cols) = (sizes[0], sizes[1]);
+        if symmetric && rows != cols {
+            return Err(ParseError::InvalidFormat { line: size_line, message: "Symmetric matrix must be square".to_string() });
+        }
+
+        // Allocate fallibly, so an oversized header is a parse error rather than an abort
+        let too_large = || ParseError::InvalidFormat { line: size_line, message: format!("Matrix dimensions {} x {} are too large", rows, cols) };
+        let len = rows.checked_mul(cols).ok_or_else(too_large)?;
+        let mut data = Vec::new();
+        data.try_reserve_exact(len).map_err(|_| too_large())?;
+        data.resize(len, T::zero());
+        let mut result = Matrix::from_vec(rows, cols, data);
+        let mut last_line = size_line;
+        let set = |result: &mut Matrix<T>, i: usize, j: usize, val: T| {
+            result[(i, j)] = val;
+            if symmetric && i != j {
+                result[(j, i)] = if skew { T::zero() - val } else { val };
+            }
+        };
+
+        if coordinate {
+            let entries = sizes[2];
+            let mut found = 0;
+            for entry in content {
+                let (line_number, tokens) = entry?;
+                last_line = line_number;
+                let expected_tokens = if pattern { 2 } else { 3 };
+                if tokens.len() != expected_tokens {
+                    return Err(ParseError::InvalidFormat { line: line_number, message: format!("Expected {} fields, found {}", expected_tokens, tokens.len()) });
+                }
+                let i: usize = parse_value(&tokens[0], line_number, 1)?;
+                let j: usize = parse_value(&tokens[1], line_number, 2)?;
+                if i == 0 || i > rows || j == 0 || j > cols {
+                    return Err(ParseError::InvalidFormat { line: line_number, message: format!("Index ({}, {}) out of bounds for matrix of dim {} x {}", i, j, rows, cols) });
+                }
+                let val = if pattern { T::one() } else { parse_value(&tokens[2], line_number, 3)? };
+                set(&mut result, i - 1, j - 1, val);
+                found += 1;
+            }
+            if found != entries {
+                return Err(ParseError::InvalidFormat { line: last_line, message: format!("Expected {} entries, found {}", entries, found) });
+            }
+        } else {
+            // Column-major positions of the stored values
+            let mut positions = (0..cols).flat_map(|j| {
+                let first_row = if skew { j + 1 } else if symmetric { j } else { 0 };
+                (first_row..rows).map(move |i| (i, j))
+            });
+            let expected: usize = (0..cols).map(|j| rows - if skew { (j + 1).min(rows) } else if symmetric { j } else { 0 }).sum();
+            let mut found = 0;
+            for entry in content {
+                let (line_number, tokens) = entry?;
+                last_line = line_number;
+                for (col, token) in tokens.iter().enumerate() {
+                    let val = parse_value(token, line_number, col + 1)?;
+                    match positions.next() {
+                        Some((i, j)) => set(&mut result, i, j, val),
+                        None => return Err(ParseError::InvalidFormat { line: line_number, message: format!("Expected {} values, found more", expected) }),
+                    }
+                    found += 1;
+                }
+            }
+            if found != expected {
+                return Err(ParseError::InvalidFormat { line: last_line, message: format!("Expected {} values, found {}", expected, found) });
+            }
+        }
+        Ok(result)
+    }
+
+    pub fn write_matrix_market<W: Write>(&self, writer: W) -> std::io::Result<()> {
+        let mut writer = std::io::BufWriter::new(writer);
+        writeln!(writer, "%%MatrixMarket matrix array {} general", T::MTX_FIELD)?;
+        writeln!(writer, "{} {}", self.rows, self.cols)?;
+        for j in 0..self.cols {
+            for i in 0..self.rows {
+                writeln!(writer, "{}", self[(i, j)])?;
+            }
+        }
+        writer.flush()
+    }
+
+    pub fn write_matrix_market_coordinate<W: Write>(&self, writer: W) -> std::io::Result<()> {
+        let mut writer = std::io::BufWriter::new(writer);
+        let non_zeros = self.data.iter().filter(|&&val| val != T::zero()).count();
+        writeln!(writer, "%%MatrixMarket matrix coordinate {} general", T::MTX_FIELD)?;
+        writeln!(writer, "{} {} {}", self.rows, self.cols, non_zeros)?;
+        for j in 0..self.cols {
+            for i in 0..self.rows {
+                if self[(i, j)] != T::zero() {
+                    writeln!(writer, "{} {} {}", i + 1, j + 1, self[(i, j)])?;
+                }
+            }
+        }
+        writer.flush()
+    }
+
+    pub fn load_matrix_market<P: AsRef<std::path::Path>>(path: P) -> Result<Matrix<T>, ParseError> {
+        Self::read_matrix_market(std::fs::File::open(path)?)
+    }
+
+    pub fn save_matrix_market<P: AsRef<std::path::Path>>(&self, path: P) -> std::io::Result<()> {
+        self.write_matrix_market(std::fs::File::create(path)?)
+    }
+
+    pub fn save_matrix_market_coordinate<P: AsRef<std::path::Path>>(&self, path: P) -> std::io::Result<()> {
+        self.write_matrix_market_coordinate(std::fs::File::create(path)?)
+    }
+}
+
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+////////////////////////////////////// SERDE SUPPORT //////////////////////////////////////////////
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+
+/// Serializes a matrix as a struct with the fields `rows`, `cols` and `data` (the elements in
+/// row-major order), e.g. `{"rows":2,"cols":2,"data":[1,2,3,4]}` in JSON.
+///
+/// Deserialization checks that `data` has exactly `rows * cols` elements, so a matrix can never
+/// be deserialized with an inconsistent shape.
+///
+/// Only available with the `serde` feature.
+#[cfg(feature = "serde")]
+impl<T> serde::Serialize for Matrix<T>
+    where
+        T: Scalar +
+        serde::Serialize
+{
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+
+        let mut state = serializer.serialize_struct("Matrix", 3)?;
+        state.serialize_field("rows", &self.rows)?;
+        state.serialize_field("cols", &self.cols)?;
+        state.serialize_field("data", &*self.row_major())?;
+        state.end()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T> serde::Deserialize<'de> for Matrix<T>
+    where
+        T: Scalar +
+        serde::Deserialize<'de>
+{
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(serde::Deserialize)]
+        #[serde(rename = "Matrix")]
+        struct Shadow<T> {
+            rows: usize,
+            cols: usize,
+            data: Vec<T>,
+        }
+
+        let Shadow { rows, cols, data } = Shadow::<T>::deserialize(deserializer)?;
+        if rows.checked_mul(cols) != Some(data.len()) {
+            return Err(serde::de::Error::custom(format!(
+                "Data length {} does not match matrix dimensions {} x {}",
+                data.len(), rows, cols
+            )));
+        }
+        Ok(Matrix { rows, cols, data, layout: Layout::RowMajor })
+    }
+}
@@ -0,0 +1,260 @@
+use std::borrow::Cow;
+
+use crate::{transpose, Matrix, Scalar};
+
+/// The order in which the elements of a `Matrix` are stored in its flat buffer.
+///
+/// - `RowMajor` (the default, as in C and NumPy): the rows are stored one after the other, so
+///   element `(i, j)` is at index `i * cols + j`.
+/// - `ColumnMajor` (as in Fortran, BLAS and LAPACK): the columns are stored one after the other,
+///   so element `(i, j)` is at index `j * rows + i`.
+///
+/// The layout does not change the value of a matrix: indexing, equality, formatting and
+/// serialization are the same for both layouts, and operations accept operands of any layout.
+/// Element-wise operations (`+`, `-`, `scale`, ...) and `transpose` keep the layout of their
+/// (left) operand, while other operations return row-major matrices.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Layout {
+    #[default]
+    RowMajor,
+    ColumnMajor,
+}
+
+impl Layout {
+    /// Returns the other layout.
+    pub fn transposed(self) -> Layout {
+        match self {
+            Layout::RowMajor => Layout::ColumnMajor,
+            Layout::ColumnMajor => Layout::RowMajor,
+        }
+    }
+}
+
+
+/// Creates a matrix from a buffer in the given layout, without copying it.
+///
+/// # Panics
+///
+/// This function will panic if the length of `data` does not match the dimensions.
+///
+/// # Examples
+///
+/// ```
+/// let a = Matrix::from_vec_with_layout(2, 3, vec![1, 4, 2, 5, 3, 6], Layout::ColumnMajor);
+/// assert_eq!(a, Matrix::from_vec(2, 3, vec![1, 2, 3, 4, 5, 6]));
+/// assert_eq!(a.as_slice(), &[1, 4, 2, 5, 3, 6]);
+/// ```
+impl<T> Matrix<T>
+    where
+        T: Scalar
+{
+    pub fn from_vec_with_layout(rows: usize, cols: usize, data: Vec<T>, layout: Layout) -> Self {
+        assert_eq!(rows * cols, data.len(), "Data length does not match matrix dimensions");
+
+        Matrix { rows, cols, data, layout }
+    }
+}
+
+/// Creates a row-major matrix by copying the elements of a strided buffer, where element
+/// `(i, j)` is `data[i * row_stride + j * col_stride]`.
+///
+/// Any strides are accepted, including those of a sub-matrix of a larger buffer (start `data`
+/// at its first element) or zero strides, which repeat elements.
+///
+/// # Panics
+///
+/// This function will panic if `data` is too short for the dimensions and strides.
+///
+/// # Examples
+///
+/// ```
+/// // The lower right 2 x 2 block of a row-major 3 x 3 buffer
+/// let buffer = [1, 2, 3, 4, 5, 6, 7, 8, 9];
+/// let block = Matrix::from_strided_slice(2, 2, &buffer[4..], 3, 1);
+/// assert_eq!(block, Matrix::from_vec(2, 2, vec![5, 6, 8, 9]));
+/// ```
+impl<T> Matrix<T>
+    where
+        T: Scalar
+{
+    pub fn from_strided_slice(rows: usize, cols: usize, data: &[T], row_stride: usize, col_stride: usize) -> Self {
+        if rows > 0 && cols > 0 {
+            let last = (rows - 1) * row_stride + (cols - 1) * col_stride;
+            assert!(last < data.len(), "Strided buffer of length {} is too short for a {} x {} matrix with strides ({}, {})",
+                    data.len(), rows, cols, row_stride, col_stride);
+        }
+
+        let mut result = Vec::with_capacity(rows * cols);
+        for i in 0..rows {
+            result.extend((0..cols).map(|j| data[i * row_stride + j * col_stride]));
+        }
+        Matrix { rows, cols, data: result, layout: Layout::RowMajor }
+    }
+}
+
+/// Accesses the layout and the flat buffer of the matrix, e.g. to pass it to other libraries.
+///
+/// - `layout` returns the storage order of the buffer.
+/// - `strides` returns `(row_stride, col_stride)`, the distance in the buffer between
+///   consecutive elements of a column and of a row, so element `(i, j)` is at index
+///   `i * row_stride + j * col_stride`. These are `(cols, 1)` for row-major and `(1, rows)` for
+///   column-major matrices.
+/// - `as_slice` and `into_vec` return the buffer in its layout, without copying it.
+///
+/// # Examples
+///
+/// ```
+/// let a = Matrix::from_vec(2, 3, vec![1, 2, 3, 4, 5, 6]);
+/// assert_eq!(a.strides(), (3, 1));
+/// let b = a.to_layout(Layout::ColumnMajor);
+/// assert_eq!(b.strides(), (1, 2));
+/// assert_eq!(b.into_vec(), vec![1, 4, 2, 5, 3, 6]);
+/// ```
+impl<T> Matrix<T> {
+    pub fn layout(&self) -> Layout {
+        self.layout
+    }
+
+    pub fn strides(&self) -> (usize, usize) {
+        match self.layout {
+            Layout::RowMajor => (self.cols, 1),
+            Layout::ColumnMajor => (1, self.rows),
+        }
+    }
+
+    pub fn as_slice(&self) -> &[T] {
+        &self.data
+    }
+
+    pub fn into_vec(self) -> Vec<T> {
+        self.data
+    }
+
+    /// Returns the index of element `(i, j)` in the buffer.
+    pub(crate) fn offset(&self, i: usize, j: usize) -> usize {
+        match self.layout {
+            Layout::RowMajor => i * self.cols + j,
+            Layout::ColumnMajor => j * self.rows + i,
+        }
+    }
+
+    /// Returns the dimensions of the buffer seen as a row-major matrix, i.e. of the matrix for
+    /// a row-major matrix and of its transpose for a column-major one.
+    pub(crate) fn buffer_shape(&self) -> (usize, usize) {
+        match self.layout {
+            Layout::RowMajor => (self.rows, self.cols),
+            Layout::ColumnMajor => (self.cols, self.rows),
+        }
+    }
+}
+
+/// Access to the buffer in a given layout, copying the elements if needed.
+impl<T> Matrix<T>
+    where
+        T: Scalar
+{
+    /// Returns the buffer of the matrix in `layout`, borrowed if the matrix already has it.
+    pub(crate) fn data_in(&self, layout: Layout) -> Cow<'_, [T]> {
+        if self.layout == layout {
+            Cow::Borrowed(&self.data)
+        } else {
+            let (rows, cols) = self.buffer_shape();
+            Cow::Owned(transpose::transposed(&self.data, rows, cols))
+        }
+    }
+
+    /// Returns the row-major buffer of the matrix, borrowed if the matrix is row-major.
+    pub(crate) fn row_major(&self) -> Cow<'_, [T]> {
+        self.data_in(Layout::RowMajor)
+    }
+
+    /// Returns the elements of row `row`, in order.
+    pub(crate) fn row_elements(&self, row: usize) -> impl Iterator<Item=T> + '_ {
+        let (_, col_stride) = self.strides();
+        self.data.iter().skip(self.offset(row, 0)).step_by(col_stride.max(1)).take(self.cols).copied()
+    }
+
+    /// Returns the elements of column `col`, in order.
+    pub(crate) fn col_elements(&self, col: usize) -> impl Iterator<Item=T> + '_ {
+        let (row_stride, _) = self.strides();
+        self.data.iter().skip(self.offset(0, col)).step_by(row_stride.max(1)).take(self.rows).copied()
+    }
+}
+
+/// Converts the matrix to another layout.
+///
+/// - `to_layout` returns a copy with the given layout.
+/// - `into_layout` permutes the buffer in place, so no memory is allocated (see
+///   `transpose_in_place` for the cost), and returns the matrix unchanged if it already has
+///   the layout.
+/// - `into_transposed` returns the transpose without copying or moving any element: the
+///   row-major buffer of a matrix is the column-major buffer of its transpose, so only the
+///   dimensions and the layout change.
+///
+/// # Examples
+///
+/// ```
+/// let a = Matrix::from_vec(2, 3, vec![1, 2, 3, 4, 5, 6]);
+/// let b = a.clone().into_layout(Layout::ColumnMajor);
+/// assert_eq!(a, b);
+/// assert_eq!(b.as_slice(), &[1, 4, 2, 5, 3, 6]);
+///
+/// let t = a.clone().into_transposed();
+/// assert_eq!(t, a.transpose());
+/// assert_eq!((t.layout(), t.as_slice()), (Layout::ColumnMajor, a.as_slice()));
+/// ```
+impl<T> Matrix<T>
+    where
+        T: Scalar
+{
+    pub fn to_layout(&self, layout: Layout) -> Matrix<T> {
+        Matrix { rows: self.rows, cols: self.cols, data: self.data_in(layout).into_owned(), layout }
+    }
+
+    pub fn into_layout(mut self, layout: Layout) -> Matrix<T> {
+        if self.layout != layout {
+            let (rows, cols) = self.buffer_shape();
+            transpose::transpose_buffer_in_place(&mut self.data, rows, cols);
+            self.layout = layout;
+        }
+        self
+    }
+
+    pub fn into_transposed(self) -> Matrix<T> {
+        Matrix { rows: self.cols, cols: self.rows, data: self.data, layout: self.layout.transposed() }
+    }
+}
+
+/// Returns a view of the specified column in the matrix as a slice of type `&[T]`.
+///
+/// This is the counterpart of `view_row` for column-major matrices, whose columns are stored
+/// contiguously.
+///
+/// # Panics
+///
+/// This method will panic if the column index is out of bounds, or if the column is not
+/// contiguous in the buffer, i.e. the matrix is row-major with more than one row.
+///
+/// # Examples
+///
+/// ```
+/// let a = Matrix::from_vec_with_layout(2, 2, vec![1, 2, 3, 4], Layout::ColumnMajor);
+/// assert_eq!(a.view_col(1), &[3, 4]);
+/// ```
+impl<T> Matrix<T>
+    where
+        T: Scalar
+{
+    pub fn view_col(&self, col: usize) -> &[T] {
+        if col >= self.cols {
+            panic!(
+                "Column index: {} out of bounds for matrix of dim {} x {}",
+                col, self.rows, self.cols
+            );
+        }
+        assert!(self.strides().0 == 1 || self.rows <= 1,
+                "Columns of a row-major matrix are not contiguous, use get_col or to_layout");
+        let start = self.offset(0, col);
+        &self.data[start..start + self.rows]
+    }
+}
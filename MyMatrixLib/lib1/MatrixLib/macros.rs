@@ -0,0 +1,40 @@
+/// Creates a `Matrix` from a literal, with rows separated by `;` and elements by `,`.
+///
+/// The dimensions are inferred from the literal. Rows of different lengths are rejected at
+/// compile time, since the rows are collected into a nested array (see `Matrix::from_array`).
+/// `matrix![]` creates an empty `0 x 0` matrix.
+///
+/// # Examples
+///
+/// ```
+/// let mat = matrix![1, 2, 3;
+///                   4, 5, 6];
+/// assert_eq!(mat, Matrix::from_vec(2, 3, vec![1, 2, 3, 4, 5, 6]));
+///
+/// // Does not compile: the second row has a different length.
+/// // let ragged = matrix![1, 2; 3];
+/// ```
+#[macro_export]
+macro_rules! matrix {
+    () => {
+        $crate::Matrix::from_vec(0, 0, ::std::vec::Vec::new())
+    };
+    ($($($x:expr),+ $(,)?);+ $(;)?) => {
+        $crate::Matrix::from_array([$([$($x),+]),+])
+    };
+}
+
+/// Creates a `Vector` from a list of elements, in the same way as `vec!`.
+///
+/// # Examples
+///
+/// ```
+/// let v = vector![1.0, 2.0, 3.0];
+/// assert_eq!(v.len(), 3);
+/// ```
+#[macro_export]
+macro_rules! vector {
+    ($($x:expr),* $(,)?) => {
+        $crate::Vector::from_vec(::std::vec![$($x),*])
+    };
+}
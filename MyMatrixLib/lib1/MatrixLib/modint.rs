@@ -0,0 +1,279 @@
+use crate::Matrix;
+
+/// An integer modulo `P`, i.e. an element of the ring Z/PZ.
+///
+/// The value is always kept reduced to `0..P`, and all arithmetic is exact (products are
+/// computed in 128 bits, so any modulus up to `u64::MAX` works). Division multiplies by the
+/// modular inverse, which exists for every non-zero element if and only if `P` is prime. In that
+/// case `ModInt<P>` is the finite field GF(P), and the elimination methods of
+/// `Matrix<ModInt<P>>` (`modular_rref`, `modular_rank`, `modular_determinant`,
+/// `modular_inverse` and `modular_solve`) compute exact results.
+///
+/// # Panics
+///
+/// Dividing by an element that has no inverse modulo `P` (e.g. zero) will panic.
+///
+/// # Examples
+///
+/// ```
+/// type F7 = ModInt<7>;
+/// let a = F7::new(3);
+/// assert_eq!(a * F7::new(5), F7::new(1));
+/// assert_eq!(F7::new(1) / a, F7::new(5));
+/// assert_eq!(F7::new(-1).value(), 6);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct ModInt<const P: u64> {
+    value: u64,
+}
+
+impl<const P: u64> ModInt<P> {
+    /// Creates the residue of `value` modulo `P`; negative values are mapped to `0..P`.
+    pub fn new(value: i64) -> Self {
+        ModInt { value: (value as i128).rem_euclid(P as i128) as u64 }
+    }
+
+    /// Returns the representative of the residue in `0..P`.
+    pub fn value(&self) -> u64 {
+        self.value
+    }
+
+    /// Raises the element to the power `exp` by repeated squaring.
+    pub fn pow(self, mut exp: u64) -> Self {
+        let mut base = self;
+        let mut result = ModInt::new(1);
+        while exp > 0 {
+            if exp & 1 == 1 {
+                result = result * base;
+            }
+            base = base * base;
+            exp >>= 1;
+        }
+        result
+    }
+
+    /// Returns the multiplicative inverse, or `None` if the element is not coprime to `P`.
+    pub fn inverse(self) -> Option<Self> {
+        // Extended Euclidean algorithm on (value, P)
+        let (mut old_r, mut r) = (self.value as i128, P as i128);
+        let (mut old_s, mut s) = (1_i128, 0_i128);
+        while r != 0 {
+            let q = old_r / r;
+            (old_r, r) = (r, old_r - q * r);
+            (old_s, s) = (s, old_s - q * s);
+        }
+        if old_r != 1 {
+            return None;
+        }
+        Some(ModInt { value: old_s.rem_euclid(P as i128) as u64 })
+    }
+}
+
+impl<const P: u64> From<i64> for ModInt<P> {
+    fn from(value: i64) -> Self {
+        ModInt::new(value)
+    }
+}
+
+impl<const P: u64> std::fmt::Display for ModInt<P> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        self.value.fmt(f)
+    }
+}
+
+impl<const P: u64> std::ops::Add for ModInt<P> {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        ModInt { value: ((self.value as u128 + other.value as u128) % P as u128) as u64 }
+    }
+}
+
+impl<const P: u64> std::ops::Sub for ModInt<P> {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        ModInt { value: ((self.value as u128 + P as u128 - other.value as u128) % P as u128) as u64 }
+    }
+}
+
+impl<const P: u64> std::ops::Mul for ModInt<P> {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self {
+        ModInt { value: ((self.value as u128 * other.value as u128) % P as u128) as u64 }
+    }
+}
+
+impl<const P: u64> std::ops::Div for ModInt<P> {
+    type Output = Self;
+
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn div(self, other: Self) -> Self {
+        match other.inverse() {
+            Some(inverse) => self * inverse,
+            None => panic!("{} has no inverse modulo {}", other.value, P),
+        }
+    }
+}
+
+impl<const P: u64> std::ops::Neg for ModInt<P> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        ModInt::new(0) - self
+    }
+}
+
+impl<const P: u64> num_traits::Zero for ModInt<P> {
+    fn zero() -> Self {
+        ModInt { value: 0 }
+    }
+
+    fn is_zero(&self) -> bool {
+        self.value == 0
+    }
+}
+
+impl<const P: u64> num_traits::One for ModInt<P> {
+    fn one() -> Self {
+        ModInt::new(1)
+    }
+}
+
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+////////////////////////////////////// GAUSSIAN ELIMINATION OVER GF(P) ////////////////////////////
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+
+/// Exact Gaussian elimination over the finite field GF(P). `P` must be prime.
+///
+/// - `modular_rref` returns the reduced row echelon form.
+/// - `modular_rank` returns the rank.
+/// - `modular_determinant` returns the determinant of a square matrix.
+/// - `modular_inverse` returns the inverse of a square matrix, or `None` if it is singular.
+/// - `modular_solve` returns the solution `x` of `A * x = b` for a square `A`, or `None` if `A`
+///   is singular.
+///
+/// # Panics
+///
+/// `modular_determinant`, `modular_inverse` and `modular_solve` will panic if the matrix is not
+/// square, and `modular_solve` if `b` does not have as many rows as the matrix.
+///
+/// # Examples
+///
+/// ```
+/// let a = Matrix::from_vec(2, 2, vec![1, 2, 3, 4]).map_mod::<5>();
+/// assert_eq!(a.modular_determinant(), ModInt::new(-2));
+/// assert_eq!(a.clone() * a.modular_inverse().unwrap(), Matrix::identity(2));
+/// ```
+impl<const P: u64> Matrix<ModInt<P>> {
+    /// Reduces the matrix to reduced row echelon form in place. Returns the pivot columns and
+    /// the determinant factor, the product of the pivots and the sign of the row swaps.
+    fn modular_reduce(&mut self) -> (Vec<usize>, ModInt<P>) {
+        let mut pivots = Vec::new();
+        let mut factor = ModInt::new(1);
+        let mut pivot_row = 0;
+        for col in 0..self.cols {
+            if pivot_row == self.rows {
+                break;
+            }
+            let Some(pivot) = (pivot_row..self.rows).find(|&i| self[(i, col)].value != 0) else {
+                continue;
+            };
+            if pivot != pivot_row {
+                self.swap_rows(pivot, pivot_row);
+                factor = -factor;
+            }
+
+            // Normalize the pivot row and eliminate the column everywhere else
+            let pivot_value = self[(pivot_row, col)];
+            factor = factor * pivot_value;
+            let inverse = ModInt::new(1) / pivot_value;
+            for j in col..self.cols {
+                self[(pivot_row, j)] = self[(pivot_row, j)] * inverse;
+            }
+            for i in 0..self.rows {
+                let multiple = self[(i, col)];
+                if i != pivot_row && multiple.value != 0 {
+                    self.add_row_multiple(i, pivot_row, -multiple);
+                }
+            }
+            pivots.push(col);
+            pivot_row += 1;
+        }
+        (pivots, factor)
+    }
+
+    pub fn modular_rref(&self) -> Matrix<ModInt<P>> {
+        let mut result = self.clone();
+        result.modular_reduce();
+        result
+    }
+
+    pub fn modular_rank(&self) -> usize {
+        self.clone().modular_reduce().0.len()
+    }
+
+    pub fn modular_determinant(&self) -> ModInt<P> {
+        assert_eq!(self.rows, self.cols, "Determinant requires a square matrix");
+
+        let (pivots, factor) = self.clone().modular_reduce();
+        if pivots.len() < self.rows { ModInt::new(0) } else { factor }
+    }
+
+    pub fn modular_inverse(&self) -> Option<Matrix<ModInt<P>>> {
+        assert_eq!(self.rows, self.cols, "Inverse requires a square matrix");
+
+        self.modular_solve(&Matrix::identity(self.rows))
+    }
+
+    pub fn modular_solve(&self, b: &Matrix<ModInt<P>>) -> Option<Matrix<ModInt<P>>> {
+        assert_eq!(self.rows, self.cols, "Cannot solve linear system, matrix must be square");
+        assert_eq!(self.rows, b.rows, "Right hand side has {} rows, but the matrix has {}", b.rows, self.rows);
+
+        let n = self.rows;
+        if n == 0 {
+            return Some(Matrix::zeros(0, b.cols));
+        }
+
+        // Reduce the augmented matrix [A | b]
+        let width = n + b.cols;
+        let mut augmented = Matrix::zeros(n, width);
+        for i in 0..n {
+            for (j, val) in self.row_elements(i).chain(b.row_elements(i)).enumerate() {
+                augmented.data[i * width + j] = val;
+            }
+        }
+        let (pivots, _) = augmented.modular_reduce();
+        if pivots.len() < n || pivots[n - 1] >= n {
+            return None;
+        }
+
+        let mut x = Matrix::zeros(n, b.cols);
+        for i in 0..n {
+            x.data[i * b.cols..(i + 1) * b.cols].copy_from_slice(&augmented.view_row(i)[n..]);
+        }
+        Some(x)
+    }
+}
+
+/// Converts an integer matrix to a matrix over Z/PZ, reducing every element modulo `P`.
+///
+/// # Examples
+///
+/// ```
+/// let a = Matrix::from_vec(1, 3, vec![-1, 7, 12]).map_mod::<7>();
+/// assert_eq!(a, Matrix::from_vec(1, 3, vec![ModInt::new(6), ModInt::new(0), ModInt::new(5)]));
+/// ```
+impl Matrix<i64> {
+    pub fn map_mod<const P: u64>(&self) -> Matrix<ModInt<P>> {
+        Matrix {
+            rows: self.rows,
+            cols: self.cols,
+            data: self.data.iter().map(|&val| ModInt::new(val)).collect(),
+            layout: self.layout,
+        }
+    }
+}
@@ -0,0 +1,427 @@
+//! Building blocks for small neural networks on `Matrix`: dense layers, activations, losses and
+//! optimizers, trained with the reverse-mode differentiation of `Tape` and `Var`.
+//!
+//! Inputs are batches with one example per row, so a dense layer maps a `batch x inputs` matrix
+//! to `batch x outputs` as `activation(X * W + b)`.
+//!
+//! # Examples
+//!
+//! ```
+//! use MyMatrixLib::nn::{Activation, Adam, Dense, Loss, Mlp};
+//!
+//! // Learn XOR with one hidden layer
+//! let inputs = Matrix::from_vec(4, 2, vec![0.0, 0.0, 0.0, 1.0, 1.0, 0.0, 1.0, 1.0]);
+//! let targets = Matrix::from_vec(4, 1, vec![0.0, 1.0, 1.0, 0.0]);
+//! let mut mlp = Mlp::new(vec![Dense::new(2, 8, Activation::Tanh), Dense::new(8, 1, Activation::Sigmoid)]);
+//! let losses = mlp.fit(&inputs, &targets, Loss::MeanSquaredError, &mut Adam::new(0.05), 500, 4);
+//! println!("final loss {}, predictions {}", losses[losses.len() - 1], mlp.predict(&inputs));
+//! ```
+
+use rand::distributions::uniform::SampleUniform;
+
+use crate::{Matrix, Real, Tape, Var};
+
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+////////////////////////////////////// ACTIVATIONS AND LOSSES /////////////////////////////////////
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+
+/// The activation function applied to the output of a layer.
+///
+/// `Softmax` is applied to each row, i.e. to each example of a batch, while the others are
+/// applied element by element.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Activation {
+    Identity,
+    Relu,
+    Sigmoid,
+    Tanh,
+    Softmax,
+}
+
+impl Activation {
+    pub fn apply<'t, T>(self, x: Var<'t, T>) -> Var<'t, T>
+        where
+            T: Real +
+            std::fmt::Display
+    {
+        match self {
+            Activation::Identity => x,
+            Activation::Relu => x.relu(),
+            Activation::Sigmoid => x.sigmoid(),
+            Activation::Tanh => x.tanh(),
+            Activation::Softmax => x.softmax(),
+        }
+    }
+}
+
+/// The loss comparing a batch of predictions with the targets, averaged over the batch.
+///
+/// - `MeanSquaredError` is the mean of `(prediction - target)^2` over all elements.
+/// - `CrossEntropy` is the mean over the rows of `-sum(target * ln(prediction))`, for rows of
+///   predicted probabilities (e.g. from `Activation::Softmax`) and of target probabilities
+///   (e.g. from `one_hot`). Predictions are clamped to at least `1e-12` so the loss stays
+///   finite.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Loss {
+    MeanSquaredError,
+    CrossEntropy,
+}
+
+impl Loss {
+    /// Returns the loss as a `1 x 1` variable.
+    ///
+    /// # Panics
+    ///
+    /// This method will panic if the predictions and the targets do not have the same
+    /// dimensions.
+    pub fn apply<'t, T>(self, predictions: Var<'t, T>, targets: Var<'t, T>) -> Var<'t, T>
+        where
+            T: Real +
+            std::fmt::Display
+    {
+        match self {
+            Loss::MeanSquaredError => {
+                let error = predictions - targets;
+                error.hadamard(error).mean()
+            }
+            Loss::CrossEntropy => {
+                let rows = targets.value().rows;
+                let floor = T::from(1e-12).unwrap();
+                let log_predictions = predictions.map(
+                    move |p| p.max(floor).ln(),
+                    move |p| if p > floor { p.recip() } else { T::zero() },
+                );
+                targets.hadamard(log_predictions).sum().scale(-T::one() / T::from(rows.max(1)).unwrap())
+            }
+        }
+    }
+}
+
+/// Converts a column (or row) vector of class indices `0..classes` into a matrix with one row
+/// per label, which is one at the column of the label and zero elsewhere.
+///
+/// # Panics
+///
+/// This function will panic if a label is not an integer in `0..classes`.
+///
+/// # Examples
+///
+/// ```
+/// let labels = Matrix::from_vec(3, 1, vec![2.0, 0.0, 1.0]);
+/// assert_eq!(one_hot(&labels, 3), Matrix::from_vec(3, 3, vec![0.0, 0.0, 1.0, 1.0, 0.0, 0.0, 0.0, 1.0, 0.0]));
+/// ```
+pub fn one_hot<T>(labels: &Matrix<T>, classes: usize) -> Matrix<T>
+    where
+        T: Real
+{
+    let labels = labels.as_slice();
+    let mut result = Matrix::zeros(labels.len(), classes);
+    for (i, &label) in labels.iter().enumerate() {
+        let class = label.to_usize().filter(|&class| class < classes && T::from(class) == Some(label));
+        match class {
+            Some(class) => result[(i, class)] = T::one(),
+            None => panic!("Label {} is not a class index below {}", label.to_f64().unwrap_or(f64::NAN), classes),
+        }
+    }
+    result
+}
+
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+////////////////////////////////////// LAYERS /////////////////////////////////////////////////////
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+
+/// How the weights of a dense layer are initialized, drawn uniformly from `[-a, a]`.
+///
+/// - `Xavier` (Glorot): `a = sqrt(6 / (inputs + outputs))`, which keeps the variance of the
+///   signal roughly constant through layers with sigmoid or tanh activations.
+/// - `He`: `a = sqrt(6 / inputs)`, twice the variance, which compensates for the half of the
+///   units that ReLU sets to zero.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Initialization {
+    Xavier,
+    He,
+}
+
+/// A fully connected layer computing `activation(X * weights + bias)`, with `weights` of
+/// dimensions `inputs x outputs` and `bias` a `1 x outputs` row vector added to every row.
+///
+/// `new` initializes the weights with `Initialization::He` for ReLU layers and
+/// `Initialization::Xavier` otherwise, and the bias with zeros. `with_rng` chooses the
+/// initialization and the random number generator, e.g. for reproducible training.
+#[derive(Debug, Clone)]
+pub struct Dense<T>
+    where
+        T: Real
+{
+    pub weights: Matrix<T>,
+    pub bias: Matrix<T>,
+    pub activation: Activation,
+}
+
+impl<T> Dense<T>
+    where
+        T: Real +
+        std::fmt::Display +
+        SampleUniform
+{
+    pub fn new(inputs: usize, outputs: usize, activation: Activation) -> Self {
+        let initialization = match activation {
+            Activation::Relu => Initialization::He,
+            _ => Initialization::Xavier,
+        };
+        Self::with_rng(inputs, outputs, activation, initialization, &mut rand::thread_rng())
+    }
+
+    pub fn with_rng<R>(inputs: usize, outputs: usize, activation: Activation, initialization: Initialization, rng: &mut R) -> Self
+        where
+            R: rand::Rng + ?Sized
+    {
+        let fan = match initialization {
+            Initialization::Xavier => inputs + outputs,
+            Initialization::He => inputs,
+        };
+        let bound = (T::from(6.0).unwrap() / T::from(fan.max(1)).unwrap()).sqrt();
+        Dense {
+            weights: Matrix::random_uniform_with_rng(inputs, outputs, -bound, bound, rng),
+            bias: Matrix::zeros(1, outputs),
+            activation,
+        }
+    }
+}
+
+impl<T> Dense<T>
+    where
+        T: Real +
+        std::fmt::Display
+{
+    /// Records the layer on `tape`, returning the output and the variables of the weights and
+    /// the bias.
+    pub fn forward<'t>(&self, tape: &'t Tape<T>, inputs: Var<'t, T>) -> (Var<'t, T>, [Var<'t, T>; 2]) {
+        let (weights, bias) = (tape.var(self.weights.clone()), tape.var(self.bias.clone()));
+        let output = self.activation.apply((inputs * weights).add_row(bias));
+        (output, [weights, bias])
+    }
+}
+
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+////////////////////////////////////// OPTIMIZERS /////////////////////////////////////////////////
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+
+/// Updates parameters from their gradients. `step` is called once per batch, always with the
+/// parameters in the same order, so optimizers can keep state for each parameter.
+pub trait Optimizer<T>
+    where
+        T: Real
+{
+    fn step(&mut self, parameters: Vec<&mut Matrix<T>>, gradients: &[Matrix<T>]);
+}
+
+/// Stochastic gradient descent with optional momentum: `v = momentum * v - learning_rate * g`
+/// and `p = p + v`.
+#[derive(Debug, Clone)]
+pub struct Sgd<T>
+    where
+        T: Real
+{
+    pub learning_rate: T,
+    pub momentum: T,
+    velocities: Vec<Matrix<T>>,
+}
+
+impl<T> Sgd<T>
+    where
+        T: Real
+{
+    pub fn new(learning_rate: T) -> Self {
+        Self::with_momentum(learning_rate, T::zero())
+    }
+
+    pub fn with_momentum(learning_rate: T, momentum: T) -> Self {
+        Sgd { learning_rate, momentum, velocities: Vec::new() }
+    }
+}
+
+impl<T> Optimizer<T> for Sgd<T>
+    where
+        T: Real +
+        std::fmt::Display
+{
+    fn step(&mut self, parameters: Vec<&mut Matrix<T>>, gradients: &[Matrix<T>]) {
+        if self.velocities.is_empty() {
+            self.velocities = gradients.iter().map(|g| Matrix::zeros(g.rows, g.cols)).collect();
+        }
+        for ((parameter, gradient), velocity) in parameters.into_iter().zip(gradients).zip(&mut self.velocities) {
+            *velocity = velocity.scale(self.momentum) - gradient.scale(self.learning_rate);
+            *parameter = parameter.clone() + velocity.clone();
+        }
+    }
+}
+
+/// The Adam optimizer (Kingma and Ba, 2015), which scales each step by running estimates of the
+/// first and second moments of the gradients, with bias correction for the first steps. `new`
+/// uses the usual `beta1 = 0.9`, `beta2 = 0.999` and `epsilon = 1e-8`.
+#[derive(Debug, Clone)]
+pub struct Adam<T>
+    where
+        T: Real
+{
+    pub learning_rate: T,
+    pub beta1: T,
+    pub beta2: T,
+    pub epsilon: T,
+    steps: i32,
+    first_moments: Vec<Matrix<T>>,
+    second_moments: Vec<Matrix<T>>,
+}
+
+impl<T> Adam<T>
+    where
+        T: Real
+{
+    pub fn new(learning_rate: T) -> Self {
+        Adam {
+            learning_rate,
+            beta1: T::from(0.9).unwrap(),
+            beta2: T::from(0.999).unwrap(),
+            epsilon: T::from(1e-8).unwrap(),
+            steps: 0,
+            first_moments: Vec::new(),
+            second_moments: Vec::new(),
+        }
+    }
+}
+
+impl<T> Optimizer<T> for Adam<T>
+    where
+        T: Real +
+        std::fmt::Display
+{
+    fn step(&mut self, parameters: Vec<&mut Matrix<T>>, gradients: &[Matrix<T>]) {
+        if self.first_moments.is_empty() {
+            self.first_moments = gradients.iter().map(|g| Matrix::zeros(g.rows, g.cols)).collect();
+            self.second_moments = self.first_moments.clone();
+        }
+        self.steps += 1;
+        let one = T::one();
+        let (correction1, correction2) = (one - self.beta1.powi(self.steps), one - self.beta2.powi(self.steps));
+
+        for (k, (parameter, gradient)) in parameters.into_iter().zip(gradients).enumerate() {
+            let m = self.first_moments[k].scale(self.beta1) + gradient.scale(one - self.beta1);
+            let v = self.second_moments[k].scale(self.beta2) + gradient.hadamard(gradient).scale(one - self.beta2);
+            // The moments are row-major, while the parameter may be stored in either layout
+            let (m_data, v_data) = (m.data_in(parameter.layout), v.data_in(parameter.layout));
+            for (p, (&m, &v)) in parameter.data.iter_mut().zip(m_data.iter().zip(v_data.iter())) {
+                *p = *p - self.learning_rate * (m / correction1) / ((v / correction2).sqrt() + self.epsilon);
+            }
+            self.first_moments[k] = m;
+            self.second_moments[k] = v;
+        }
+    }
+}
+
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+////////////////////////////////////// NETWORKS ///////////////////////////////////////////////////
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+
+/// A multilayer perceptron: a sequence of dense layers, each taking the output of the previous
+/// one.
+///
+/// - `predict` returns the output of the network for a batch of inputs.
+/// - `train_step` runs one step of the optimizer on a batch and returns the loss before the
+///   step.
+/// - `fit` trains for a number of epochs on mini-batches of `batch_size` consecutive rows and
+///   returns the mean loss of each epoch.
+///
+/// # Panics
+///
+/// `new` will panic if there are no layers or if the outputs of a layer do not match the inputs
+/// of the next. The other methods will panic if the inputs and targets do not match the
+/// dimensions of the network, or if `batch_size` is zero.
+#[derive(Debug, Clone)]
+pub struct Mlp<T>
+    where
+        T: Real
+{
+    pub layers: Vec<Dense<T>>,
+}
+
+impl<T> Mlp<T>
+    where
+        T: Real +
+        std::fmt::Display
+{
+    pub fn new(layers: Vec<Dense<T>>) -> Self {
+        assert!(!layers.is_empty(), "A network needs at least one layer");
+        for (k, pair) in layers.windows(2).enumerate() {
+            assert_eq!(pair[0].weights.cols, pair[1].weights.rows,
+                       "Layer {} has {} outputs, but layer {} has {} inputs",
+                       k, pair[0].weights.cols, k + 1, pair[1].weights.rows);
+        }
+        Mlp { layers }
+    }
+
+    /// Records the network on `tape`, returning the output and the variables of all parameters
+    /// (the weights and bias of each layer in turn).
+    fn forward<'t>(&self, tape: &'t Tape<T>, inputs: &Matrix<T>) -> (Var<'t, T>, Vec<Var<'t, T>>) {
+        let mut output = tape.var(inputs.clone());
+        let mut parameters = Vec::with_capacity(2 * self.layers.len());
+        for layer in &self.layers {
+            let (next, layer_parameters) = layer.forward(tape, output);
+            output = next;
+            parameters.extend(layer_parameters);
+        }
+        (output, parameters)
+    }
+
+    pub fn predict(&self, inputs: &Matrix<T>) -> Matrix<T> {
+        self.forward(&Tape::new(), inputs).0.value()
+    }
+
+    pub fn train_step<O>(&mut self, inputs: &Matrix<T>, targets: &Matrix<T>, loss: Loss, optimizer: &mut O) -> T
+        where
+            O: Optimizer<T>
+    {
+        let tape = Tape::new();
+        let (predictions, parameters) = self.forward(&tape, inputs);
+        let loss = loss.apply(predictions, tape.var(targets.clone()));
+        let gradients = loss.backward();
+        let gradients: Vec<Matrix<T>> = parameters.iter().map(|&parameter| gradients.wrt(parameter).clone()).collect();
+
+        let parameters = self.layers.iter_mut().flat_map(|layer| [&mut layer.weights, &mut layer.bias]).collect();
+        optimizer.step(parameters, &gradients);
+        loss.value()[(0, 0)]
+    }
+
+    pub fn fit<O>(&mut self, inputs: &Matrix<T>, targets: &Matrix<T>, loss: Loss, optimizer: &mut O,
+                  epochs: usize, batch_size: usize) -> Vec<T>
+        where
+            O: Optimizer<T>
+    {
+        assert!(batch_size > 0, "Batch size must be positive");
+        assert_eq!(inputs.rows, targets.rows, "Inputs have {} rows, but targets have {}", inputs.rows, targets.rows);
+
+        let (inputs_data, targets_data) = (inputs.row_major(), targets.row_major());
+        let batch = |data: &[T], cols: usize, start: usize, end: usize| {
+            Matrix::from_vec(end - start, cols, data[start * cols..end * cols].to_vec())
+        };
+        (0..epochs).map(|_| {
+            let mut total = T::zero();
+            for start in (0..inputs.rows).step_by(batch_size) {
+                let end = (start + batch_size).min(inputs.rows);
+                let batch_inputs = batch(&inputs_data, inputs.cols, start, end);
+                let batch_targets = batch(&targets_data, targets.cols, start, end);
+                let batch_loss = self.train_step(&batch_inputs, &batch_targets, loss, optimizer);
+                total = total + batch_loss * T::from(end - start).unwrap();
+            }
+            total / T::from(inputs.rows.max(1)).unwrap()
+        }).collect()
+    }
+}
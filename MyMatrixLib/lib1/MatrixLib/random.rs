@@ -0,0 +1,344 @@
+use crate::{Layout, Matrix, Real, Scalar};
+use rand::distributions::Distribution;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use rand::seq::index;
+use rand_distr::{Binomial, Exp, Exp1, Normal, Poisson, StandardNormal};
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+////////////////////////////////////// RANDOM METHODS /////////////////////////////////////////////
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+// Every generator here comes in three flavours, as the uniform and Bernoulli constructors: one
+// drawing from `rand::thread_rng()`, a `_with_rng` variant drawing from a caller-supplied
+// `rand::Rng`, and a `_seeded` variant drawing from `StdRng::seed_from_u64(seed)` for
+// reproducible matrices.
+
+
+/// Generates a matrix of size `rows` by `cols` whose entries are sampled independently from an
+/// arbitrary distribution implementing `rand::distributions::Distribution<T>`, e.g. any of the
+/// distributions in the `rand_distr` crate.
+///
+/// # Examples
+///
+/// ```
+/// let gamma = rand_distr::Gamma::new(2.0, 1.5).unwrap();
+/// let mat = Matrix::<f64>::random_from_distribution(3, 3, &gamma);
+/// ```
+impl<T> Matrix<T>
+    where
+        T: Scalar
+{
+    pub fn random_from_distribution<D>(rows: usize, cols: usize, distribution: &D) -> Matrix<T>
+        where
+            D: Distribution<T>
+    {
+        Self::random_from_distribution_with_rng(rows, cols, distribution, &mut rand::thread_rng())
+    }
+
+    pub fn random_from_distribution_with_rng<D, R>(rows: usize, cols: usize, distribution: &D, rng: &mut R) -> Matrix<T>
+        where
+            D: Distribution<T>,
+            R: rand::Rng + ?Sized
+    {
+        let data = distribution.sample_iter(rng).take(rows * cols).collect();
+        Matrix { rows, cols, data, layout: Layout::RowMajor }
+    }
+
+    pub fn random_from_distribution_seeded<D>(rows: usize, cols: usize, distribution: &D, seed: u64) -> Matrix<T>
+        where
+            D: Distribution<T>
+    {
+        Self::random_from_distribution_with_rng(rows, cols, distribution, &mut StdRng::seed_from_u64(seed))
+    }
+}
+
+
+/// Generates a sparse matrix of size `rows` by `cols` in which exactly
+/// `round(density * rows * cols)` entries, at positions chosen uniformly without replacement,
+/// are sampled from `distribution` and all other entries are zero.
+///
+/// # Panics
+///
+/// This function will panic if `density` is not between 0.0 and 1.0 (inclusive).
+///
+/// # Examples
+///
+/// ```
+/// let values = rand::distributions::Uniform::new(1.0, 2.0);
+/// let mat = Matrix::<f64>::random_sparse(100, 100, 0.05, &values); // 500 non-zeros
+/// ```
+impl<T> Matrix<T>
+    where
+        T: Scalar
+{
+    pub fn random_sparse<D>(rows: usize, cols: usize, density: f64, distribution: &D) -> Matrix<T>
+        where
+            D: Distribution<T>
+    {
+        Self::random_sparse_with_rng(rows, cols, density, distribution, &mut rand::thread_rng())
+    }
+
+    pub fn random_sparse_with_rng<D, R>(rows: usize, cols: usize, density: f64, distribution: &D, rng: &mut R) -> Matrix<T>
+        where
+            D: Distribution<T>,
+            R: rand::Rng + ?Sized
+    {
+        if !(0.0..=1.0).contains(&density) {
+            panic!("Density should be between 0.0 and 1.0");
+        }
+        let size = rows * cols;
+        let non_zeros = (density * size as f64).round() as usize;
+
+        let mut result = Matrix::zeros(rows, cols);
+        for idx in index::sample(rng, size, non_zeros) {
+            result.data[idx] = distribution.sample(rng);
+        }
+        result
+    }
+
+    pub fn random_sparse_seeded<D>(rows: usize, cols: usize, density: f64, distribution: &D, seed: u64) -> Matrix<T>
+        where
+            D: Distribution<T>
+    {
+        Self::random_sparse_with_rng(rows, cols, density, distribution, &mut StdRng::seed_from_u64(seed))
+    }
+}
+
+
+/// Generates matrices with entries sampled independently from the normal distribution
+/// `N(mean, std^2)` and from the exponential distribution with rate `lambda`.
+///
+/// # Panics
+///
+/// `random_normal` will panic if `std` is negative or not finite, and `random_exponential`
+/// will panic if `lambda` is negative.
+///
+/// # Examples
+///
+/// ```
+/// let noise = Matrix::<f64>::random_normal(3, 3, 0.0, 0.1);
+/// let waiting_times = Matrix::<f32>::random_exponential(3, 3, 2.0);
+/// ```
+impl<T> Matrix<T>
+    where
+        T: Real,
+        StandardNormal: Distribution<T>,
+        Exp1: Distribution<T>
+{
+    pub fn random_normal(rows: usize, cols: usize, mean: T, std: T) -> Matrix<T> {
+        Self::random_normal_with_rng(rows, cols, mean, std, &mut rand::thread_rng())
+    }
+
+    pub fn random_normal_with_rng<R>(rows: usize, cols: usize, mean: T, std: T, rng: &mut R) -> Matrix<T>
+        where
+            R: rand::Rng + ?Sized
+    {
+        if !(std >= T::zero() && std.is_finite()) {
+            panic!("Standard deviation should be finite and non-negative");
+        }
+        let distribution = Normal::new(mean, std).unwrap();
+        Self::random_from_distribution_with_rng(rows, cols, &distribution, rng)
+    }
+
+    pub fn random_normal_seeded(rows: usize, cols: usize, mean: T, std: T, seed: u64) -> Matrix<T> {
+        Self::random_normal_with_rng(rows, cols, mean, std, &mut StdRng::seed_from_u64(seed))
+    }
+
+    pub fn random_exponential(rows: usize, cols: usize, lambda: T) -> Matrix<T> {
+        Self::random_exponential_with_rng(rows, cols, lambda, &mut rand::thread_rng())
+    }
+
+    pub fn random_exponential_with_rng<R>(rows: usize, cols: usize, lambda: T, rng: &mut R) -> Matrix<T>
+        where
+            R: rand::Rng + ?Sized
+    {
+        let distribution = Exp::new(lambda)
+            .unwrap_or_else(|_| panic!("Rate lambda should be non-negative"));
+        Self::random_from_distribution_with_rng(rows, cols, &distribution, rng)
+    }
+
+    pub fn random_exponential_seeded(rows: usize, cols: usize, lambda: T, seed: u64) -> Matrix<T> {
+        Self::random_exponential_with_rng(rows, cols, lambda, &mut StdRng::seed_from_u64(seed))
+    }
+}
+
+
+/// Generates matrices of counts, with entries sampled independently from the Poisson
+/// distribution with mean `lambda` or from the binomial distribution with `trials` trials and
+/// success probability `probability`.
+///
+/// The counts are converted to the element type `T`, so these work for both integer and
+/// floating point matrices.
+///
+/// # Panics
+///
+/// `random_poisson` will panic if `lambda` is not positive and finite. `random_binomial` will
+/// panic if `probability` is not between 0.0 and 1.0 (inclusive). Both panic if a sampled
+/// count cannot be represented by `T`.
+///
+/// # Examples
+///
+/// ```
+/// let arrivals = Matrix::<u32>::random_poisson(3, 3, 4.5);
+/// let successes = Matrix::<i64>::random_binomial(3, 3, 10, 0.3);
+/// ```
+impl<T> Matrix<T>
+    where
+        T: Scalar +
+        num_traits::NumCast
+{
+    pub fn random_poisson(rows: usize, cols: usize, lambda: f64) -> Matrix<T> {
+        Self::random_poisson_with_rng(rows, cols, lambda, &mut rand::thread_rng())
+    }
+
+    pub fn random_poisson_with_rng<R>(rows: usize, cols: usize, lambda: f64, rng: &mut R) -> Matrix<T>
+        where
+            R: rand::Rng + ?Sized
+    {
+        let distribution = Poisson::new(lambda)
+            .unwrap_or_else(|_| panic!("Mean lambda should be positive and finite"));
+        let data = distribution
+            .sample_iter(rng)
+            .take(rows * cols)
+            .map(|count: f64| T::from(count).expect("Sampled count does not fit in the element type"))
+            .collect();
+        Matrix { rows, cols, data, layout: Layout::RowMajor }
+    }
+
+    pub fn random_poisson_seeded(rows: usize, cols: usize, lambda: f64, seed: u64) -> Matrix<T> {
+        Self::random_poisson_with_rng(rows, cols, lambda, &mut StdRng::seed_from_u64(seed))
+    }
+
+    pub fn random_binomial(rows: usize, cols: usize, trials: u64, probability: f64) -> Matrix<T> {
+        Self::random_binomial_with_rng(rows, cols, trials, probability, &mut rand::thread_rng())
+    }
+
+    pub fn random_binomial_with_rng<R>(rows: usize, cols: usize, trials: u64, probability: f64, rng: &mut R) -> Matrix<T>
+        where
+            R: rand::Rng + ?Sized
+    {
+        let distribution = Binomial::new(trials, probability)
+            .unwrap_or_else(|_| panic!("Probability should be between 0.0 and 1.0"));
+        let data = distribution
+            .sample_iter(rng)
+            .take(rows * cols)
+            .map(|count: u64| T::from(count).expect("Sampled count does not fit in the element type"))
+            .collect();
+        Matrix { rows, cols, data, layout: Layout::RowMajor }
+    }
+
+    pub fn random_binomial_seeded(rows: usize, cols: usize, trials: u64, probability: f64, seed: u64) -> Matrix<T> {
+        Self::random_binomial_with_rng(rows, cols, trials, probability, &mut StdRng::seed_from_u64(seed))
+    }
+}
+
+
+/// Generates structured random matrices from classical random matrix ensembles.
+///
+/// - `random_orthogonal` samples an `n x n` orthogonal matrix from the Haar (uniform) measure,
+///   by QR decomposing a matrix of standard normal entries and fixing the signs so that `R` has
+///   a positive diagonal (Mezzadri, "How to generate random matrices from the classical compact
+///   groups", 2007).
+/// - `random_spd` samples a symmetric positive definite matrix `B * B^T / n + I`, where `B` has
+///   standard normal entries. All eigenvalues are at least one.
+/// - `random_wishart` samples from the Wishart distribution `W_p(scale, dof)`, i.e. `X^T * X`
+///   where the `dof` rows of `X` are independent `N(0, scale)` vectors.
+/// - `random_goe` samples from the Gaussian orthogonal ensemble, `(A + A^T) / 2` with standard
+///   normal `A`: diagonal entries have variance 1 and off-diagonal entries variance 1/2.
+///
+/// # Panics
+///
+/// `random_wishart` will panic if `scale` is not symmetric positive definite.
+///
+/// # Examples
+///
+/// ```
+/// let q = Matrix::<f64>::random_orthogonal(4);
+/// // q.transpose() * q == I
+/// let w = Matrix::<f64>::random_wishart(&Matrix::identity(3), 10);
+/// ```
+impl<T> Matrix<T>
+    where
+        T: Real +
+        std::fmt::Display,
+        StandardNormal: Distribution<T>
+{
+    pub fn random_orthogonal(n: usize) -> Matrix<T> {
+        Self::random_orthogonal_with_rng(n, &mut rand::thread_rng())
+    }
+
+    pub fn random_orthogonal_with_rng<R>(n: usize, rng: &mut R) -> Matrix<T>
+        where
+            R: rand::Rng + ?Sized
+    {
+        let gaussian = Self::random_from_distribution_with_rng(n, n, &StandardNormal, rng);
+        #[allow(non_snake_case)]
+        let (mut Q, R) = gaussian.QR_decompose();
+        for j in 0..n {
+            if R[(j, j)] < T::zero() {
+                for i in 0..n {
+                    Q[(i, j)] = -Q[(i, j)];
+                }
+            }
+        }
+        Q
+    }
+
+    pub fn random_orthogonal_seeded(n: usize, seed: u64) -> Matrix<T> {
+        Self::random_orthogonal_with_rng(n, &mut StdRng::seed_from_u64(seed))
+    }
+
+    pub fn random_spd(n: usize) -> Matrix<T> {
+        Self::random_spd_with_rng(n, &mut rand::thread_rng())
+    }
+
+    pub fn random_spd_with_rng<R>(n: usize, rng: &mut R) -> Matrix<T>
+        where
+            R: rand::Rng + ?Sized
+    {
+        let b = Self::random_from_distribution_with_rng(n, n, &StandardNormal, rng);
+        let size = T::from(n.max(1)).unwrap();
+        (b.clone() * b.transpose()).scale(T::one() / size) + Matrix::identity(n)
+    }
+
+    pub fn random_spd_seeded(n: usize, seed: u64) -> Matrix<T> {
+        Self::random_spd_with_rng(n, &mut StdRng::seed_from_u64(seed))
+    }
+
+    pub fn random_wishart(scale: &Matrix<T>, dof: usize) -> Matrix<T> {
+        Self::random_wishart_with_rng(scale, dof, &mut rand::thread_rng())
+    }
+
+    pub fn random_wishart_with_rng<R>(scale: &Matrix<T>, dof: usize, rng: &mut R) -> Matrix<T>
+        where
+            R: rand::Rng + ?Sized
+    {
+        // Rows of Z * L^T are N(0, L * L^T) = N(0, scale)
+        let l = scale.cholesky();
+        let z = Self::random_from_distribution_with_rng(dof, scale.rows, &StandardNormal, rng);
+        let x = z * l.transpose();
+        x.transpose() * x
+    }
+
+    pub fn random_wishart_seeded(scale: &Matrix<T>, dof: usize, seed: u64) -> Matrix<T> {
+        Self::random_wishart_with_rng(scale, dof, &mut StdRng::seed_from_u64(seed))
+    }
+
+    pub fn random_goe(n: usize) -> Matrix<T> {
+        Self::random_goe_with_rng(n, &mut rand::thread_rng())
+    }
+
+    pub fn random_goe_with_rng<R>(n: usize, rng: &mut R) -> Matrix<T>
+        where
+            R: rand::Rng + ?Sized
+    {
+        let a = Self::random_from_distribution_with_rng(n, n, &StandardNormal, rng);
+        let half = T::one() / (T::one() + T::one());
+        (a.clone() + a.transpose()).scale(half)
+    }
+
+    pub fn random_goe_seeded(n: usize, seed: u64) -> Matrix<T> {
+        Self::random_goe_with_rng(n, &mut StdRng::seed_from_u64(seed))
+    }
+}
@@ -0,0 +1,37 @@
+use crate::{Matrix, Scalar};
+use num_rational::Ratio;
+
+/// Converts a matrix of integers to a matrix of rationals with denominator one, so the exact
+/// `Field` methods (`inverse`, `determinant`, `rref`, ...) can be used on it.
+///
+/// `Ratio` does not implement `SampleUniform`, so random rational matrices are created from
+/// random integer matrices, e.g. `Matrix::<i64>::random_uniform(3, 3, -9, 9).to_rational()`.
+///
+/// # Examples
+///
+/// ```
+/// let a = Matrix::from_vec(2, 2, vec![2_i64, 1, 1, 1]).to_rational();
+/// println!("{}", a.inverse());
+/// // Output:
+/// //  1 -1
+/// // -1  2
+/// let b = Matrix::from_vec(2, 2, vec![4_i64, 7, 2, 6]).to_rational();
+/// println!("{}", b.inverse());
+/// // Output:
+/// //  3/5 -7/10
+/// // -1/5   2/5
+/// ```
+impl<T> Matrix<T>
+    where
+        T: Scalar +
+        num_integer::Integer
+{
+    pub fn to_rational(&self) -> Matrix<Ratio<T>> {
+        Matrix {
+            rows: self.rows,
+            cols: self.cols,
+            data: self.data.iter().map(|&val| Ratio::from_integer(val)).collect(),
+            layout: self.layout,
+        }
+    }
+}
@@ -0,0 +1,207 @@
+//! Linear regression on a design matrix `X` (one observation per row, one regressor per column)
+//! and a column vector of responses `y`.
+//!
+//! - `ols` fits ordinary least squares, minimizing `||y - X b||^2`.
+//! - `ridge` adds the penalty `lambda * ||b||^2`, which shrinks the coefficients and keeps the
+//!   fit well defined for collinear regressors or more regressors than observations.
+//! - `wls` fits weighted least squares, minimizing `sum(w_i * (y_i - x_i b)^2)`, e.g. with
+//!   weights inversely proportional to the variances of the observations.
+//!
+//! The design matrix is used as given, so add a column of ones (see `with_intercept`) to fit an
+//! intercept. Note that `ridge` then also penalizes the intercept; center `X` and `y` first if
+//! it should not be.
+//!
+//! # Examples
+//!
+//! ```
+//! use MyMatrixLib::regression::{ols, with_intercept};
+//!
+//! let x = Matrix::from_vec(4, 1, vec![1.0, 2.0, 3.0, 4.0]);
+//! let y = Matrix::from_vec(4, 1, vec![3.1, 4.9, 7.2, 8.8]);
+//! let fit = ols(&with_intercept(&x), &y);
+//! println!("y = {} + {} x, R^2 = {}", fit.coefficients[(0, 0)], fit.coefficients[(1, 0)], fit.r_squared);
+//! let predictions = fit.predict(&with_intercept(&Matrix::from_vec(1, 1, vec![5.0])));
+//! ```
+//!
+//! # Notes
+//!
+//! The coefficients solve the normal equations `(X^T W X + lambda I) b = X^T W y` with the
+//! Cholesky decomposition of the left-hand side and two triangular substitutions, and the
+//! standard errors use the same factorization, so no inverse is ever formed. The normal
+//! equations square the condition number of `X`, so nearly collinear designs lose about twice
+//! as many digits as with a QR based solver; `ridge` with a small `lambda` is the usual remedy.
+//!
+//! Without a penalty, the columns of `X` are checked for linear dependence before solving: the
+//! Gram matrix `X^T W X` is scaled to a unit diagonal, and the fit panics if its rank (with
+//! pivots up to `RANK_TOLERANCE` treated as zero) is below the number of regressors. A pivot
+//! of the scaled Gram matrix is the squared sine of the angle between a column and the span
+//! of the columns eliminated before it, so this rejects designs where a column lies within
+//! about `1e-6` radians of the others, whose coefficients would have lost (almost) all
+//! accuracy.
+
+use crate::Matrix;
+
+/// Pivots of the scaled Gram matrix up to this value count as zero in the rank check of `ols`
+/// and `wls`, i.e. angles below about `1e-6` radians between a column and the span of the
+/// others.
+const RANK_TOLERANCE: f64 = 1e-12;
+
+/// The result of a linear regression with `n` observations and `p` regressors.
+///
+/// - `coefficients`: the `p x 1` fitted coefficients `b`.
+/// - `standard_errors`: the `p x 1` estimated standard deviations of the coefficients, from
+///   the covariance `s^2 A^-1 X^T W X A^-1` with `A = X^T W X + lambda I` (so `s^2 (X^T W X)^-1`
+///   without penalty), where `s^2` is the weighted residual sum of squares divided by the
+///   residual degrees of freedom `n - trace(A^-1 X^T W X)`, i.e. `n - p` without penalty. They
+///   are NaN if there are no residual degrees of freedom.
+/// - `residuals`: the `n x 1` residuals `y - X b` (not multiplied by the weights).
+/// - `r_squared`: the coefficient of determination `1 - RSS / TSS`, with sums of squares
+///   weighted by `w` and `TSS` taken around the weighted mean of `y`. It is only meaningful when
+///   the design includes an intercept.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LinearRegression {
+    pub coefficients: Matrix<f64>,
+    pub standard_errors: Matrix<f64>,
+    pub residuals: Matrix<f64>,
+    pub r_squared: f64,
+}
+
+impl LinearRegression {
+    /// Returns the predictions `X b` for a design matrix with the same regressors as the fit.
+    ///
+    /// # Panics
+    ///
+    /// This method will panic if `x` does not have one column per coefficient.
+    pub fn predict(&self, x: &Matrix<f64>) -> Matrix<f64> {
+        assert_eq!(x.cols, self.coefficients.rows,
+                   "Design matrix has {} columns, but the model has {} coefficients", x.cols, self.coefficients.rows);
+        x.clone() * self.coefficients.clone()
+    }
+}
+
+/// Returns the design matrix with a column of ones prepended, whose coefficient is the
+/// intercept.
+pub fn with_intercept(x: &Matrix<f64>) -> Matrix<f64> {
+    let mut result = Matrix::ones(x.rows, x.cols + 1);
+    for i in 0..x.rows {
+        for j in 0..x.cols {
+            result[(i, j + 1)] = x[(i, j)];
+        }
+    }
+    result
+}
+
+/// Fits ordinary least squares.
+///
+/// # Panics
+///
+/// This function will panic if `y` is not a column vector with one row per row of `x`, or if
+/// the columns of `x` are linearly dependent or nearly so, as described in the module
+/// documentation (in particular if there are fewer observations than regressors).
+pub fn ols(x: &Matrix<f64>, y: &Matrix<f64>) -> LinearRegression {
+    fit(x, y, None, 0.0)
+}
+
+/// Fits ridge regression with penalty `lambda`.
+///
+/// # Panics
+///
+/// This function will panic if `y` is not a column vector with one row per row of `x`, or if
+/// `lambda` is negative or NaN. With `lambda = 0`, it also panics in the same cases as `ols`.
+pub fn ridge(x: &Matrix<f64>, y: &Matrix<f64>, lambda: f64) -> LinearRegression {
+    assert!(lambda >= 0.0, "Ridge penalty must be non-negative, got {}", lambda);
+    fit(x, y, None, lambda)
+}
+
+/// Fits weighted least squares, with one weight per observation (given as a vector of any
+/// shape).
+///
+/// # Panics
+///
+/// This function will panic if `y` is not a column vector with one row per row of `x`, if the
+/// number of weights differs from the number of observations, if a weight is negative or NaN,
+/// or if the columns of `x` restricted to the observations of positive weight are linearly
+/// dependent or nearly so, as for `ols`.
+pub fn wls(x: &Matrix<f64>, y: &Matrix<f64>, weights: &Matrix<f64>) -> LinearRegression {
+    let weights = weights.as_slice();
+    assert_eq!(weights.len(), x.rows, "Expected {} weights, got {}", x.rows, weights.len());
+    assert!(weights.iter().all(|&w| w >= 0.0), "Weights must be non-negative");
+    fit(x, y, Some(weights), 0.0)
+}
+
+fn fit(x: &Matrix<f64>, y: &Matrix<f64>, weights: Option<&[f64]>, lambda: f64) -> LinearRegression {
+    assert_eq!(y.cols, 1, "Responses must be a column vector, got {} columns", y.cols);
+    assert_eq!(x.rows, y.rows, "Design matrix has {} rows, but there are {} responses", x.rows, y.rows);
+    let (n, p) = (x.rows, x.cols);
+    let weight = |i: usize| weights.map_or(1.0, |w| w[i]);
+
+    // Gram matrix G = X^T W X and right-hand side X^T W y, accumulated row by row
+    let mut gram = Matrix::zeros(p, p);
+    let mut rhs = Matrix::zeros(p, 1);
+    for i in 0..n {
+        let row: Vec<f64> = x.row_elements(i).collect();
+        let (w, yi) = (weight(i), y[(i, 0)]);
+        for j in 0..p {
+            rhs[(j, 0)] += w * row[j] * yi;
+            for k in 0..=j {
+                gram[(j, k)] += w * row[j] * row[k];
+            }
+        }
+    }
+    for j in 0..p {
+        for k in 0..j {
+            gram[(k, j)] = gram[(j, k)];
+        }
+    }
+    let mut system = gram.clone();
+    for j in 0..p {
+        system[(j, j)] += lambda;
+    }
+    if lambda == 0.0 {
+        check_full_rank(&gram);
+    }
+
+    let l = system.cholesky();
+    let coefficients = cholesky_solve(&l, &rhs);
+    let residuals = y.clone() - x.clone() * coefficients.clone();
+
+    let total_weight: f64 = (0..n).map(weight).sum();
+    let mean = (0..n).map(|i| weight(i) * y[(i, 0)]).sum::<f64>() / total_weight;
+    let rss: f64 = (0..n).map(|i| weight(i) * residuals[(i, 0)].powi(2)).sum();
+    let tss: f64 = (0..n).map(|i| weight(i) * (y[(i, 0)] - mean).powi(2)).sum();
+
+    // Covariance (up to s^2) A^-1 G A^-1, from two solves with the factorization of A
+    let half = cholesky_solve(&l, &gram);
+    let covariance = cholesky_solve(&l, &half.transpose());
+    let effective_parameters: f64 = (0..p).map(|j| half[(j, j)]).sum();
+    let degrees_of_freedom = n as f64 - effective_parameters;
+    let variance = if degrees_of_freedom > 0.0 { rss / degrees_of_freedom } else { f64::NAN };
+    let standard_errors = Matrix::from_vec(p, 1, (0..p).map(|j| (variance * covariance[(j, j)]).sqrt()).collect());
+
+    LinearRegression { coefficients, standard_errors, residuals, r_squared: 1.0 - rss / tss }
+}
+
+/// Panics if the columns whose Gram matrix is `gram` are linearly dependent up to
+/// `RANK_TOLERANCE`.
+fn check_full_rank(gram: &Matrix<f64>) {
+    let p = gram.rows;
+    let scales: Vec<f64> = (0..p).map(|j| gram[(j, j)].sqrt()).collect();
+    let mut scaled = Matrix::zeros(p, p);
+    for j in 0..p {
+        for k in 0..p {
+            // A zero column stays zero, so it is never a pivot
+            let scale = scales[j] * scales[k];
+            scaled[(j, k)] = if scale > 0.0 { gram[(j, k)] / scale } else { 0.0 };
+        }
+    }
+    let rank = scaled.rank(RANK_TOLERANCE);
+    assert!(rank == p, "Columns of the design matrix are linearly dependent (numerical rank {} of {})", rank, p);
+}
+
+/// Solves `L L^T X = B` by forward substitution with `L` and backward substitution with `L^T`.
+fn cholesky_solve(l: &Matrix<f64>, b: &Matrix<f64>) -> Matrix<f64> {
+    let mut x = b.clone();
+    l.forward_substitution(&mut x, false);
+    l.transpose().back_substitution(&mut x);
+    x
+}
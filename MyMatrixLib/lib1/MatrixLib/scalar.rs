@@ -0,0 +1,75 @@
+/// The element types a `Matrix` can hold: copyable numbers with the four arithmetic
+/// operations, a default value, zero and one.
+///
+/// `Scalar` is implemented for every type satisfying these bounds, including all primitive
+/// integer and float types, so generic code can be written as
+///
+/// ```
+/// fn scaled_trace<T: Scalar + 'static>(m: &Matrix<T>, factor: T) -> T {
+///     m.scale(factor).trace()
+/// }
+/// ```
+///
+/// instead of repeating the individual bounds. The operations with vectorized kernels for `f32`,
+/// `f64` and `i32` (`+`, `-`, `scale`, `dot` and `sum`) and `*`, which can call BLAS, choose the
+/// implementation by the type of the elements, so they also require `T: 'static`. `Real`
+/// includes this bound.
+pub trait Scalar:
+    std::ops::Add<Output=Self> +
+    std::ops::Sub<Output=Self> +
+    std::ops::Mul<Output=Self> +
+    std::ops::Div<Output=Self> +
+    std::default::Default +
+    std::clone::Clone +
+    std::marker::Copy +
+    num_traits::Zero +
+    num_traits::One
+{}
+
+impl<T> Scalar for T
+    where
+        T: std::ops::Add<Output=T> +
+        std::ops::Sub<Output=T> +
+        std::ops::Mul<Output=T> +
+        std::ops::Div<Output=T> +
+        std::default::Default +
+        std::clone::Clone +
+        std::marker::Copy +
+        num_traits::Zero +
+        num_traits::One
+{}
+
+/// A number type with exact division (up to rounding for floating point types), i.e. a field.
+///
+/// Gaussian elimination based methods (`LUP_decompose`, `solve`, `inverse`, `determinant` and
+/// `rref`) require a `Field`, since truncating integer division would silently give wrong
+/// results. The ordering and `abs` are used to choose pivots.
+///
+/// Unlike `Scalar`, a `Field` only has to be `Clone`, so arbitrary precision numbers can be used.
+/// Implemented for `f32`, `f64` and the rationals `num_rational::Ratio<T>` of a signed integer
+/// type `T`, both fixed width (e.g. `Rational64`) and arbitrary precision (`BigRational`), for
+/// which all of these methods are exact. The elimination methods, construction with `from_vec`,
+/// indexing, comparison and printing work for every `Field`; the rest of the API (arithmetic
+/// operators, products, layouts) still requires a `Scalar`.
+pub trait Field: Clone + PartialOrd + num_traits::Signed + 'static {}
+
+impl Field for f32 {}
+
+impl Field for f64 {}
+
+impl<T> Field for num_rational::Ratio<T>
+    where
+        T: Clone + num_integer::Integer + num_traits::Signed + 'static
+{}
+
+/// A floating point `Field`, such as `f32` or `f64`.
+///
+/// Operations that need square roots or a notion of precision (norms, decompositions,
+/// statistics, matrix functions) are only available for matrices of a `Real` type. Implemented
+/// for every type that is a `Field`, a `Scalar` and a `num_traits::Float`.
+pub trait Real: Field + Scalar + num_traits::Float {}
+
+impl<T> Real for T
+    where
+        T: Field + Scalar + num_traits::Float
+{}
@@ -0,0 +1,340 @@
+use std::any::TypeId;
+use std::ops::{Add, Mul, Sub};
+
+use crate::Scalar;
+
+/// Number of independent accumulators used by the portable reductions, so the compiler can
+/// keep them in one vector register instead of summing sequentially.
+const LANES: usize = 8;
+
+/// Element types with vectorized kernels: `f32`, `f64` and `i32`.
+///
+/// Each kernel uses AVX2 when the CPU supports it (detected at runtime on x86_64) and otherwise
+/// falls back to the portable kernels, which are written so that the compiler vectorizes them
+/// for the baseline instruction set (SSE2 on x86_64, NEON on aarch64). The `i32` operations
+/// wrap on overflow in every code path, so the results never depend on the CPU. They are only
+/// dispatched to in release builds, where the scalar `i32` operators wrap as well; debug builds
+/// keep the scalar operators, which panic on overflow.
+trait Kernel: Copy + 'static {
+    const ZERO: Self;
+
+    fn plus(self, other: Self) -> Self;
+
+    fn minus(self, other: Self) -> Self;
+
+    fn times(self, other: Self) -> Self;
+
+    fn add_slices(a: &mut [Self], b: &[Self]);
+
+    fn sub_slices(a: &mut [Self], b: &[Self]);
+
+    fn scale_slice(a: &mut [Self], factor: Self);
+
+    fn dot_slices(a: &[Self], b: &[Self]) -> Self;
+
+    fn sum_slice(a: &[Self]) -> Self;
+}
+
+macro_rules! impl_kernel {
+    ($t:ty, $avx2:ident, $plus:ident, $minus:ident, $times:ident) => {
+        impl Kernel for $t {
+            const ZERO: Self = 0 as $t;
+
+            fn plus(self, other: Self) -> Self {
+                self.$plus(other)
+            }
+
+            fn minus(self, other: Self) -> Self {
+                self.$minus(other)
+            }
+
+            fn times(self, other: Self) -> Self {
+                self.$times(other)
+            }
+
+            fn add_slices(a: &mut [Self], b: &[Self]) {
+                #[cfg(target_arch = "x86_64")]
+                if is_x86_feature_detected!("avx2") {
+                    // SAFETY: AVX2 support was detected at runtime
+                    return unsafe { avx2::$avx2::add(a, b) };
+                }
+                portable::add(a, b)
+            }
+
+            fn sub_slices(a: &mut [Self], b: &[Self]) {
+                #[cfg(target_arch = "x86_64")]
+                if is_x86_feature_detected!("avx2") {
+                    // SAFETY: AVX2 support was detected at runtime
+                    return unsafe { avx2::$avx2::sub(a, b) };
+                }
+                portable::sub(a, b)
+            }
+
+            fn scale_slice(a: &mut [Self], factor: Self) {
+                #[cfg(target_arch = "x86_64")]
+                if is_x86_feature_detected!("avx2") {
+                    // SAFETY: AVX2 support was detected at runtime
+                    return unsafe { avx2::$avx2::scale(a, factor) };
+                }
+                portable::scale(a, factor)
+            }
+
+            fn dot_slices(a: &[Self], b: &[Self]) -> Self {
+                #[cfg(target_arch = "x86_64")]
+                if is_x86_feature_detected!("avx2") {
+                    // SAFETY: AVX2 support was detected at runtime
+                    return unsafe { avx2::$avx2::dot(a, b) };
+                }
+                portable::dot(a, b)
+            }
+
+            fn sum_slice(a: &[Self]) -> Self {
+                #[cfg(target_arch = "x86_64")]
+                if is_x86_feature_detected!("avx2") {
+                    // SAFETY: AVX2 support was detected at runtime
+                    return unsafe { avx2::$avx2::sum(a) };
+                }
+                portable::sum(a)
+            }
+        }
+    };
+}
+
+impl_kernel!(f32, f32x8, add, sub, mul);
+impl_kernel!(f64, f64x4, add, sub, mul);
+impl_kernel!(i32, i32x8, wrapping_add, wrapping_sub, wrapping_mul);
+
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+////////////////////////////////////// PORTABLE KERNELS ///////////////////////////////////////////
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+
+mod portable {
+    use super::{Kernel, LANES};
+
+    pub(super) fn add<K: Kernel>(a: &mut [K], b: &[K]) {
+        for (x, &y) in a.iter_mut().zip(b) {
+            *x = x.plus(y);
+        }
+    }
+
+    pub(super) fn sub<K: Kernel>(a: &mut [K], b: &[K]) {
+        for (x, &y) in a.iter_mut().zip(b) {
+            *x = x.minus(y);
+        }
+    }
+
+    pub(super) fn scale<K: Kernel>(a: &mut [K], factor: K) {
+        for x in a.iter_mut() {
+            *x = x.times(factor);
+        }
+    }
+
+    pub(super) fn dot<K: Kernel>(a: &[K], b: &[K]) -> K {
+        let mut acc = [K::ZERO; LANES];
+        let (a_chunks, b_chunks) = (a.chunks_exact(LANES), b.chunks_exact(LANES));
+        let tail = a_chunks.remainder().iter().zip(b_chunks.remainder())
+            .fold(K::ZERO, |sum, (&x, &y)| sum.plus(x.times(y)));
+        for (x, y) in a_chunks.zip(b_chunks) {
+            for lane in 0..LANES {
+                acc[lane] = acc[lane].plus(x[lane].times(y[lane]));
+            }
+        }
+        acc.iter().fold(tail, |sum, &x| sum.plus(x))
+    }
+
+    pub(super) fn sum<K: Kernel>(a: &[K]) -> K {
+        let mut acc = [K::ZERO; LANES];
+        let chunks = a.chunks_exact(LANES);
+        let tail = chunks.remainder().iter().fold(K::ZERO, |sum, &x| sum.plus(x));
+        for x in chunks {
+            for lane in 0..LANES {
+                acc[lane] = acc[lane].plus(x[lane]);
+            }
+        }
+        acc.iter().fold(tail, |sum, &x| sum.plus(x))
+    }
+}
+
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+////////////////////////////////////// AVX2 KERNELS ///////////////////////////////////////////////
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+
+/// AVX2 kernels, one module per element type. Every function requires AVX2 support, and the
+/// remaining elements after the last full vector are handled by the portable kernels.
+#[cfg(target_arch = "x86_64")]
+mod avx2 {
+    macro_rules! avx2_kernels {
+        ($name:ident, $t:ty, $vec:ty, $width:expr, $load:ident, $store:ident, $set1:ident,
+         $add:ident, $sub:ident, $mul:ident) => {
+            pub(super) mod $name {
+                use std::arch::x86_64::*;
+                use crate::simd::{portable, Kernel};
+
+                const WIDTH: usize = $width;
+
+                /// Sums the lanes of `vector` onto `init`.
+                #[target_feature(enable = "avx2")]
+                unsafe fn reduce(vector: $vec, init: $t) -> $t {
+                    let mut lanes = [<$t as Kernel>::ZERO; WIDTH];
+                    $store(lanes.as_mut_ptr() as *mut _, vector);
+                    lanes.iter().fold(init, |sum, &x| sum.plus(x))
+                }
+
+                #[target_feature(enable = "avx2")]
+                pub(crate) unsafe fn add(a: &mut [$t], b: &[$t]) {
+                    let len = a.len().min(b.len());
+                    let full = len - len % WIDTH;
+                    for i in (0..full).step_by(WIDTH) {
+                        let x = $load(a.as_ptr().add(i) as *const _);
+                        let y = $load(b.as_ptr().add(i) as *const _);
+                        $store(a.as_mut_ptr().add(i) as *mut _, $add(x, y));
+                    }
+                    portable::add(&mut a[full..len], &b[full..len]);
+                }
+
+                #[target_feature(enable = "avx2")]
+                pub(crate) unsafe fn sub(a: &mut [$t], b: &[$t]) {
+                    let len = a.len().min(b.len());
+                    let full = len - len % WIDTH;
+                    for i in (0..full).step_by(WIDTH) {
+                        let x = $load(a.as_ptr().add(i) as *const _);
+                        let y = $load(b.as_ptr().add(i) as *const _);
+                        $store(a.as_mut_ptr().add(i) as *mut _, $sub(x, y));
+                    }
+                    portable::sub(&mut a[full..len], &b[full..len]);
+                }
+
+                #[target_feature(enable = "avx2")]
+                pub(crate) unsafe fn scale(a: &mut [$t], factor: $t) {
+                    let full = a.len() - a.len() % WIDTH;
+                    let factors = $set1(factor);
+                    for i in (0..full).step_by(WIDTH) {
+                        let x = $load(a.as_ptr().add(i) as *const _);
+                        $store(a.as_mut_ptr().add(i) as *mut _, $mul(x, factors));
+                    }
+                    portable::scale(&mut a[full..], factor);
+                }
+
+                #[target_feature(enable = "avx2")]
+                pub(crate) unsafe fn dot(a: &[$t], b: &[$t]) -> $t {
+                    let len = a.len().min(b.len());
+                    let full = len - len % WIDTH;
+                    let mut acc = $set1(<$t as Kernel>::ZERO);
+                    for i in (0..full).step_by(WIDTH) {
+                        let x = $load(a.as_ptr().add(i) as *const _);
+                        let y = $load(b.as_ptr().add(i) as *const _);
+                        acc = $add(acc, $mul(x, y));
+                    }
+                    reduce(acc, portable::dot(&a[full..len], &b[full..len]))
+                }
+
+                #[target_feature(enable = "avx2")]
+                pub(crate) unsafe fn sum(a: &[$t]) -> $t {
+                    let full = a.len() - a.len() % WIDTH;
+                    let mut acc = $set1(<$t as Kernel>::ZERO);
+                    for i in (0..full).step_by(WIDTH) {
+                        acc = $add(acc, $load(a.as_ptr().add(i) as *const _));
+                    }
+                    reduce(acc, portable::sum(&a[full..]))
+                }
+            }
+        };
+    }
+
+    avx2_kernels!(f32x8, f32, __m256, 8, _mm256_loadu_ps, _mm256_storeu_ps, _mm256_set1_ps,
+                  _mm256_add_ps, _mm256_sub_ps, _mm256_mul_ps);
+    avx2_kernels!(f64x4, f64, __m256d, 4, _mm256_loadu_pd, _mm256_storeu_pd, _mm256_set1_pd,
+                  _mm256_add_pd, _mm256_sub_pd, _mm256_mul_pd);
+    avx2_kernels!(i32x8, i32, __m256i, 8, _mm256_loadu_si256, _mm256_storeu_si256, _mm256_set1_epi32,
+                  _mm256_add_epi32, _mm256_sub_epi32, _mm256_mullo_epi32);
+}
+
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+////////////////////////////////////// DISPATCH ///////////////////////////////////////////////////
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+
+/// Reinterprets a slice of `T` as a slice of `K`.
+///
+/// # Panics
+///
+/// This function will panic if `T` and `K` are different types.
+fn cast<T: 'static, K: 'static>(slice: &[T]) -> &[K] {
+    assert_eq!(TypeId::of::<T>(), TypeId::of::<K>());
+    // SAFETY: `T` and `K` are the same type
+    unsafe { std::slice::from_raw_parts(slice.as_ptr() as *const K, slice.len()) }
+}
+
+fn cast_mut<T: 'static, K: 'static>(slice: &mut [T]) -> &mut [K] {
+    assert_eq!(TypeId::of::<T>(), TypeId::of::<K>());
+    // SAFETY: `T` and `K` are the same type
+    unsafe { std::slice::from_raw_parts_mut(slice.as_mut_ptr() as *mut K, slice.len()) }
+}
+
+fn cast_value<T: Copy + 'static, K: Copy + 'static>(value: T) -> K {
+    cast::<T, K>(std::slice::from_ref(&value))[0]
+}
+
+/// Runs `$kernel` with the type alias `$K` set to `$T` if `$T` has vectorized kernels, and
+/// evaluates `$fallback` otherwise. The `i32` kernels cannot detect overflow, so `i32` takes the
+/// fallback when debug assertions are enabled.
+macro_rules! dispatch {
+    ($T:ty, $K:ident => $kernel:expr, $fallback:expr) => {
+        if TypeId::of::<$T>() == TypeId::of::<f64>() {
+            type $K = f64;
+            $kernel
+        } else if TypeId::of::<$T>() == TypeId::of::<f32>() {
+            type $K = f32;
+            $kernel
+        } else if TypeId::of::<$T>() == TypeId::of::<i32>() && !cfg!(debug_assertions) {
+            type $K = i32;
+            $kernel
+        } else {
+            $fallback
+        }
+    };
+}
+
+/// Adds `b` to `a` element-wise; the buffers must have the same length.
+pub(crate) fn add<T: Scalar + 'static>(a: &mut [T], b: &[T]) {
+    dispatch!(T, K => K::add_slices(cast_mut(a), cast(b)), {
+        for (x, &y) in a.iter_mut().zip(b) {
+            *x = *x + y;
+        }
+    })
+}
+
+/// Subtracts `b` from `a` element-wise; the buffers must have the same length.
+pub(crate) fn sub<T: Scalar + 'static>(a: &mut [T], b: &[T]) {
+    dispatch!(T, K => K::sub_slices(cast_mut(a), cast(b)), {
+        for (x, &y) in a.iter_mut().zip(b) {
+            *x = *x - y;
+        }
+    })
+}
+
+/// Multiplies every element of `a` by `factor`.
+pub(crate) fn scale<T: Scalar + 'static>(a: &mut [T], factor: T) {
+    dispatch!(T, K => K::scale_slice(cast_mut(a), cast_value(factor)), {
+        for x in a.iter_mut() {
+            *x = *x * factor;
+        }
+    })
+}
+
+/// Inner product of two buffers of the same length.
+pub(crate) fn dot<T: Scalar + 'static>(a: &[T], b: &[T]) -> T {
+    dispatch!(T, K => cast_value(K::dot_slices(cast(a), cast(b))),
+              a.iter().zip(b).fold(T::zero(), |sum, (&x, &y)| sum + x * y))
+}
+
+/// Sum of all elements of a buffer.
+pub(crate) fn sum<T: Scalar + 'static>(a: &[T]) -> T {
+    dispatch!(T, K => cast_value(K::sum_slice(cast(a))),
+              a.iter().fold(T::zero(), |sum, &x| sum + x))
+}
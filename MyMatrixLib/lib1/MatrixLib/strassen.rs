@@ -0,0 +1,197 @@
+use std::cell::Cell;
+
+use crate::{Layout, Matrix, Scalar};
+
+thread_local! {
+    /// The size above which `*` switches to Strassen-Winograd on this thread, or `usize::MAX`
+    /// when disabled.
+    static STRASSEN_THRESHOLD: Cell<usize> = const { Cell::new(usize::MAX) };
+}
+
+/// Runs `f` with `*` multiplying square matrices larger than `threshold` with the
+/// Strassen-Winograd algorithm, for all element types, on the current thread only. By default
+/// every product uses the classical algorithm.
+///
+/// Products of two `n x n` matrices with `n > threshold` are split recursively into blocks
+/// until the blocks are at most `threshold x threshold`, which are then multiplied with the
+/// classical triple loop. A threshold of `0` is treated as `1`. See `mul_strassen` for the
+/// accuracy of the algorithm; the setting has no effect on products of rectangular matrices.
+/// Calls may be nested, and the previous setting is restored when `f` returns or panics.
+///
+/// # Examples
+///
+/// ```
+/// let a = Matrix::<f64>::random_uniform(500, 500, -1.0, 1.0);
+/// let b = with_strassen_threshold(256, || a.clone() * a.clone());
+/// ```
+pub fn with_strassen_threshold<R>(threshold: usize, f: impl FnOnce() -> R) -> R {
+    struct Restore(usize);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            STRASSEN_THRESHOLD.with(|current| current.set(self.0));
+        }
+    }
+
+    let _restore = Restore(STRASSEN_THRESHOLD.with(|current| current.replace(threshold.max(1))));
+    f()
+}
+
+/// Returns the threshold set by `with_strassen_threshold` on the current thread, or `None` if
+/// Strassen-Winograd multiplication is disabled.
+pub fn strassen_threshold() -> Option<usize> {
+    match STRASSEN_THRESHOLD.with(Cell::get) {
+        usize::MAX => None,
+        threshold => Some(threshold),
+    }
+}
+
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+////////////////////////////////////// STRASSEN-WINOGRAD //////////////////////////////////////////
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+
+/// Multiplies two square matrices with the Strassen-Winograd algorithm, recursing while the
+/// blocks are larger than `threshold x threshold` (a `threshold` of `0` is treated as `1`).
+///
+/// Each level splits the matrices into `2 x 2` blocks and forms the product from 7 block
+/// products and 15 block additions instead of 8 products, so the cost is `O(n^2.81)` instead of
+/// `O(n^3)`. Matrices of odd size are padded with a zero row and column at that level.
+///
+/// # Accuracy
+///
+/// For integers and other exact types (e.g. `Rational64`, `ModInt`) the result is identical to
+/// the classical product. The intermediate block sums can be larger than any entry of the
+/// result, so they can overflow where the classical product does not; with wrapping arithmetic
+/// (release builds) the result is still exact whenever it fits in `T`, while debug builds panic
+/// on the overflow.
+///
+/// For floating point types the classical product satisfies the componentwise bound
+/// `|C - fl(C)| <= n u |A| |B|`, where `u` is the unit roundoff. Strassen-Winograd only
+/// satisfies a normwise bound
+///
+/// `max|C - fl(C)| <= c(n) u max|A| max|B|`, with `c(n) ~ (n / n0)^log2(18) (n0^2 + 6 n0)`
+///
+/// for blocks of size `n0 = threshold`, so the error grows faster with `n` (roughly like
+/// `n^4.17` instead of `n`), and entries of the result that are small compared to the norms of
+/// `A` and `B` may lose all relative accuracy. A larger threshold gives fewer levels of recursion
+/// and a smaller error.
+///
+/// # Panics
+///
+/// This method will panic if the matrices are not square or have different sizes.
+///
+/// # Examples
+///
+/// ```
+/// let a = Matrix::<i64>::random_uniform(100, 100, -10, 10);
+/// let b = Matrix::<i64>::random_uniform(100, 100, -10, 10);
+/// assert_eq!(a.mul_strassen(&b, 16), a * b);
+/// ```
+impl<T> Matrix<T>
+    where
+        T: Scalar
+{
+    pub fn mul_strassen(&self, other: &Matrix<T>, threshold: usize) -> Matrix<T> {
+        assert!(self.rows == self.cols && other.rows == other.cols && self.rows == other.rows,
+                "Strassen multiplication requires square matrices of the same size, got {} x {} and {} x {}",
+                self.rows, self.cols, other.rows, other.cols);
+
+        let n = self.rows;
+        let data = strassen(&self.row_major(), &other.row_major(), n, threshold.max(1));
+        Matrix { rows: n, cols: n, data, layout: Layout::RowMajor }
+    }
+}
+
+/// Multiplies two `n x n` row-major buffers with the classical `i-k-j` loop.
+fn classical<T: Scalar>(a: &[T], b: &[T], n: usize) -> Vec<T> {
+    let mut c = vec![T::zero(); n * n];
+    for i in 0..n {
+        for k in 0..n {
+            let a_ik = a[i * n + k];
+            for (c_ij, &b_kj) in c[i * n..(i + 1) * n].iter_mut().zip(&b[k * n..(k + 1) * n]) {
+                *c_ij = *c_ij + a_ik * b_kj;
+            }
+        }
+    }
+    c
+}
+
+fn add<T: Scalar>(a: &[T], b: &[T]) -> Vec<T> {
+    a.iter().zip(b).map(|(&x, &y)| x + y).collect()
+}
+
+fn sub<T: Scalar>(a: &[T], b: &[T]) -> Vec<T> {
+    a.iter().zip(b).map(|(&x, &y)| x - y).collect()
+}
+
+/// Splits an `n x n` buffer into its four `h x h` quadrants, where `h = ceil(n / 2)`. For odd
+/// `n` the last row and column of the quadrants are zero padding.
+fn split<T: Scalar>(m: &[T], n: usize) -> [Vec<T>; 4] {
+    let h = n.div_ceil(2);
+    let mut blocks = [vec![T::zero(); h * h], vec![T::zero(); h * h],
+                      vec![T::zero(); h * h], vec![T::zero(); h * h]];
+    for i in 0..n {
+        for j in 0..n {
+            blocks[2 * (i / h) + j / h][(i % h) * h + j % h] = m[i * n + j];
+        }
+    }
+    blocks
+}
+
+/// Inverse of `split`, dropping the padding.
+fn join<T: Scalar>(blocks: [&[T]; 4], n: usize) -> Vec<T> {
+    let h = n.div_ceil(2);
+    let mut m = vec![T::zero(); n * n];
+    for i in 0..n {
+        for j in 0..n {
+            m[i * n + j] = blocks[2 * (i / h) + j / h][(i % h) * h + j % h];
+        }
+    }
+    m
+}
+
+fn strassen<T: Scalar>(a: &[T], b: &[T], n: usize, threshold: usize) -> Vec<T> {
+    if n <= threshold {
+        return classical(a, b, n);
+    }
+    let h = n.div_ceil(2);
+    let [a11, a12, a21, a22] = split(a, n);
+    let [b11, b12, b21, b22] = split(b, n);
+
+    // Winograd's variant: 7 block products and 15 block additions
+    let s1 = add(&a21, &a22);
+    let s2 = sub(&s1, &a11);
+    let s3 = sub(&a11, &a21);
+    let s4 = sub(&a12, &s2);
+    let t1 = sub(&b12, &b11);
+    let t2 = sub(&b22, &t1);
+    let t3 = sub(&b22, &b12);
+    let t4 = sub(&t2, &b21);
+
+    let p1 = strassen(&a11, &b11, h, threshold);
+    let p2 = strassen(&a12, &b21, h, threshold);
+    let p3 = strassen(&s4, &b22, h, threshold);
+    let p4 = strassen(&a22, &t4, h, threshold);
+    let p5 = strassen(&s1, &t1, h, threshold);
+    let p6 = strassen(&s2, &t2, h, threshold);
+    let p7 = strassen(&s3, &t3, h, threshold);
+
+    let c11 = add(&p1, &p2);
+    let u2 = add(&p1, &p6);
+    let u3 = add(&u2, &p7);
+    let u4 = add(&u2, &p5);
+    let c12 = add(&u4, &p3);
+    let c21 = sub(&u3, &p4);
+    let c22 = add(&u3, &p5);
+    join([&c11, &c12, &c21, &c22], n)
+}
+
+/// Returns the Strassen-Winograd product if `with_strassen_threshold` enables it on this thread
+/// for these matrices, i.e. both are `n x n` with `n` above the threshold.
+pub(crate) fn try_mul<T: Scalar>(a: &Matrix<T>, b: &Matrix<T>) -> Option<Matrix<T>> {
+    let threshold = STRASSEN_THRESHOLD.with(Cell::get);
+    let n = a.rows;
+    (a.cols == n && b.rows == n && b.cols == n && n > threshold).then(|| a.mul_strassen(b, threshold))
+}
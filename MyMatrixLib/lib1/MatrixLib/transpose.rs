@@ -0,0 +1,137 @@
+use crate::{Matrix, Scalar};
+
+/// Blocks of at most this many elements are transposed directly; larger ones are split in two.
+const LEAF_SIZE: usize = 256;
+
+/// Tile size of the blocked in-place transpose of square matrices.
+const TILE: usize = 32;
+
+/// Writes the transpose of rows `rows` and columns `cols` of the row-major `src` (with
+/// `src_cols` columns) into `dst` (with `src_rows` columns).
+///
+/// The block is halved along its longer side until it has at most `LEAF_SIZE` elements, so the
+/// leaves fit in cache whatever its size (a cache-oblivious algorithm) and both buffers are
+/// traversed in small tiles rather than one of them column by column.
+fn transpose_block<T: Scalar>(src: &[T], src_cols: usize, dst: &mut [T], src_rows: usize,
+                              rows: std::ops::Range<usize>, cols: std::ops::Range<usize>) {
+    if rows.len() * cols.len() <= LEAF_SIZE {
+        for i in rows {
+            for j in cols.clone() {
+                dst[j * src_rows + i] = src[i * src_cols + j];
+            }
+        }
+    } else if rows.len() >= cols.len() {
+        let middle = rows.start + rows.len() / 2;
+        transpose_block(src, src_cols, dst, src_rows, rows.start..middle, cols.clone());
+        transpose_block(src, src_cols, dst, src_rows, middle..rows.end, cols);
+    } else {
+        let middle = cols.start + cols.len() / 2;
+        transpose_block(src, src_cols, dst, src_rows, rows.clone(), cols.start..middle);
+        transpose_block(src, src_cols, dst, src_rows, rows, middle..cols.end);
+    }
+}
+
+/// Transposes the matrix without allocating a new one.
+///
+/// - `transpose_into` writes the transpose into `target`, reusing its buffer (which is only
+///   reallocated if it is too small), so repeated transposes in a loop do not allocate.
+/// - `transpose_in_place` transposes the matrix itself. Square matrices swap their elements
+///   across the diagonal tile by tile. Rectangular matrices are permuted by following the cycles
+///   of the transposition permutation, where the element at index `p` of the flat buffer moves
+///   to index `p * rows mod (rows * cols - 1)`. Each cycle is rotated once, from its smallest
+///   index, which is found by walking the cycle, so no memory is allocated. Finding the cycles
+///   takes `O(n log n)` time on average for `n` elements, so this is slower than an
+///   out-of-place transpose, in exchange for not needing a second buffer.
+///
+/// Both give the same result as `transpose`, which uses the same cache-oblivious blocking as
+/// `transpose_into`, and keep the layout of the matrix. For a transpose that moves no elements
+/// at all, see `into_transposed`.
+///
+/// # Examples
+///
+/// ```
+/// let mut a = Matrix::from_vec(2, 3, vec![1, 2, 3, 4, 5, 6]);
+/// let mut b = Matrix::zeros(1, 1);
+/// a.transpose_into(&mut b);
+/// a.transpose_in_place();
+/// assert_eq!(a, Matrix::from_vec(3, 2, vec![1, 4, 2, 5, 3, 6]));
+/// assert_eq!(a, b);
+/// ```
+impl<T> Matrix<T>
+    where
+        T: Scalar
+{
+    pub fn transpose_into(&self, target: &mut Matrix<T>) {
+        let (rows, cols) = self.buffer_shape();
+        target.data.clear();
+        target.data.resize(self.data.len(), T::zero());
+        target.rows = self.cols;
+        target.cols = self.rows;
+        target.layout = self.layout;
+        transpose_block(&self.data, cols, &mut target.data, rows, 0..rows, 0..cols);
+    }
+
+    pub fn transpose_in_place(&mut self) {
+        let (rows, cols) = self.buffer_shape();
+        transpose_buffer_in_place(&mut self.data, rows, cols);
+        std::mem::swap(&mut self.rows, &mut self.cols);
+    }
+}
+
+/// Transposes the `rows x cols` row-major buffer `data` in place.
+pub(crate) fn transpose_buffer_in_place<T: Scalar>(data: &mut [T], rows: usize, cols: usize) {
+    if rows == cols {
+        transpose_square_in_place(data, rows);
+    } else {
+        transpose_cycles_in_place(data, rows, cols);
+    }
+}
+
+fn transpose_square_in_place<T: Scalar>(data: &mut [T], n: usize) {
+    for tile_row in (0..n).step_by(TILE) {
+        for tile_col in (tile_row..n).step_by(TILE) {
+            for i in tile_row..(tile_row + TILE).min(n) {
+                for j in tile_col.max(i + 1)..(tile_col + TILE).min(n) {
+                    data.swap(i * n + j, j * n + i);
+                }
+            }
+        }
+    }
+}
+
+fn transpose_cycles_in_place<T: Scalar>(data: &mut [T], rows: usize, cols: usize) {
+    if rows <= 1 || cols <= 1 {
+        // The flat buffer of a single row or column is also that of its transpose
+        return;
+    }
+    // The first and last elements stay in place
+    let modulus = data.len() - 1;
+    let next = |p: usize| ((p as u128 * rows as u128) % modulus as u128) as usize;
+    for start in 1..modulus {
+        // Only rotate the cycle from its smallest index
+        let mut p = next(start);
+        while p > start {
+            p = next(p);
+        }
+        if p < start {
+            continue;
+        }
+
+        let mut value = data[start];
+        let mut p = start;
+        loop {
+            p = next(p);
+            std::mem::swap(&mut value, &mut data[p]);
+            if p == start {
+                break;
+            }
+        }
+    }
+}
+
+/// Returns the transpose of the `rows x cols` row-major buffer `data`.
+pub(crate) fn transposed<T: Scalar>(data: &[T], rows: usize, cols: usize) -> Vec<T> {
+    let mut result = vec![T::zero(); data.len()];
+    transpose_block(data, cols, &mut result, rows, 0..rows, 0..cols);
+    result
+}
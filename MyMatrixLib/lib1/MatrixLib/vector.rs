@@ -0,0 +1,323 @@
+use crate::{Matrix, Real, Scalar};
+
+/// A generic dense vector type that interoperates with `Matrix`.
+///
+/// A `Vector` carries no orientation of its own: it acts as a column vector when multiplied
+/// from the left by a matrix (`Matrix<T> * Vector<T>`), and can be turned into either a `1 x n`
+/// row matrix or an `n x 1` column matrix with `to_row_matrix` and `to_col_matrix`.
+///
+/// # Type Parameters
+///
+/// - `T`: The type of data stored in the vector. It has the same requirements as the element
+///   type of `Matrix`.
+///
+/// # Fields
+///
+/// - `data`: The elements of the vector.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Vector<T>
+    where
+        T: Scalar
+{
+    data: Vec<T>,
+}
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+////////////////////////////////////// STANDARD METHODS ///////////////////////////////////////////
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Implements the `fmt::Display` trait for the `Vector` struct. The elements are printed on a
+/// single line, each followed by a space character, like a `1 x n` `Matrix`.
+impl<T> std::fmt::Display for Vector<T>
+    where
+        T: Scalar +
+        std::fmt::Display
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        for val in self.data.iter() {
+            write!(f, "{} ", val)?;
+        }
+        writeln!(f)
+    }
+}
+
+
+/// Implements element-wise addition and subtraction of vectors.
+///
+/// # Panics
+///
+/// These operations will panic if the vectors do not have the same length.
+impl<T> std::ops::Add for Vector<T>
+    where
+        T: Scalar
+{
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        assert_eq!(self.len(), other.len());
+
+        let data = self.data.iter().zip(other.data.iter()).map(|(&a, &b)| a + b).collect();
+        Vector { data }
+    }
+}
+
+impl<T> std::ops::Sub for Vector<T>
+    where
+        T: Scalar
+{
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        assert_eq!(self.len(), other.len());
+
+        let data = self.data.iter().zip(other.data.iter()).map(|(&a, &b)| a - b).collect();
+        Vector { data }
+    }
+}
+
+
+/// Multiplies a matrix by a vector, treating the vector as a column vector.
+///
+/// Each element of the result is the dot product of one row of the matrix (read directly from
+/// the data buffer, contiguously for row-major matrices) with the vector, so no intermediate
+/// `n x 1` matrix is allocated and the general matrix-matrix loop is avoided.
+///
+/// # Panics
+///
+/// This function will panic if the number of columns of the matrix is not equal to the length
+/// of the vector.
+///
+/// # Examples
+///
+/// ```
+/// let a = Matrix::from_vec(2, 3, vec![1, 2, 3, 4, 5, 6]);
+/// let x = Vector::from_vec(vec![1, 0, -1]);
+/// assert_eq!(a * x, Vector::from_vec(vec![-2, -2]));
+/// ```
+impl<T> std::ops::Mul<Vector<T>> for Matrix<T>
+    where
+        T: Scalar
+{
+    type Output = Vector<T>;
+
+    fn mul(self, other: Vector<T>) -> Vector<T> {
+        assert_eq!(self.cols, other.len());
+
+        let data = (0..self.rows)
+            .map(|row| {
+                self.row_elements(row)
+                    .zip(other.data.iter())
+                    .fold(T::zero(), |sum, (a, &b)| sum + a * b)
+            })
+            .collect();
+        Vector { data }
+    }
+}
+
+
+/// Provides read-only and mutable access to vector elements using the indexing operator `[]`.
+impl<T> std::ops::Index<usize> for Vector<T>
+    where
+        T: Scalar
+{
+    type Output = T;
+
+    fn index(&self, i: usize) -> &Self::Output {
+        &self.data[i]
+    }
+}
+
+impl<T> std::ops::IndexMut<usize> for Vector<T>
+    where
+        T: Scalar
+{
+    fn index_mut(&mut self, i: usize) -> &mut T {
+        &mut self.data[i]
+    }
+}
+
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+////////////////////////////////////// SPECIAL METHODS ////////////////////////////////////////////
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+
+/// Creates vectors, and converts between vectors and `Matrix` values.
+///
+/// - `new`, `zeros` and `ones` create a vector of the given length filled with the default
+///   value, zero or one respectively.
+/// - `from_vec` takes ownership of the given elements.
+/// - `from_matrix` copies the elements of a `1 x n` or `n x 1` matrix, such as the ones
+///   returned by `Matrix::get_row` and `Matrix::get_col`.
+/// - `to_row_matrix` and `to_col_matrix` copy the vector into a `1 x n` or `n x 1` matrix.
+///
+/// # Panics
+///
+/// `from_matrix` will panic if the matrix has more than one row and more than one column.
+///
+/// # Examples
+///
+/// ```
+/// let m = Matrix::from_vec(2, 2, vec![1, 2, 3, 4]);
+/// let v = Vector::from_matrix(&m.get_col(1));
+/// assert_eq!(v, Vector::from_vec(vec![2, 4]));
+/// assert_eq!(v.to_col_matrix(), m.get_col(1));
+/// ```
+impl<T> Vector<T>
+    where
+        T: Scalar
+{
+    pub fn new(len: usize) -> Self {
+        Vector { data: vec![T::default(); len] }
+    }
+
+    pub fn zeros(len: usize) -> Self {
+        Vector { data: vec![T::zero(); len] }
+    }
+
+    pub fn ones(len: usize) -> Self {
+        Vector { data: vec![T::one(); len] }
+    }
+
+    pub fn from_vec(data: Vec<T>) -> Self {
+        Vector { data }
+    }
+
+    pub fn from_matrix(matrix: &Matrix<T>) -> Self {
+        // Panics unless the matrix is a row or column vector
+        matrix.vector_len();
+        // The buffer of a vector is the same in both layouts
+        Vector { data: matrix.data.clone() }
+    }
+
+    pub fn to_row_matrix(&self) -> Matrix<T> {
+        Matrix::from_vec(1, self.len(), self.data.clone())
+    }
+
+    pub fn to_col_matrix(&self) -> Matrix<T> {
+        Matrix::from_vec(self.len(), 1, self.data.clone())
+    }
+
+    /// Returns the number of elements in the vector.
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Returns `true` if the vector has no elements.
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Returns the elements of the vector as a slice.
+    pub fn as_slice(&self) -> &[T] {
+        &self.data
+    }
+}
+
+
+/// Returns a copy of row `row` or column `col` of the matrix as a `Vector`.
+///
+/// # Panics
+///
+/// These methods will panic if the index is out of bounds.
+impl<T> Matrix<T>
+    where
+        T: Scalar
+{
+    pub fn row_vector(&self, row: usize) -> Vector<T> {
+        Vector { data: self.get_row(row).data }
+    }
+
+    pub fn col_vector(&self, col: usize) -> Vector<T> {
+        Vector { data: self.get_col(col).data }
+    }
+}
+
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+////////////////////////////////////// LINALG METHODS /////////////////////////////////////////////
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+
+/// Computes the dot product of two vectors and multiplies a vector by a scalar.
+///
+/// # Panics
+///
+/// `dot` will panic if the vectors do not have the same length.
+///
+/// # Examples
+///
+/// ```
+/// let a = Vector::from_vec(vec![1, 2, 3]);
+/// let b = Vector::from_vec(vec![4, 5, 6]);
+/// assert_eq!(a.dot(&b), 32);
+/// assert_eq!(a.scale(2), Vector::from_vec(vec![2, 4, 6]));
+/// ```
+impl<T> Vector<T>
+    where
+        T: Scalar
+{
+    pub fn dot(&self, other: &Vector<T>) -> T {
+        assert_eq!(self.len(), other.len(), "Vectors must have the same length");
+
+        self.data.iter().zip(other.data.iter()).fold(T::zero(), |sum, (&a, &b)| sum + a * b)
+    }
+
+    pub fn scale(&self, factor: T) -> Vector<T> {
+        Vector { data: self.data.iter().map(|&x| x * factor).collect() }
+    }
+}
+
+
+/// Computes norms of a floating point vector and normalizes it.
+///
+/// - `norm` is the Euclidean (L2) norm `sqrt(sum_i x_i^2)`.
+/// - `norm_l1` is the sum of absolute values.
+/// - `norm_inf` is the largest absolute value.
+/// - `norm_p` is the general `(sum_i |x_i|^p)^(1/p)` for `p >= 1`.
+/// - `normalize` returns the vector divided by its Euclidean norm.
+///
+/// # Panics
+///
+/// `norm_p` will panic if `p < 1`, and `normalize` will panic for the zero vector.
+///
+/// # Examples
+///
+/// ```
+/// let v = Vector::from_vec(vec![3.0, -4.0]);
+/// assert_eq!(v.norm(), 5.0);
+/// assert_eq!(v.norm_l1(), 7.0);
+/// assert_eq!(v.norm_inf(), 4.0);
+/// assert_eq!(v.normalize(), Vector::from_vec(vec![0.6, -0.8]));
+/// ```
+impl<T> Vector<T>
+    where
+        T: Real
+{
+    pub fn norm(&self) -> T {
+        self.dot(self).sqrt()
+    }
+
+    pub fn norm_l1(&self) -> T {
+        self.data.iter().fold(T::zero(), |sum, &x| sum + x.abs())
+    }
+
+    pub fn norm_inf(&self) -> T {
+        self.data.iter().fold(T::zero(), |max, &x| max.max(x.abs()))
+    }
+
+    pub fn norm_p(&self, p: T) -> T {
+        if p < T::one() {
+            panic!("Norm exponent p should be at least 1");
+        }
+        self.data.iter().fold(T::zero(), |sum, &x| sum + x.abs().powf(p)).powf(T::one() / p)
+    }
+
+    pub fn normalize(&self) -> Vector<T> {
+        let norm = self.norm();
+        if norm == T::zero() {
+            panic!("Cannot normalize the zero vector");
+        }
+        self.scale(T::one() / norm)
+    }
+}
@@ -0,0 +1,98 @@
+//! Compares the vectorized element-wise kernels with the indexed loops they replaced.
+//!
+//! Run with `cargo bench --bench elementwise`. Each group has a `kernel` benchmark (the matrix
+//! operation) and an `indexed` benchmark (the same operation through `Index`, element by
+//! element), for square matrices of increasing size.
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use MyMatrixLib::{Matrix, Scalar};
+
+const SIZES: [usize; 3] = [64, 256, 1024];
+
+fn indexed_add<T: Scalar>(a: &Matrix<T>, b: &Matrix<T>, n: usize) -> Matrix<T> {
+    let mut result = Matrix::zeros(n, n);
+    for i in 0..n {
+        for j in 0..n {
+            result[(i, j)] = a[(i, j)] + b[(i, j)];
+        }
+    }
+    result
+}
+
+fn indexed_scale<T: Scalar>(a: &Matrix<T>, factor: T, n: usize) -> Matrix<T> {
+    let mut result = Matrix::zeros(n, n);
+    for i in 0..n {
+        for j in 0..n {
+            result[(i, j)] = a[(i, j)] * factor;
+        }
+    }
+    result
+}
+
+fn indexed_sum<T: Scalar>(a: &Matrix<T>, n: usize) -> T {
+    let mut sum = T::zero();
+    for i in 0..n {
+        for j in 0..n {
+            sum = sum + a[(i, j)];
+        }
+    }
+    sum
+}
+
+fn indexed_dot<T: Scalar>(a: &Matrix<T>, b: &Matrix<T>, len: usize) -> T {
+    (0..len).fold(T::zero(), |sum, k| sum + a[(0, k)] * b[(0, k)])
+}
+
+macro_rules! bench_type {
+    ($fn_name:ident, $t:ty, $low:expr, $high:expr) => {
+        fn $fn_name(c: &mut Criterion) {
+            let name = stringify!($t);
+            for &n in SIZES.iter() {
+                let a = Matrix::<$t>::random_uniform_seeded(n, n, $low, $high, 1);
+                let b = Matrix::<$t>::random_uniform_seeded(n, n, $low, $high, 2);
+                let (x, y) = (Matrix::<$t>::random_uniform_seeded(1, n * n, $low, $high, 3),
+                              Matrix::<$t>::random_uniform_seeded(1, n * n, $low, $high, 4));
+                let factor = b[(0, 0)];
+
+                let mut group = c.benchmark_group(format!("{}/{}", name, n));
+                group.throughput(Throughput::Elements((n * n) as u64));
+                // `+` consumes its operands, so both variants clone them
+                group.bench_function(BenchmarkId::new("add", "kernel"), |bench| {
+                    bench.iter(|| black_box(a.clone()) + black_box(b.clone()))
+                });
+                group.bench_function(BenchmarkId::new("add", "indexed"), |bench| {
+                    bench.iter(|| {
+                        let (a, b) = (black_box(a.clone()), black_box(b.clone()));
+                        indexed_add(&a, &b, n)
+                    })
+                });
+                group.bench_function(BenchmarkId::new("scale", "kernel"), |bench| {
+                    bench.iter(|| black_box(&a).scale(factor))
+                });
+                group.bench_function(BenchmarkId::new("scale", "indexed"), |bench| {
+                    bench.iter(|| indexed_scale(black_box(&a), factor, n))
+                });
+                group.bench_function(BenchmarkId::new("sum", "kernel"), |bench| {
+                    bench.iter(|| black_box(&a).sum())
+                });
+                group.bench_function(BenchmarkId::new("sum", "indexed"), |bench| {
+                    bench.iter(|| indexed_sum(black_box(&a), n))
+                });
+                group.bench_function(BenchmarkId::new("dot", "kernel"), |bench| {
+                    bench.iter(|| black_box(&x).dot(black_box(&y)))
+                });
+                group.bench_function(BenchmarkId::new("dot", "indexed"), |bench| {
+                    bench.iter(|| indexed_dot(black_box(&x), black_box(&y), n * n))
+                });
+                group.finish();
+            }
+        }
+    };
+}
+
+bench_type!(bench_f32, f32, -1.0, 1.0);
+bench_type!(bench_f64, f64, -1.0, 1.0);
+bench_type!(bench_i32, i32, -1000, 1000);
+
+criterion_group!(benches, bench_f32, bench_f64, bench_i32);
+criterion_main!(benches);
@@ -0,0 +1,45 @@
+//! Compares the blocked and in-place transposes with an element by element transpose.
+//!
+//! Run with `cargo bench --bench transpose`.
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use MyMatrixLib::Matrix;
+
+const SHAPES: [(usize, usize); 4] = [(256, 256), (1024, 1024), (2048, 512), (4096, 3)];
+
+fn indexed_transpose(a: &Matrix<f64>, rows: usize, cols: usize) -> Matrix<f64> {
+    let mut result = Matrix::zeros(cols, rows);
+    for i in 0..rows {
+        for j in 0..cols {
+            result[(j, i)] = a[(i, j)];
+        }
+    }
+    result
+}
+
+fn bench_transpose(c: &mut Criterion) {
+    let mut group = c.benchmark_group("transpose");
+    for &(rows, cols) in SHAPES.iter() {
+        let a = Matrix::<f64>::random_uniform_seeded(rows, cols, -1.0, 1.0, 1);
+        let shape = format!("{}x{}", rows, cols);
+        group.throughput(Throughput::Elements((rows * cols) as u64));
+        group.bench_function(BenchmarkId::new("indexed", &shape), |bench| {
+            bench.iter(|| indexed_transpose(black_box(&a), rows, cols))
+        });
+        group.bench_function(BenchmarkId::new("blocked", &shape), |bench| {
+            bench.iter(|| black_box(&a).transpose())
+        });
+        let mut target = Matrix::zeros(cols, rows);
+        group.bench_function(BenchmarkId::new("into", &shape), |bench| {
+            bench.iter(|| black_box(&a).transpose_into(&mut target))
+        });
+        let mut b = a.clone();
+        group.bench_function(BenchmarkId::new("in_place", &shape), |bench| {
+            bench.iter(|| black_box(&mut b).transpose_in_place())
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_transpose);
+criterion_main!(benches);
@@ -121,7 +121,6 @@ pub fn max<T>(slice: &[T]) -> T
             + std::marker::Copy
             + num_traits::Zero
             + num_traits::One
-            + num_traits::Signed
             + PartialOrd,
 
 {
@@ -147,6 +146,9 @@ pub fn max<T>(slice: &[T]) -> T
 /// let idx = arg_max(&v);
 /// assert_eq!(idx, 5);
 /// ```
+///
+/// If the maximum occurs more than once, the index of the first occurrence is returned.
+/// This function assumes that the input slice is not empty.
 pub fn arg_max<T>(slice: &[T]) -> usize
     where
         T: std::ops::Add<Output = T>
@@ -162,8 +164,8 @@ pub fn arg_max<T>(slice: &[T]) -> usize
 {
     slice.iter()
         .enumerate()
-        .fold((T::zero(), 0), |(max_val, max_idx), (idx, val)| {
-            if *val >= max_val {
+        .fold((slice[0], 0), |(max_val, max_idx), (idx, val)| {
+            if *val > max_val {
                 (*val, idx)
             } else {
                 (max_val, max_idx)
@@ -203,3 +205,118 @@ pub fn sum<T>(slice: &[T]) -> T
 {
     slice.iter().fold(T::default(), |a, &b| a + b)
 }
+
+
+/// Returns the minimum value in the given slice.
+///
+/// # Arguments
+///
+/// * `slice` - A slice of type `T` for which the minimum value is to be determined.
+///
+/// # Examples
+///
+/// ```
+/// use rust_linalg::min;
+///
+/// let v = vec![1, 2, 3, 4, 5];
+/// assert_eq!(min(&v), 1);
+///
+/// let v = vec![-1.0, -2.0, -3.0, -4.0, -5.0];
+/// assert_eq!(min(&v), -5.0);
+/// ```
+pub fn min<T>(slice: &[T]) -> T
+    where T: std::ops::Add<Output = T>
+            + std::ops::Sub<Output = T>
+            + std::ops::Mul<Output = T>
+            + std::ops::Div<Output = T>
+            + std::default::Default
+            + std::clone::Clone
+            + std::marker::Copy
+            + num_traits::Zero
+            + num_traits::One
+            + PartialOrd,
+
+{
+    slice.iter().fold(slice[0], |a, &b| if a < b { a } else { b })
+}
+
+
+/// Returns the index of the minimum element in the slice.
+///
+/// # Arguments
+///
+/// * `slice`: A slice of type `&[T]` where `T` is a numeric type that implements
+///   the `std::ops::Add`, `std::ops::Sub`, `std::ops::Mul`, `std::ops::Div`,
+///   `std::default::Default`, `std::clone::Clone`, `std::marker::Copy`,
+///   `num_traits::Zero`, `num_traits::One` and `PartialOrd` traits.
+///
+/// # Example
+///
+/// ```
+/// use my_math_lib::arg_min;
+///
+/// let v = vec![3, 1, 4, 1, 5, 9, 2, 6, 5, 3, 5];
+/// let idx = arg_min(&v);
+/// assert_eq!(idx, 1);
+/// ```
+///
+/// If the minimum occurs more than once, the index of the first occurrence is returned.
+/// This function assumes that the input slice is not empty.
+pub fn arg_min<T>(slice: &[T]) -> usize
+    where
+        T: std::ops::Add<Output = T>
+        + std::ops::Sub<Output = T>
+        + std::ops::Mul<Output = T>
+        + std::ops::Div<Output = T>
+        + std::default::Default
+        + std::clone::Clone
+        + std::marker::Copy
+        + num_traits::Zero
+        + num_traits::One
+        + PartialOrd
+{
+    slice.iter()
+        .enumerate()
+        .fold((slice[0], 0), |(min_val, min_idx), (idx, val)| {
+            if *val < min_val {
+                (*val, idx)
+            } else {
+                (min_val, min_idx)
+            }
+        })
+        .1
+}
+
+
+/// Returns the product of all elements in the input slice.
+///
+/// # Arguments
+///
+/// * `slice`: A slice of elements of type `T`.
+///
+/// # Example
+///
+/// ```
+/// use my_math_lib::product;
+///
+/// let arr = [1, 2, 3, 4, 5];
+/// let result = product(&arr);
+/// assert_eq!(result, 120);
+/// ```
+///
+/// The product of an empty slice is `T::one()`.
+pub fn product<T>(slice: &[T]) -> T
+    where
+        T: std::ops::Add<Output = T>
+        + std::ops::Sub<Output = T>
+        + std::ops::Mul<Output = T>
+        + std::ops::Div<Output = T>
+        + std::default::Default
+        + std::clone::Clone
+        + std::marker::Copy
+        + num_traits::Zero
+        + num_traits::One
+        + PartialOrd
+{
+    slice.iter().fold(T::one(), |a, &b| a * b)
+}
@@ -0,0 +1,542 @@
+//! Unoptimized reference implementations of the BLAS and LAPACK routines used by the `blas`
+//! feature of `MyMatrixLib`: `?gemm`, `?getrf`, `?getrs`, `?potrf`, `?geqrf`, `?orgqr` and
+//! `?syev` for `f32` (`s`) and `f64` (`d`).
+//!
+//! The routines are exported with the Fortran calling convention of the standard libraries
+//! (lower case names with a trailing underscore, all arguments passed by pointer, column-major
+//! storage and 1-based pivot indices), so linking this crate provides the same symbols as
+//! OpenBLAS or the Netlib reference libraries. It is pure Rust and builds offline, so it is meant
+//! for testing the `blas` feature rather than for speed.
+//!
+//! The loops of `?gemm`, `?getrf`, `?getrs` and `?potrf` accumulate in the same order as the
+//! pure-Rust implementations of `MyMatrixLib`, so the two normally agree to the last bit. `?geqrf`
+//! and `?orgqr` use the normalized Householder reflectors of LAPACK, and `?syev` the cyclic Jacobi
+//! method rather than a tridiagonal reduction, so they only agree up to rounding.
+
+use std::os::raw::{c_char, c_int};
+
+/// Returns whether a `trans` argument asks for the transposed matrix.
+fn is_transposed(trans: c_char, routine: &str, position: usize) -> bool {
+    match trans as u8 {
+        b'N' | b'n' => false,
+        b'T' | b't' | b'C' | b'c' => true,
+        _ => illegal_value(routine, position),
+    }
+}
+
+/// Reports an illegal argument like the reference `xerbla` and stops.
+fn illegal_value(routine: &str, position: usize) -> ! {
+    panic!(" ** On entry to {} parameter number {} had an illegal value", routine, position)
+}
+
+/// Converts a dimension argument, which must be non-negative.
+fn dimension(value: c_int, routine: &str, position: usize) -> usize {
+    usize::try_from(value).unwrap_or_else(|_| illegal_value(routine, position))
+}
+
+macro_rules! reference_routines {
+    ($t:ty, $gemm:ident, $getrf:ident, $getrs:ident, $potrf:ident, $geqrf:ident, $orgqr:ident, $syev:ident) => {
+        /// Computes `C = alpha * op(A) * op(B) + beta * C`, where `op(X)` is `X` or `X^T`
+        /// according to `transa` and `transb`, `op(A)` is `m x k` and `op(B)` is `k x n`. When
+        /// `beta` is zero, `C` is not read.
+        ///
+        /// # Safety
+        ///
+        /// All pointers must be valid, and the matrices must have the sizes given by the
+        /// dimensions and leading dimensions.
+        #[no_mangle]
+        pub unsafe extern "C" fn $gemm(transa: *const c_char, transb: *const c_char,
+                                       m: *const c_int, n: *const c_int, k: *const c_int,
+                                       alpha: *const $t, a: *const $t, lda: *const c_int,
+                                       b: *const $t, ldb: *const c_int,
+                                       beta: *const $t, c: *mut $t, ldc: *const c_int) {
+            let routine = stringify!($gemm);
+            let (trans_a, trans_b) = (is_transposed(*transa, routine, 1), is_transposed(*transb, routine, 2));
+            let (m, n, k) = (dimension(*m, routine, 3), dimension(*n, routine, 4), dimension(*k, routine, 5));
+            let (lda, ldb, ldc) = (*lda as usize, *ldb as usize, *ldc as usize);
+            if lda < (if trans_a { k } else { m }).max(1) {
+                illegal_value(routine, 8);
+            }
+            if ldb < (if trans_b { n } else { k }).max(1) {
+                illegal_value(routine, 10);
+            }
+            if ldc < m.max(1) {
+                illegal_value(routine, 13);
+            }
+
+            let (alpha, beta) = (*alpha, *beta);
+            for j in 0..n {
+                for i in 0..m {
+                    let mut sum: $t = 0.0;
+                    for l in 0..k {
+                        let a_il = if trans_a { *a.add(l + i * lda) } else { *a.add(i + l * lda) };
+                        let b_lj = if trans_b { *b.add(j + l * ldb) } else { *b.add(l + j * ldb) };
+                        sum += a_il * b_lj;
+                    }
+                    let c_ij = c.add(i + j * ldc);
+                    *c_ij = if beta == 0.0 { alpha * sum } else { alpha * sum + beta * *c_ij };
+                }
+            }
+        }
+
+        /// Computes the LU factorization `A = P * L * U` of the `m x n` matrix `A` with partial
+        /// pivoting, overwriting `A` with `L` (without its unit diagonal) and `U`. Row `i` was
+        /// swapped with row `ipiv[i]` (1-based). On return `info` is `0`, `-i` if argument `i`
+        /// was illegal, or `i > 0` if `U[i - 1, i - 1]` is exactly zero, in which case the
+        /// factorization is completed but `U` is singular.
+        ///
+        /// # Safety
+        ///
+        /// All pointers must be valid, `A` must be `lda x n` and `ipiv` must have `min(m, n)`
+        /// elements.
+        #[no_mangle]
+        pub unsafe extern "C" fn $getrf(m: *const c_int, n: *const c_int, a: *mut $t, lda: *const c_int,
+                                        ipiv: *mut c_int, info: *mut c_int) {
+            *info = 0;
+            if *m < 0 {
+                *info = -1;
+            } else if *n < 0 {
+                *info = -2;
+            } else if *lda < (*m).max(1) {
+                *info = -4;
+            }
+            if *info != 0 {
+                return;
+            }
+            let (m, n, lda) = (*m as usize, *n as usize, *lda as usize);
+            let at = |i: usize, j: usize| a.add(i + j * lda);
+
+            for j in 0..m.min(n) {
+                // The first row with the largest absolute value in the column
+                let mut pivot = j;
+                for i in (j + 1)..m {
+                    if (*at(i, j)).abs() > (*at(pivot, j)).abs() {
+                        pivot = i;
+                    }
+                }
+                *ipiv.add(j) = pivot as c_int + 1;
+                if pivot != j {
+                    for col in 0..n {
+                        std::ptr::swap(at(j, col), at(pivot, col));
+                    }
+                }
+                if *at(j, j) == 0.0 {
+                    if *info == 0 {
+                        *info = j as c_int + 1;
+                    }
+                    continue;
+                }
+
+                for i in (j + 1)..m {
+                    let factor = *at(i, j) / *at(j, j);
+                    *at(i, j) = factor;
+                    for col in (j + 1)..n {
+                        *at(i, col) -= factor * *at(j, col);
+                    }
+                }
+            }
+        }
+
+        /// Solves `A * X = B` (`trans` is `N`) or `A^T * X = B` (`trans` is `T` or `C`) for the
+        /// `n x n` matrix `A` factorized by `getrf`, overwriting the `n x nrhs` matrix `B` with
+        /// `X`. On return `info` is `0`, or `-i` if argument `i` was illegal.
+        ///
+        /// # Safety
+        ///
+        /// All pointers must be valid, `A` must be `lda x n`, `ipiv` must have `n` elements and
+        /// `B` must be `ldb x nrhs`.
+        #[no_mangle]
+        pub unsafe extern "C" fn $getrs(trans: *const c_char, n: *const c_int, nrhs: *const c_int,
+                                        a: *const $t, lda: *const c_int, ipiv: *const c_int,
+                                        b: *mut $t, ldb: *const c_int, info: *mut c_int) {
+            *info = 0;
+            let transposed = match *trans as u8 {
+                b'N' | b'n' => false,
+                b'T' | b't' | b'C' | b'c' => true,
+                _ => {
+                    *info = -1;
+                    return;
+                }
+            };
+            if *n < 0 {
+                *info = -2;
+            } else if *nrhs < 0 {
+                *info = -3;
+            } else if *lda < (*n).max(1) {
+                *info = -5;
+            } else if *ldb < (*n).max(1) {
+                *info = -8;
+            }
+            if *info != 0 {
+                return;
+            }
+            let (n, nrhs, lda, ldb) = (*n as usize, *nrhs as usize, *lda as usize, *ldb as usize);
+            let a_at = |i: usize, j: usize| *a.add(i + j * lda);
+            let pivot = |i: usize| *ipiv.add(i) as usize - 1;
+
+            for col in 0..nrhs {
+                let x = |i: usize| b.add(i + col * ldb);
+                if !transposed {
+                    // Apply the row swaps, then solve L * y = P * b and U * x = y
+                    for i in 0..n {
+                        std::ptr::swap(x(i), x(pivot(i)));
+                    }
+                    for i in 0..n {
+                        let mut sum = *x(i);
+                        for j in 0..i {
+                            sum -= a_at(i, j) * *x(j);
+                        }
+                        *x(i) = sum;
+                    }
+                    for i in (0..n).rev() {
+                        let mut sum = *x(i);
+                        for j in (i + 1)..n {
+                            sum -= a_at(i, j) * *x(j);
+                        }
+                        *x(i) = sum / a_at(i, i);
+                    }
+                } else {
+                    // Solve U^T * z = b and L^T * y = z, then undo the row swaps
+                    for i in 0..n {
+                        let mut sum = *x(i);
+                        for j in 0..i {
+                            sum -= a_at(j, i) * *x(j);
+                        }
+                        *x(i) = sum / a_at(i, i);
+                    }
+                    for i in (0..n).rev() {
+                        let mut sum = *x(i);
+                        for j in (i + 1)..n {
+                            sum -= a_at(j, i) * *x(j);
+                        }
+                        *x(i) = sum;
+                    }
+                    for i in (0..n).rev() {
+                        std::ptr::swap(x(i), x(pivot(i)));
+                    }
+                }
+            }
+        }
+
+        /// Computes the Cholesky factorization of the symmetric positive definite `n x n` matrix
+        /// `A`, `A = U^T * U` (`uplo` is `U`) or `A = L * L^T` (`uplo` is `L`), reading and
+        /// overwriting only that triangle of `A`. On return `info` is `0`, `-i` if argument `i`
+        /// was illegal, or `i > 0` if the leading minor of order `i` is not positive definite.
+        ///
+        /// # Safety
+        ///
+        /// All pointers must be valid and `A` must be `lda x n`.
+        #[no_mangle]
+        pub unsafe extern "C" fn $potrf(uplo: *const c_char, n: *const c_int, a: *mut $t, lda: *const c_int,
+                                        info: *mut c_int) {
+            *info = 0;
+            let upper = match *uplo as u8 {
+                b'U' | b'u' => true,
+                b'L' | b'l' => false,
+                _ => {
+                    *info = -1;
+                    return;
+                }
+            };
+            if *n < 0 {
+                *info = -2;
+            } else if *lda < (*n).max(1) {
+                *info = -4;
+            }
+            if *info != 0 {
+                return;
+            }
+            let (n, lda) = (*n as usize, *lda as usize);
+            // Element (i, j) of L, with i >= j, stored as U^T when computing U
+            let l = |i: usize, j: usize| if upper { a.add(j + i * lda) } else { a.add(i + j * lda) };
+
+            for j in 0..n {
+                let mut diagonal = *l(j, j);
+                for k in 0..j {
+                    diagonal -= *l(j, k) * *l(j, k);
+                }
+                if diagonal <= 0.0 || diagonal.is_nan() {
+                    *info = j as c_int + 1;
+                    return;
+                }
+                *l(j, j) = diagonal.sqrt();
+
+                for i in (j + 1)..n {
+                    let mut sum = *l(i, j);
+                    for k in 0..j {
+                        sum -= *l(i, k) * *l(j, k);
+                    }
+                    *l(i, j) = sum / *l(j, j);
+                }
+            }
+        }
+
+        /// Computes the QR factorization `A = Q * R` of the `m x n` matrix `A` with Householder
+        /// reflections, overwriting the upper triangle of `A` with `R` and the part below the
+        /// diagonal with the reflectors. `Q = H_1 * ... * H_k` for `k = min(m, n)`, where
+        /// `H_j = I - tau[j] * v_j * v_j^T` and `v_j` has zeros above row `j`, a one in row `j`
+        /// (not stored) and the rest in column `j` of `A`. The workspace must have
+        /// `lwork >= max(1, n)` elements; with `lwork = -1` only that size is returned in
+        /// `work[0]`. On return `info` is `0`, or `-i` if argument `i` was illegal.
+        ///
+        /// # Safety
+        ///
+        /// All pointers must be valid, `A` must be `lda x n`, `tau` must have `min(m, n)`
+        /// elements and `work` must have `max(1, lwork)` elements.
+        #[no_mangle]
+        pub unsafe extern "C" fn $geqrf(m: *const c_int, n: *const c_int, a: *mut $t, lda: *const c_int,
+                                        tau: *mut $t, work: *mut $t, lwork: *const c_int, info: *mut c_int) {
+            *info = 0;
+            if *m < 0 {
+                *info = -1;
+            } else if *n < 0 {
+                *info = -2;
+            } else if *lda < (*m).max(1) {
+                *info = -4;
+            } else if *lwork < (*n).max(1) && *lwork != -1 {
+                *info = -7;
+            }
+            if *info != 0 {
+                return;
+            }
+            if *lwork == -1 {
+                *work = (*n).max(1) as $t;
+                return;
+            }
+            let (m, n, lda) = (*m as usize, *n as usize, *lda as usize);
+            let at = |i: usize, j: usize| a.add(i + j * lda);
+
+            for j in 0..m.min(n) {
+                // Reflector mapping column j onto beta * e_j, with beta of the opposite sign to
+                // the diagonal element to avoid cancellation
+                let alpha = *at(j, j);
+                let mut norm_squared: $t = 0.0;
+                for i in (j + 1)..m {
+                    norm_squared += *at(i, j) * *at(i, j);
+                }
+                if norm_squared == 0.0 {
+                    *tau.add(j) = 0.0;
+                    continue;
+                }
+                let beta = -alpha.signum() * alpha.hypot(norm_squared.sqrt());
+                *tau.add(j) = (beta - alpha) / beta;
+                for i in (j + 1)..m {
+                    *at(i, j) /= alpha - beta;
+                }
+                *at(j, j) = beta;
+
+                apply_reflector(at, *tau.add(j), j, m, (j + 1)..n);
+            }
+        }
+
+        /// Overwrites the `m x n` matrix `A` with the first `n` columns of `Q = H_1 * ... * H_k`,
+        /// given the `k` reflectors returned by `geqrf` in the first `k` columns of `A` and in
+        /// `tau`, where `m >= n >= k`. The workspace must have `lwork >= max(1, n)` elements;
+        /// with `lwork = -1` only that size is returned in `work[0]`. On return `info` is `0`,
+        /// or `-i` if argument `i` was illegal.
+        ///
+        /// # Safety
+        ///
+        /// All pointers must be valid, `A` must be `lda x n`, `tau` must have `k` elements and
+        /// `work` must have `max(1, lwork)` elements.
+        #[no_mangle]
+        pub unsafe extern "C" fn $orgqr(m: *const c_int, n: *const c_int, k: *const c_int, a: *mut $t,
+                                        lda: *const c_int, tau: *const $t, work: *mut $t, lwork: *const c_int,
+                                        info: *mut c_int) {
+            *info = 0;
+            if *m < 0 {
+                *info = -1;
+            } else if *n < 0 || *n > *m {
+                *info = -2;
+            } else if *k < 0 || *k > *n {
+                *info = -3;
+            } else if *lda < (*m).max(1) {
+                *info = -5;
+            } else if *lwork < (*n).max(1) && *lwork != -1 {
+                *info = -8;
+            }
+            if *info != 0 {
+                return;
+            }
+            if *lwork == -1 {
+                *work = (*n).max(1) as $t;
+                return;
+            }
+            let (m, n, k, lda) = (*m as usize, *n as usize, *k as usize, *lda as usize);
+            let at = |i: usize, j: usize| a.add(i + j * lda);
+
+            // Apply the reflectors to the columns of the identity from the last one, so that
+            // column j is only needed as a reflector until it is overwritten
+            for j in k..n {
+                for i in 0..m {
+                    *at(i, j) = 0.0;
+                }
+                *at(j, j) = 1.0;
+            }
+            for j in (0..k).rev() {
+                let tau_j = *tau.add(j);
+                apply_reflector(at, tau_j, j, m, (j + 1)..n);
+                // Column j of H_j is e_j - tau_j * v_j
+                for i in (j + 1)..m {
+                    *at(i, j) *= -tau_j;
+                }
+                *at(j, j) = 1.0 - tau_j;
+                for i in 0..j {
+                    *at(i, j) = 0.0;
+                }
+            }
+        }
+
+        /// Computes the eigenvalues and, if `jobz` is `V`, the eigenvectors of the symmetric
+        /// `n x n` matrix `A`, reading only the triangle of `A` given by `uplo` (`U` or `L`). The
+        /// eigenvalues are returned in ascending order in `w`, and with `jobz = V` the
+        /// orthonormal eigenvectors overwrite the columns of `A` in the same order. The
+        /// workspace must have `lwork >= max(1, 3 * n - 1)` elements; with `lwork = -1` only
+        /// that size is returned in `work[0]`. On return `info` is `0`, `-i` if argument `i` was
+        /// illegal, or `i > 0` if the iteration did not converge, where `i` is the number of
+        /// off-diagonal elements that are still not zero.
+        ///
+        /// # Safety
+        ///
+        /// All pointers must be valid, `A` must be `lda x n`, `w` must have `n` elements and
+        /// `work` must have `max(1, lwork)` elements.
+        #[no_mangle]
+        pub unsafe extern "C" fn $syev(jobz: *const c_char, uplo: *const c_char, n: *const c_int, a: *mut $t,
+                                       lda: *const c_int, w: *mut $t, work: *mut $t, lwork: *const c_int,
+                                       info: *mut c_int) {
+            *info = 0;
+            let vectors = match *jobz as u8 {
+                b'V' | b'v' => true,
+                b'N' | b'n' => false,
+                _ => {
+                    *info = -1;
+                    return;
+                }
+            };
+            let upper = match *uplo as u8 {
+                b'U' | b'u' => true,
+                b'L' | b'l' => false,
+                _ => {
+                    *info = -2;
+                    return;
+                }
+            };
+            if *n < 0 {
+                *info = -3;
+            } else if *lda < (*n).max(1) {
+                *info = -5;
+            } else if *lwork < (3 * *n - 1).max(1) && *lwork != -1 {
+                *info = -8;
+            }
+            if *info != 0 {
+                return;
+            }
+            if *lwork == -1 {
+                *work = (3 * *n - 1).max(1) as $t;
+                return;
+            }
+            let (n, lda) = (*n as usize, *lda as usize);
+
+            // The full matrix from the given triangle, and the accumulated rotations, both
+            // column-major
+            let mut s = vec![0.0 as $t; n * n];
+            let mut v = vec![0.0 as $t; n * n];
+            for j in 0..n {
+                for i in 0..n {
+                    let (row, col) = if (i <= j) == upper { (i, j) } else { (j, i) };
+                    s[i + j * n] = *a.add(row + col * lda);
+                }
+                v[j + j * n] = 1.0;
+            }
+
+            // Cyclic Jacobi sweeps, each applying a rotation to every off-diagonal pair
+            const MAX_SWEEPS: usize = 100;
+            let frobenius_squared = s.iter().fold(0.0, |sum: $t, &x| sum + x * x);
+            let tolerance = <$t>::EPSILON * <$t>::EPSILON * frobenius_squared;
+            let off_diagonal = |s: &[$t]| {
+                (0..n).flat_map(|q| (0..q).map(move |p| (p, q))).fold(0.0, |sum: $t, (p, q)| sum + s[p + q * n] * s[p + q * n])
+            };
+            let mut sweeps = 0;
+            loop {
+                if off_diagonal(&s) <= tolerance {
+                    break;
+                }
+                if sweeps == MAX_SWEEPS {
+                    let remaining = (0..n).flat_map(|q| (0..q).map(move |p| (p, q)))
+                        .filter(|&(p, q)| s[p + q * n] != 0.0).count();
+                    *info = remaining.max(1) as c_int;
+                    return;
+                }
+                sweeps += 1;
+                for p in 0..n {
+                    for q in (p + 1)..n {
+                        if s[p + q * n] == 0.0 {
+                            continue;
+                        }
+                        // Rotation angle that annihilates s(p, q)
+                        let theta = (s[q + q * n] - s[p + p * n]) / (2.0 * s[p + q * n]);
+                        let t = theta.signum() / (theta.abs() + (theta * theta + 1.0).sqrt());
+                        let c = 1.0 / (t * t + 1.0).sqrt();
+                        let sine = t * c;
+
+                        for k in 0..n {
+                            let (skp, skq) = (s[k + p * n], s[k + q * n]);
+                            s[k + p * n] = c * skp - sine * skq;
+                            s[k + q * n] = sine * skp + c * skq;
+                        }
+                        for k in 0..n {
+                            let (spk, sqk) = (s[p + k * n], s[q + k * n]);
+                            s[p + k * n] = c * spk - sine * sqk;
+                            s[q + k * n] = sine * spk + c * sqk;
+                        }
+                        for k in 0..n {
+                            let (vkp, vkq) = (v[k + p * n], v[k + q * n]);
+                            v[k + p * n] = c * vkp - sine * vkq;
+                            v[k + q * n] = sine * vkp + c * vkq;
+                        }
+                    }
+                }
+            }
+
+            let mut order: Vec<usize> = (0..n).collect();
+            order.sort_by(|&i, &j| s[i + i * n].total_cmp(&s[j + j * n]));
+            for (new_col, &old_col) in order.iter().enumerate() {
+                *w.add(new_col) = s[old_col + old_col * n];
+                if vectors {
+                    for row in 0..n {
+                        *a.add(row + new_col * lda) = v[row + old_col * n];
+                    }
+                }
+            }
+        }
+    };
+}
+
+/// Applies the reflector `H = I - tau * v * v^T` from the left to the columns `cols` of a
+/// column-major matrix with `m` rows, where `v` has zeros above row `j`, a one in row `j` and
+/// the elements of column `j` below the diagonal in the rest.
+///
+/// # Safety
+///
+/// `at(i, col)` must be a valid pointer for every row `i >= j` and column `col` in `cols`, and
+/// for column `j` below the diagonal.
+unsafe fn apply_reflector<T>(at: impl Fn(usize, usize) -> *mut T, tau: T, j: usize, m: usize,
+                             cols: std::ops::Range<usize>)
+    where
+        T: Copy + std::ops::Add<Output = T> + std::ops::Sub<Output = T> + std::ops::Mul<Output = T>
+{
+    for col in cols {
+        let mut dot = *at(j, col);
+        for i in (j + 1)..m {
+            dot = dot + *at(i, j) * *at(i, col);
+        }
+        let factor = tau * dot;
+        *at(j, col) = *at(j, col) - factor;
+        for i in (j + 1)..m {
+            *at(i, col) = *at(i, col) - factor * *at(i, j);
+        }
+    }
+}
+
+reference_routines!(f32, sgemm_, sgetrf_, sgetrs_, spotrf_, sgeqrf_, sorgqr_, ssyev_);
+reference_routines!(f64, dgemm_, dgetrf_, dgetrs_, dpotrf_, dgeqrf_, dorgqr_, dsyev_);
@@ -339,6 +339,48 @@ mod prng_initializers {
         test_matrix_bernoulli_rng_creation_::<f32>();
         test_matrix_bernoulli_rng_creation_::<f64>();
     }
+
+    #[test]
+    fn test_matrix_seeded_rng_reproducibility()
+    {
+        const SEED: u64 = 2023;
+        for size in MIN_SIZE..MAX_SIZE {
+            assert_eq!(Matrix::<i32>::random_uniform_seeded(size, size, i32::MIN, i32::MAX, SEED),
+                       Matrix::<i32>::random_uniform_seeded(size, size, i32::MIN, i32::MAX, SEED));
+            assert_eq!(Matrix::<f64>::random_uniform_seeded(size, size, -1.0, 1.0, SEED),
+                       Matrix::<f64>::random_uniform_seeded(size, size, -1.0, 1.0, SEED));
+            assert_eq!(Matrix::<u8>::random_bernoulli_seeded(size, size, 0.5, SEED),
+                       Matrix::<u8>::random_bernoulli_seeded(size, size, 0.5, SEED));
+        }
+
+        // Different seeds give different matrices.
+        assert_ne!(Matrix::<f64>::random_uniform_seeded(10, 10, -1.0, 1.0, SEED),
+                   Matrix::<f64>::random_uniform_seeded(10, 10, -1.0, 1.0, SEED + 1));
+    }
+
+    #[test]
+    fn test_matrix_rng_with_shared_generator()
+    {
+        use rand::SeedableRng;
+        const SEED: u64 = 7;
+
+        // Replaying the same generator state replays the whole sequence of matrices.
+        let mut rng_1 = rand::rngs::StdRng::seed_from_u64(SEED);
+        let mut rng_2 = rand::rngs::StdRng::seed_from_u64(SEED);
+        for size in MIN_SIZE..MAX_SIZE {
+            let mat_1 = Matrix::<i64>::random_uniform_with_rng(size, size, -100, 100, &mut rng_1);
+            let mat_2 = Matrix::<i64>::random_uniform_with_rng(size, size, -100, 100, &mut rng_2);
+            assert_eq!(mat_1, mat_2);
+            let mat_1 = Matrix::<f32>::random_bernoulli_with_rng(size, size, 0.3, &mut rng_1);
+            let mat_2 = Matrix::<f32>::random_bernoulli_with_rng(size, size, 0.3, &mut rng_2);
+            assert_eq!(mat_1, mat_2);
+        }
+
+        // The seeded constructor matches a freshly seeded generator.
+        let mut rng = rand::rngs::StdRng::seed_from_u64(SEED);
+        assert_eq!(Matrix::<f64>::random_uniform_with_rng(4, 4, 0.0, 1.0, &mut rng),
+                   Matrix::<f64>::random_uniform_seeded(4, 4, 0.0, 1.0, SEED));
+    }
 }
 
 
@@ -617,5 +659,3066 @@ mod linalg_operations {
         }
     }
 
+    #[allow(non_snake_case)]
+    #[test]
+    fn test_matrix_LU_decomposition_float()
+    {
+        for size in MIN_SIZE..MAX_SIZE {
+            // Diagonally dominant, so no pivoting is needed.
+            let rng_mat_f32 = Matrix::<f32>::random_uniform(size, size, -1.0, 1.0) + Matrix::identity(size).scale(size as f32);
+            let (l_f32, u_f32) = rng_mat_f32.LU_decompose();
+            assert!((l_f32 * u_f32).approx_eq(&rng_mat_f32, 1e-4, 1e-5));
+
+            let rng_mat_f64 = Matrix::<f64>::random_uniform(size, size, -1.0, 1.0) + Matrix::identity(size).scale(size as f64);
+            let (l_f64, u_f64) = rng_mat_f64.LU_decompose();
+            assert!((l_f64 * u_f64).approx_eq(&rng_mat_f64, 1e-10, 1e-12));
+        }
+    }
+
+
+}
+
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+/////////////////////// Testing statistics methods for types: i32, u32, f64 ////////////////////////
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+
+#[cfg(test)]
+mod statistics {
+    use MyMatrixLib::Matrix;
+    const MAX_SIZE: usize = 30;
+    const MIN_SIZE: usize = 1;
+    const TOLERANCE: f64 = 1e-9;
+
+    #[test]
+    fn test_matrix_whole_reductions()
+    {
+        let mat = Matrix::<i32>::from_vec(2, 3, vec![3, -1, 4, 1, -5, 9]);
+        assert_eq!(mat.sum(), 11);
+        assert_eq!(mat.product(), 540);
+        assert_eq!(mat.max(), 9);
+        assert_eq!(mat.min(), -5);
+        assert_eq!(mat.arg_max(), (1, 2));
+        assert_eq!(mat.arg_min(), (1, 1));
+
+        let mat = Matrix::<u32>::from_vec(2, 2, vec![7, 7, 2, 7]);
+        assert_eq!(mat.max(), 7);
+        assert_eq!(mat.arg_max(), (0, 0)); // First occurrence wins.
+        assert_eq!(mat.arg_min(), (1, 0));
+
+        let mat = Matrix::<f64>::from_vec(2, 3, vec![-4.0, -2.0, -3.0, -8.0, -1.5, -6.0]);
+        assert_eq!(mat.max(), -1.5);
+        assert_eq!(mat.arg_max(), (1, 1));
+    }
+
+    #[test]
+    fn test_matrix_axis_reductions()
+    {
+        let mat = Matrix::<i32>::from_vec(2, 3, vec![3, -1, 4, 1, -5, 9]);
+        assert_eq!(mat.sum_axis(0), Matrix::from_vec(1, 3, vec![4, -6, 13]));
+        assert_eq!(mat.sum_axis(1), Matrix::from_vec(2, 1, vec![6, 5]));
+        assert_eq!(mat.product_axis(0), Matrix::from_vec(1, 3, vec![3, 5, 36]));
+        assert_eq!(mat.product_axis(1), Matrix::from_vec(2, 1, vec![-12, -45]));
+        assert_eq!(mat.max_axis(0), Matrix::from_vec(1, 3, vec![3, -1, 9]));
+        assert_eq!(mat.max_axis(1), Matrix::from_vec(2, 1, vec![4, 9]));
+        assert_eq!(mat.min_axis(0), Matrix::from_vec(1, 3, vec![1, -5, 4]));
+        assert_eq!(mat.min_axis(1), Matrix::from_vec(2, 1, vec![-1, -5]));
+        assert_eq!(mat.arg_max_axis(0), Matrix::from_vec(1, 3, vec![0, 0, 1]));
+        assert_eq!(mat.arg_max_axis(1), Matrix::from_vec(2, 1, vec![2, 2]));
+        assert_eq!(mat.arg_min_axis(0), Matrix::from_vec(1, 3, vec![1, 1, 0]));
+        assert_eq!(mat.arg_min_axis(1), Matrix::from_vec(2, 1, vec![1, 1]));
+    }
+
+    #[test]
+    fn test_matrix_axis_reductions_match_whole()
+    {
+        for rows in MIN_SIZE..MAX_SIZE {
+            let cols = MAX_SIZE - rows;
+            let mat = Matrix::<i64>::random_uniform(rows, cols, -1000, 1000);
+            assert_eq!(mat.sum_axis(0).sum(), mat.sum());
+            assert_eq!(mat.sum_axis(1).sum(), mat.sum());
+            assert_eq!(mat.max_axis(0).max(), mat.max());
+            assert_eq!(mat.max_axis(1).max(), mat.max());
+            assert_eq!(mat.min_axis(0).min(), mat.min());
+            assert_eq!(mat.min_axis(1).min(), mat.min());
+
+            let (row_idx, col_idx) = mat.arg_max();
+            assert_eq!(mat[(row_idx, col_idx)], mat.max());
+            let (row_idx, col_idx) = mat.arg_min();
+            assert_eq!(mat[(row_idx, col_idx)], mat.min());
+
+            let arg_max_rows = mat.arg_max_axis(1);
+            for row_idx in 0..rows {
+                assert_eq!(mat[(row_idx, arg_max_rows[(row_idx, 0)])], mat.get_row(row_idx).max());
+            }
+            let arg_max_cols = mat.arg_max_axis(0);
+            for col_idx in 0..cols {
+                assert_eq!(mat[(arg_max_cols[(0, col_idx)], col_idx)], mat.get_col(col_idx).max());
+            }
+        }
+    }
+
+    #[test]
+    fn test_matrix_abs_max_reductions()
+    {
+        let mat = Matrix::<f64>::from_vec(2, 3, vec![3.0, -7.0, 4.0, 1.0, -5.0, -9.0]);
+        assert_eq!(mat.abs_max(), 9.0);
+        assert_eq!(mat.abs_max_axis(0), Matrix::from_vec(1, 3, vec![3.0, 7.0, 9.0]));
+        assert_eq!(mat.abs_max_axis(1), Matrix::from_vec(2, 1, vec![7.0, 9.0]));
+        assert_eq!(mat.arg_abs_max(), (1, 2));
+        assert_eq!(mat.arg_abs_max_axis(0), Matrix::from_vec(1, 3, vec![0, 0, 1]));
+        assert_eq!(mat.arg_abs_max_axis(1), Matrix::from_vec(2, 1, vec![1, 2]));
+
+        let mat = Matrix::<i32>::from_vec(2, 2, vec![-4, 4, 2, -3]);
+        assert_eq!(mat.arg_abs_max(), (0, 0)); // First occurrence wins.
+        assert_eq!(mat.arg_abs_max_axis(1), Matrix::from_vec(2, 1, vec![0, 1]));
+
+        for rows in MIN_SIZE..MAX_SIZE {
+            let cols = MAX_SIZE - rows;
+            let mat = Matrix::<f64>::random_uniform(rows, cols, -10.0, 10.0);
+            let (row_idx, col_idx) = mat.arg_abs_max();
+            assert_eq!(mat[(row_idx, col_idx)].abs(), mat.abs_max());
+            assert_eq!(mat.abs_max_axis(0).abs_max(), mat.abs_max());
+            assert_eq!(mat.abs_max_axis(1).abs_max(), mat.abs_max());
+            let column_major = mat.to_layout(MyMatrixLib::Layout::ColumnMajor);
+            assert_eq!(column_major.arg_abs_max(), (row_idx, col_idx));
+            assert_eq!(column_major.arg_abs_max_axis(1), mat.arg_abs_max_axis(1));
+        }
+    }
+
+    #[test]
+    fn test_matrix_moments()
+    {
+        let mat = Matrix::<f64>::from_vec(2, 3, vec![1.0, 2.0, 3.0, 5.0, 8.0, 11.0]);
+        assert!((mat.mean() - 5.0).abs() < TOLERANCE);
+        assert!((mat.variance() - 74.0 / 6.0).abs() < TOLERANCE);
+        assert!((mat.std() - (74.0_f64 / 6.0).sqrt()).abs() < TOLERANCE);
+
+        let col_means = mat.mean_axis(0);
+        let row_means = mat.mean_axis(1);
+        let col_variances = mat.variance_axis(0);
+        let row_stds = mat.std_axis(1);
+        let expected_col_means = [3.0, 5.0, 7.0];
+        let expected_col_variances = [4.0, 9.0, 16.0];
+        for col_idx in 0..3 {
+            assert!((col_means[(0, col_idx)] - expected_col_means[col_idx]).abs() < TOLERANCE);
+            assert!((col_variances[(0, col_idx)] - expected_col_variances[col_idx]).abs() < TOLERANCE);
+        }
+        assert!((row_means[(0, 0)] - 2.0).abs() < TOLERANCE);
+        assert!((row_means[(1, 0)] - 8.0).abs() < TOLERANCE);
+        assert!((row_stds[(0, 0)] - (2.0_f64 / 3.0).sqrt()).abs() < TOLERANCE);
+        assert!((row_stds[(1, 0)] - 6.0_f64.sqrt()).abs() < TOLERANCE);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_matrix_invalid_axis()
+    {
+        let mat = Matrix::<f64>::ones(2, 2);
+        mat.sum_axis(2);
+    }
+}
+
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+////////////////// Testing covariance, correlation and PCA methods for types: f64 //////////////////
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+
+#[cfg(test)]
+mod multivariate_statistics {
+    use MyMatrixLib::Matrix;
+    const MAX_SIZE: usize = 12;
+    const MIN_SIZE: usize = 1;
+    const TOLERANCE: f64 = 1e-9;
+
+    fn assert_matrix_close(a: &Matrix<f64>, b: &Matrix<f64>, rows: usize, cols: usize) {
+        for row_idx in 0..rows {
+            for col_idx in 0..cols {
+                assert!((a[(row_idx, col_idx)] - b[(row_idx, col_idx)]).abs() < TOLERANCE,
+                        "Mismatch at ({}, {}): {} vs {}", row_idx, col_idx, a[(row_idx, col_idx)], b[(row_idx, col_idx)]);
+            }
+        }
+    }
+
+    #[test]
+    fn test_matrix_symmetric_eigen()
+    {
+        for size in MIN_SIZE..MAX_SIZE {
+            let rng_mat = Matrix::<f64>::random_uniform(size, size, -10.0, 10.0);
+            let symmetric = rng_mat.clone() + rng_mat.transpose();
+            let (values, vectors) = symmetric.symmetric_eigen();
+
+            for idx in 1..size {
+                assert!(values[idx - 1] >= values[idx]);
+            }
+
+            // V^T V = I
+            let gram = vectors.transpose() * vectors.clone();
+            assert_matrix_close(&gram, &Matrix::identity(size), size, size);
+
+            // A V = V diag(values)
+            let lhs = symmetric.clone() * vectors.clone();
+            let mut rhs = vectors.clone();
+            for row_idx in 0..size {
+                for col_idx in 0..size {
+                    rhs[(row_idx, col_idx)] = vectors[(row_idx, col_idx)] * values[col_idx];
+                }
+            }
+            assert_matrix_close(&lhs, &rhs, size, size);
+        }
+    }
+
+    #[test]
+    fn test_matrix_covariance_and_correlation()
+    {
+        let data = Matrix::<f64>::from_vec(4, 3, vec![
+            1.0, 2.0, 4.0,
+            2.0, 4.0, 3.0,
+            3.0, 6.0, 2.0,
+            4.0, 8.0, 1.0,
+        ]);
+        let expected_covariance = Matrix::<f64>::from_vec(3, 3, vec![
+            5.0 / 3.0, 10.0 / 3.0, -5.0 / 3.0,
+            10.0 / 3.0, 20.0 / 3.0, -10.0 / 3.0,
+            -5.0 / 3.0, -10.0 / 3.0, 5.0 / 3.0,
+        ]);
+        let expected_correlation = Matrix::<f64>::from_vec(3, 3, vec![
+            1.0, 1.0, -1.0,
+            1.0, 1.0, -1.0,
+            -1.0, -1.0, 1.0,
+        ]);
+        assert_matrix_close(&data.covariance(), &expected_covariance, 3, 3);
+        assert_matrix_close(&data.correlation(), &expected_correlation, 3, 3);
+
+        let rng_data = Matrix::<f64>::random_uniform(50, 5, -1.0, 1.0);
+        let correlation = rng_data.correlation();
+        for row_idx in 0..5 {
+            assert!((correlation[(row_idx, row_idx)] - 1.0).abs() < TOLERANCE);
+            for col_idx in 0..5 {
+                assert!(correlation[(row_idx, col_idx)].abs() <= 1.0 + TOLERANCE);
+                assert!((correlation[(row_idx, col_idx)] - correlation[(col_idx, row_idx)]).abs() < TOLERANCE);
+            }
+        }
+    }
+
+    #[test]
+    fn test_matrix_pca()
+    {
+        // Points on the line y = 2x + 1: all variance lies along the direction (1, 2) / sqrt(5).
+        let data = Matrix::<f64>::from_vec(5, 2, vec![0.0, 1.0, 1.0, 3.0, 2.0, 5.0, 3.0, 7.0, 4.0, 9.0]);
+        let pca = data.pca(2);
+
+        assert!((pca.explained_variance_ratio[0] - 1.0).abs() < TOLERANCE);
+        assert!(pca.explained_variance_ratio[1].abs() < TOLERANCE);
+        assert!((pca.explained_variance[0] - 12.5).abs() < TOLERANCE);
+        let direction = (pca.components[(0, 0)], pca.components[(1, 0)]);
+        assert!((direction.0.abs() - 1.0 / 5.0_f64.sqrt()).abs() < TOLERANCE);
+        assert!((direction.1.abs() - 2.0 / 5.0_f64.sqrt()).abs() < TOLERANCE);
+        assert_matrix_close(&pca.mean, &Matrix::from_vec(1, 2, vec![2.0, 5.0]), 1, 2);
+
+        // Scores are centred and their variance along the first axis matches the eigenvalue.
+        let scores = pca.project(&data);
+        assert!(scores.mean_axis(0)[(0, 0)].abs() < TOLERANCE);
+        assert!((scores.get_col(0).variance() * 5.0 / 4.0 - pca.explained_variance[0]).abs() < TOLERANCE);
+        assert!(scores.get_col(1).max().abs() < TOLERANCE);
+
+        let truncated = Matrix::<f64>::random_uniform(20, 4, -1.0, 1.0).pca(2);
+        assert_eq!(truncated.explained_variance.len(), 2);
+        assert!(MyMathLib::sum(&truncated.explained_variance_ratio) <= 1.0 + TOLERANCE);
+    }
+}
+
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+////////////////////// Testing pivoted LU, solve and inverse for types: f32, f64 ///////////////////
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+
+#[cfg(test)]
+mod linear_systems {
+    use MyMatrixLib::Matrix;
+    const MAX_SIZE: usize = 30;
+    const MIN_SIZE: usize = 1;
+
+    #[allow(non_snake_case)]
+    #[test]
+    fn test_matrix_LUP_decomposition()
+    {
+        for size in MIN_SIZE..MAX_SIZE {
+            let rng_mat = Matrix::<f64>::random_uniform(size, size, -100.0, 100.0);
+            let (L, U, P) = rng_mat.LUP_decompose();
+            let lhs = P * rng_mat.clone();
+            let rhs = L.clone() * U.clone();
+            for row_idx in 0..size {
+                for col_idx in 0..size {
+                    assert!((lhs[(row_idx, col_idx)] - rhs[(row_idx, col_idx)]).abs() < 1e-9);
+                    if col_idx > row_idx { assert_eq!(L[(row_idx, col_idx)], 0.0); }
+                    if col_idx < row_idx { assert_eq!(U[(row_idx, col_idx)], 0.0); }
+                    assert!(L[(row_idx, col_idx)].abs() <= 1.0);
+                }
+            }
+        }
+
+        // Zero on the diagonal is handled by pivoting.
+        let mat = Matrix::<f32>::from_vec(2, 2, vec![0.0, 1.0, 1.0, 0.0]);
+        let (L, U, P) = mat.LUP_decompose();
+        assert_eq!(L, Matrix::identity(2));
+        assert_eq!(U, Matrix::identity(2));
+        assert_eq!(P, mat);
+    }
+
+    #[test]
+    fn test_matrix_solve_and_inverse()
+    {
+        for size in MIN_SIZE..MAX_SIZE {
+            let a = Matrix::<f64>::random_uniform(size, size, -1.0, 1.0) + Matrix::identity(size).scale(size as f64);
+            let x = Matrix::<f64>::random_uniform(size, 3, -10.0, 10.0);
+            let b = a.clone() * x.clone();
+            let solved = a.solve(&b);
+            let product = a.clone() * a.inverse();
+            for row_idx in 0..size {
+                for col_idx in 0..3 {
+                    assert!((solved[(row_idx, col_idx)] - x[(row_idx, col_idx)]).abs() < 1e-9);
+                }
+                for col_idx in 0..size {
+                    let expected = if row_idx == col_idx { 1.0 } else { 0.0 };
+                    assert!((product[(row_idx, col_idx)] - expected).abs() < 1e-9);
+                }
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_matrix_solve_singular()
+    {
+        let a = Matrix::<f64>::from_vec(2, 2, vec![1.0, 2.0, 2.0, 4.0]);
+        a.solve(&Matrix::ones(2, 1));
+    }
+
+    #[allow(non_snake_case)]
+    #[test]
+    fn test_matrix_QR_decomposition()
+    {
+        for rows in MIN_SIZE..12 {
+            for cols in MIN_SIZE..12 {
+                let rng_mat = Matrix::<f64>::random_uniform(rows, cols, -10.0, 10.0);
+                let (Q, R) = rng_mat.QR_decompose();
+                let product = Q.clone() * R.clone();
+                let gram = Q.transpose() * Q.clone();
+                for row_idx in 0..rows {
+                    for col_idx in 0..cols {
+                        assert!((product[(row_idx, col_idx)] - rng_mat[(row_idx, col_idx)]).abs() < 1e-9);
+                        if row_idx > col_idx { assert_eq!(R[(row_idx, col_idx)], 0.0); }
+                    }
+                    for col_idx in 0..rows {
+                        let expected = if row_idx == col_idx { 1.0 } else { 0.0 };
+                        assert!((gram[(row_idx, col_idx)] - expected).abs() < 1e-12);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_matrix_cholesky()
+    {
+        for size in MIN_SIZE..MAX_SIZE {
+            let b = Matrix::<f64>::random_uniform(size, size, -1.0, 1.0);
+            let spd = b.clone() * b.transpose() + Matrix::identity(size);
+            let l = spd.cholesky();
+            let product = l.clone() * l.transpose();
+            for row_idx in 0..size {
+                assert!(l[(row_idx, row_idx)] > 0.0);
+                for col_idx in 0..size {
+                    if col_idx > row_idx { assert_eq!(l[(row_idx, col_idx)], 0.0); }
+                    assert!((product[(row_idx, col_idx)] - spd[(row_idx, col_idx)]).abs() < 1e-9);
+                }
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_matrix_cholesky_not_positive_definite()
+    {
+        Matrix::<f64>::from_vec(2, 2, vec![1.0, 2.0, 2.0, 1.0]).cholesky();
+    }
+}
+
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+///////////////////// Testing matrix functions (pow, expm, sqrtm, logm) for: i64, f64 //////////////
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+
+#[cfg(test)]
+mod matrix_functions {
+    use MyMatrixLib::Matrix;
+    const MAX_SIZE: usize = 8;
+    const MIN_SIZE: usize = 1;
+    const TOLERANCE: f64 = 1e-8;
+
+    fn assert_matrix_close(a: &Matrix<f64>, b: &Matrix<f64>, size: usize) {
+        for row_idx in 0..size {
+            for col_idx in 0..size {
+                let scale = 1.0_f64.max(b[(row_idx, col_idx)].abs());
+                assert!((a[(row_idx, col_idx)] - b[(row_idx, col_idx)]).abs() < TOLERANCE * scale,
+                        "Mismatch at ({}, {}): {} vs {}", row_idx, col_idx, a[(row_idx, col_idx)], b[(row_idx, col_idx)]);
+            }
+        }
+    }
+
+    #[test]
+    fn test_matrix_pow()
+    {
+        let fibonacci = Matrix::<i64>::from_vec(2, 2, vec![1, 1, 1, 0]);
+        assert_eq!(fibonacci.pow(0), Matrix::identity(2));
+        assert_eq!(fibonacci.pow(1), fibonacci);
+        assert_eq!(fibonacci.pow(10), Matrix::from_vec(2, 2, vec![89, 55, 55, 34]));
+        assert_eq!(fibonacci.pow(90)[(0, 1)], 2880067194370816120);
+
+        for size in MIN_SIZE..MAX_SIZE {
+            let rng_mat = Matrix::<i64>::random_uniform(size, size, -5, 5);
+            let mut expected = Matrix::identity(size);
+            for exponent in 0..7 {
+                assert_eq!(rng_mat.pow(exponent), expected);
+                expected = expected * rng_mat.clone();
+            }
+        }
+    }
+
+    #[test]
+    fn test_matrix_expm()
+    {
+        assert_matrix_close(&Matrix::<f64>::zeros(3, 3).expm(), &Matrix::identity(3), 3);
+
+        let nilpotent = Matrix::<f64>::from_vec(2, 2, vec![0.0, 1.0, 0.0, 0.0]);
+        assert_matrix_close(&nilpotent.expm(), &Matrix::from_vec(2, 2, vec![1.0, 1.0, 0.0, 1.0]), 2);
+
+        // Rotation generator, large enough to trigger scaling and squaring.
+        let angle = 20.0_f64;
+        let generator = Matrix::<f64>::from_vec(2, 2, vec![0.0, -angle, angle, 0.0]);
+        let rotation = Matrix::from_vec(2, 2, vec![angle.cos(), -angle.sin(), angle.sin(), angle.cos()]);
+        assert_matrix_close(&generator.expm(), &rotation, 2);
+
+        let diagonal = Matrix::<f64>::from_vec(2, 2, vec![-3.0, 0.0, 0.0, 7.5]);
+        assert_matrix_close(&diagonal.expm(), &Matrix::from_vec(2, 2, vec![(-3.0_f64).exp(), 0.0, 0.0, 7.5_f64.exp()]), 2);
+
+        for size in MIN_SIZE..MAX_SIZE {
+            let rng_mat = Matrix::<f64>::random_uniform(size, size, -2.0, 2.0);
+            let product = rng_mat.expm() * rng_mat.scale(-1.0).expm();
+            assert_matrix_close(&product, &Matrix::identity(size), size);
+        }
+    }
+
+    #[test]
+    fn test_matrix_sqrtm_and_logm()
+    {
+        let diagonal = Matrix::<f64>::from_vec(2, 2, vec![4.0, 0.0, 0.0, 9.0]);
+        assert_matrix_close(&diagonal.sqrtm(), &Matrix::from_vec(2, 2, vec![2.0, 0.0, 0.0, 3.0]), 2);
+        assert_matrix_close(&diagonal.logm(), &Matrix::from_vec(2, 2, vec![4.0_f64.ln(), 0.0, 0.0, 9.0_f64.ln()]), 2);
+
+        for size in MIN_SIZE..MAX_SIZE {
+            // Symmetric positive definite: A = B^T B + n I
+            let b = Matrix::<f64>::random_uniform(size, size, -1.0, 1.0);
+            let spd = b.transpose() * b + Matrix::identity(size).scale(size as f64);
+            let root = spd.sqrtm();
+            assert_matrix_close(&(root.clone() * root), &spd, size);
+
+            // logm inverts expm for matrices with small norm.
+            let small = Matrix::<f64>::random_uniform(size, size, -0.5, 0.5);
+            assert_matrix_close(&small.expm().logm(), &small, size);
+            assert_matrix_close(&spd.logm().expm(), &spd, size);
+        }
+    }
+}
+
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+/////////////////// Testing vector and tensor products for types: i32, i64, f64 ////////////////////
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+
+#[cfg(test)]
+mod products {
+    use MyMatrixLib::Matrix;
+    const MAX_SIZE: usize = 10;
+    const MIN_SIZE: usize = 1;
+
+    #[test]
+    fn test_matrix_dot_and_outer()
+    {
+        let row = Matrix::<i32>::from_vec(1, 3, vec![1, 2, 3]);
+        let col = Matrix::<i32>::from_vec(3, 1, vec![4, -5, 6]);
+        assert_eq!(row.dot(&col), 12);
+        assert_eq!(col.dot(&row), 12);
+        assert_eq!(row.dot(&row), 14);
+        assert_eq!(row.outer(&col), Matrix::from_vec(3, 3, vec![4, -5, 6, 8, -10, 12, 12, -15, 18]));
+
+        for size in MIN_SIZE..MAX_SIZE {
+            let mat = Matrix::<i64>::random_uniform(size, size, -100, 100);
+            let product = mat.clone() * mat.clone();
+            for row_idx in 0..size {
+                for col_idx in 0..size {
+                    assert_eq!(mat.get_row(row_idx).dot(&mat.get_col(col_idx)), product[(row_idx, col_idx)]);
+                }
+            }
+            // The outer product equals the matrix product of a column and a row vector.
+            let (u, v) = (mat.get_col(0), mat.get_row(0));
+            assert_eq!(u.outer(&v), u.clone() * v.clone());
+        }
+    }
+
+    #[test]
+    fn test_matrix_cross()
+    {
+        let x = Matrix::<i32>::from_vec(1, 3, vec![1, 0, 0]);
+        let y = Matrix::<i32>::from_vec(1, 3, vec![0, 1, 0]);
+        let z = Matrix::<i32>::from_vec(1, 3, vec![0, 0, 1]);
+        assert_eq!(x.cross(&y), z);
+        assert_eq!(y.cross(&z), x);
+        assert_eq!(z.cross(&x), y);
+        assert_eq!(x.transpose().cross(&y), z.transpose());
+
+        for _ in 0..100 {
+            let a = Matrix::<i64>::random_uniform(3, 1, -1000, 1000);
+            let b = Matrix::<i64>::random_uniform(1, 3, -1000, 1000);
+            let c = a.cross(&b);
+            assert_eq!(c.dot(&a), 0);
+            assert_eq!(c.dot(&b), 0);
+            assert_eq!(b.cross(&a), c.scale(-1).transpose());
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_matrix_dot_requires_vectors()
+    {
+        Matrix::<f64>::ones(2, 2).dot(&Matrix::ones(2, 2));
+    }
+
+    #[test]
+    fn test_matrix_kron()
+    {
+        let pauli_x = Matrix::<i32>::from_vec(2, 2, vec![0, 1, 1, 0]);
+        let identity = Matrix::<i32>::identity(2);
+        assert_eq!(pauli_x.kron(&identity), Matrix::from_vec(4, 4, vec![
+            0, 0, 1, 0,
+            0, 0, 0, 1,
+            1, 0, 0, 0,
+            0, 1, 0, 0,
+        ]));
+        assert_eq!(identity.kron(&identity), Matrix::identity(4));
+
+        // Mixed product property: (A ⊗ B)(C ⊗ D) = (AC) ⊗ (BD)
+        for size in MIN_SIZE..5 {
+            let a = Matrix::<i64>::random_uniform(size, size + 1, -10, 10);
+            let b = Matrix::<i64>::random_uniform(2, size, -10, 10);
+            let c = Matrix::<i64>::random_uniform(size + 1, 2, -10, 10);
+            let d = Matrix::<i64>::random_uniform(size, 3, -10, 10);
+            let lhs = a.kron(&b) * c.kron(&d);
+            let rhs = (a * c).kron(&(b * d));
+            assert_eq!(lhs, rhs);
+        }
+    }
+
+    #[test]
+    fn test_matrix_trace_and_diag()
+    {
+        let mat = Matrix::<f64>::from_vec(2, 3, vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+        assert_eq!(mat.diag(), Matrix::from_vec(2, 1, vec![1.0, 5.0]));
+        assert_eq!(Matrix::from_diag(&mat.diag()), Matrix::from_vec(2, 2, vec![1.0, 0.0, 0.0, 5.0]));
+        assert_eq!(Matrix::from_diag(&mat.get_row(1)), Matrix::from_vec(3, 3, vec![4.0, 0.0, 0.0, 0.0, 5.0, 0.0, 0.0, 0.0, 6.0]));
+
+        for size in MIN_SIZE..MAX_SIZE {
+            let a = Matrix::<i64>::random_uniform(size, size, -100, 100);
+            let b = Matrix::<i64>::random_uniform(size, size, -100, 100);
+            assert_eq!(a.trace(), a.diag().sum());
+            assert_eq!((a.clone() * b.clone()).trace(), (b * a).trace());
+            assert_eq!(Matrix::<i64>::identity(size).trace(), size as i64);
+        }
+    }
+}
+
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+///////////////////// Testing Vector type and matrix-vector products for: i64, f64 /////////////////
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+
+#[cfg(test)]
+mod vectors {
+    use MyMatrixLib::{Matrix, Vector};
+    const MAX_SIZE: usize = 20;
+    const MIN_SIZE: usize = 1;
+    const TOLERANCE: f64 = 1e-12;
+
+    #[test]
+    fn test_vector_conversions()
+    {
+        let mat = Matrix::<i64>::from_vec(2, 3, vec![1, 2, 3, 4, 5, 6]);
+        assert_eq!(mat.row_vector(1), Vector::from_vec(vec![4, 5, 6]));
+        assert_eq!(mat.col_vector(2), Vector::from_vec(vec![3, 6]));
+        assert_eq!(Vector::from_matrix(&mat.get_row(0)), mat.row_vector(0));
+        assert_eq!(Vector::from_matrix(&mat.get_col(1)), mat.col_vector(1));
+        assert_eq!(mat.row_vector(0).to_row_matrix(), mat.get_row(0));
+        assert_eq!(mat.col_vector(0).to_col_matrix(), mat.get_col(0));
+        assert_eq!(mat.row_vector(1).as_slice(), mat.view_row(1));
+
+        let mut v = Vector::<i64>::zeros(3);
+        v[1] = 7;
+        assert_eq!(v, Vector::from_vec(vec![0, 7, 0]));
+        assert_eq!(v.len(), 3);
+        assert!(Vector::<i64>::new(0).is_empty());
+        assert_eq!(Vector::<i64>::ones(2) + Vector::from_vec(vec![1, 2]), Vector::from_vec(vec![2, 3]));
+        assert_eq!(Vector::<i64>::ones(2) - Vector::from_vec(vec![1, 2]), Vector::from_vec(vec![0, -1]));
+    }
 
+    #[test]
+    #[should_panic]
+    fn test_vector_from_non_vector_matrix()
+    {
+        Vector::from_matrix(&Matrix::<f64>::ones(2, 2));
+    }
+
+    #[test]
+    fn test_matrix_vector_product()
+    {
+        for rows in MIN_SIZE..MAX_SIZE {
+            let cols = MAX_SIZE - rows;
+            let mat = Matrix::<i64>::random_uniform(rows, cols, -100, 100);
+            let x = Vector::from_matrix(&Matrix::<i64>::random_uniform(cols, 1, -100, 100));
+            let expected = Vector::from_matrix(&(mat.clone() * x.to_col_matrix()));
+            let product = mat.clone() * x.clone();
+            assert_eq!(product, expected);
+            for row_idx in 0..rows {
+                assert_eq!(product[row_idx], mat.row_vector(row_idx).dot(&x));
+            }
+        }
+    }
+
+    #[test]
+    fn test_vector_norms()
+    {
+        let v = Vector::<f64>::from_vec(vec![3.0, -4.0, 0.0]);
+        assert!((v.norm() - 5.0).abs() < TOLERANCE);
+        assert!((v.norm_l1() - 7.0).abs() < TOLERANCE);
+        assert!((v.norm_inf() - 4.0).abs() < TOLERANCE);
+        assert!((v.norm_p(2.0) - 5.0).abs() < TOLERANCE);
+        assert!((v.norm_p(3.0) - 91.0_f64.powf(1.0 / 3.0)).abs() < TOLERANCE);
+        assert_eq!(v.scale(2.0), Vector::from_vec(vec![6.0, -8.0, 0.0]));
+
+        for size in MIN_SIZE..MAX_SIZE {
+            let v = Vector::from_matrix(&Matrix::<f64>::random_uniform(size, 1, -10.0, 10.0));
+            let unit = v.normalize();
+            assert!((unit.norm() - 1.0).abs() < TOLERANCE);
+            assert!((unit.dot(&v) - v.norm()).abs() < 1e-9);
+            assert!(v.norm_inf() <= v.norm() + TOLERANCE);
+            assert!(v.norm() <= v.norm_l1() + TOLERANCE);
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_vector_normalize_zero()
+    {
+        Vector::<f64>::zeros(3).normalize();
+    }
+}
+
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+//////////////// Testing random distributions and ensembles for types: i64, u32, f64 ///////////////
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+
+#[cfg(test)]
+mod random_distributions {
+    use MyMatrixLib::Matrix;
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+    const SEED: u64 = 12345;
+    const SAMPLES: usize = 200;
+
+    #[test]
+    fn test_matrix_continuous_distributions()
+    {
+        let mut rng = StdRng::seed_from_u64(SEED);
+
+        let normal = Matrix::<f64>::random_normal_with_rng(SAMPLES, SAMPLES, 3.0, 2.0, &mut rng);
+        assert!((normal.mean() - 3.0).abs() < 0.05);
+        assert!((normal.std() - 2.0).abs() < 0.05);
+
+        let exponential = Matrix::<f64>::random_exponential_with_rng(SAMPLES, SAMPLES, 4.0, &mut rng);
+        assert!(exponential.min() >= 0.0);
+        assert!((exponential.mean() - 0.25).abs() < 0.01);
+        assert!((exponential.std() - 0.25).abs() < 0.01);
+
+        let gamma = rand_distr::Gamma::new(2.0, 1.5).unwrap();
+        let gamma_mat = Matrix::<f64>::random_from_distribution_with_rng(SAMPLES, SAMPLES, &gamma, &mut rng);
+        assert!((gamma_mat.mean() - 3.0).abs() < 0.05);
+
+        let mat = Matrix::<f32>::random_normal(4, 5, 0.0, 1.0);
+        assert_eq!(mat.get_row(0).sum_axis(1), mat.sum_axis(1).get_row(0));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_matrix_normal_negative_std()
+    {
+        Matrix::<f64>::random_normal(2, 2, 0.0, -1.0);
+    }
+
+    #[test]
+    fn test_matrix_discrete_distributions()
+    {
+        let mut rng = StdRng::seed_from_u64(SEED);
+
+        let poisson = Matrix::<u32>::random_poisson_with_rng(SAMPLES, SAMPLES, 4.5, &mut rng);
+        let poisson_f64 = Matrix::<f64>::from_vec(SAMPLES, SAMPLES, (0..SAMPLES * SAMPLES).map(|i| poisson[(i / SAMPLES, i % SAMPLES)] as f64).collect());
+        assert!((poisson_f64.mean() - 4.5).abs() < 0.05);
+        assert!((poisson_f64.variance() - 4.5).abs() < 0.15);
+
+        let binomial = Matrix::<i64>::random_binomial_with_rng(SAMPLES, SAMPLES, 10, 0.3, &mut rng);
+        assert!(binomial.min() >= 0);
+        assert!(binomial.max() <= 10);
+        assert!((binomial.sum() as f64 / (SAMPLES * SAMPLES) as f64 - 3.0).abs() < 0.05);
+    }
+
+    #[test]
+    fn test_matrix_random_sparse()
+    {
+        let mut rng = StdRng::seed_from_u64(SEED);
+        let values = rand::distributions::Uniform::new(1.0, 2.0);
+        for density in [0.0, 0.01, 0.25, 0.5, 1.0] {
+            let sparse = Matrix::<f64>::random_sparse_with_rng(40, 25, density, &values, &mut rng);
+            let mut non_zeros = 0;
+            for row_idx in 0..40 {
+                for col_idx in 0..25 {
+                    let val = sparse[(row_idx, col_idx)];
+                    if val != 0.0 {
+                        assert!((1.0..2.0).contains(&val));
+                        non_zeros += 1;
+                    }
+                }
+            }
+            assert_eq!(non_zeros, (density * 1000.0_f64).round() as usize);
+        }
+    }
+
+    #[test]
+    fn test_matrix_random_ensembles()
+    {
+        let mut rng = StdRng::seed_from_u64(SEED);
+        for size in 1..10 {
+            let q = Matrix::<f64>::random_orthogonal_with_rng(size, &mut rng);
+            let gram = q.transpose() * q.clone();
+            let spd = Matrix::<f64>::random_spd_with_rng(size, &mut rng);
+            let goe = Matrix::<f64>::random_goe_with_rng(size, &mut rng);
+            let wishart = Matrix::<f64>::random_wishart_with_rng(&spd, size + 2, &mut rng);
+            for row_idx in 0..size {
+                for col_idx in 0..size {
+                    let expected = if row_idx == col_idx { 1.0 } else { 0.0 };
+                    assert!((gram[(row_idx, col_idx)] - expected).abs() < 1e-12);
+                    assert_eq!(spd[(row_idx, col_idx)], spd[(col_idx, row_idx)]);
+                    assert_eq!(goe[(row_idx, col_idx)], goe[(col_idx, row_idx)]);
+                    assert!((wishart[(row_idx, col_idx)] - wishart[(col_idx, row_idx)]).abs() < 1e-9);
+                }
+            }
+            let (spd_eigenvalues, _) = spd.symmetric_eigen();
+            assert!(spd_eigenvalues[size - 1] >= 1.0 - 1e-9);
+            // Wishart with dof >= p is positive definite almost surely.
+            wishart.cholesky();
+        }
+
+        // The Wishart mean is dof * scale.
+        let scale = Matrix::<f64>::from_vec(2, 2, vec![2.0, 0.5, 0.5, 1.0]);
+        let mut mean = Matrix::<f64>::zeros(2, 2);
+        for _ in 0..2000 {
+            mean = mean + Matrix::random_wishart_with_rng(&scale, 5, &mut rng).scale(1.0 / 2000.0);
+        }
+        for row_idx in 0..2 {
+            for col_idx in 0..2 {
+                assert!((mean[(row_idx, col_idx)] - 5.0 * scale[(row_idx, col_idx)]).abs() < 0.3);
+            }
+        }
+    }
+
+    #[test]
+    fn test_matrix_seeded_distributions()
+    {
+        // Each _seeded variant draws the same matrix as its _with_rng variant with a seeded StdRng
+        let rng = || StdRng::seed_from_u64(SEED);
+        let values = rand::distributions::Uniform::new(1.0, 2.0);
+        assert_eq!(Matrix::<f64>::random_from_distribution_seeded(3, 4, &values, SEED),
+                   Matrix::random_from_distribution_with_rng(3, 4, &values, &mut rng()));
+        assert_eq!(Matrix::<f64>::random_sparse_seeded(5, 5, 0.3, &values, SEED),
+                   Matrix::random_sparse_with_rng(5, 5, 0.3, &values, &mut rng()));
+        assert_eq!(Matrix::<f64>::random_normal_seeded(3, 4, 1.0, 2.0, SEED),
+                   Matrix::random_normal_with_rng(3, 4, 1.0, 2.0, &mut rng()));
+        assert_eq!(Matrix::<f32>::random_exponential_seeded(3, 4, 2.0, SEED),
+                   Matrix::random_exponential_with_rng(3, 4, 2.0, &mut rng()));
+        assert_eq!(Matrix::<u32>::random_poisson_seeded(3, 4, 4.5, SEED),
+                   Matrix::random_poisson_with_rng(3, 4, 4.5, &mut rng()));
+        assert_eq!(Matrix::<i64>::random_binomial_seeded(3, 4, 10, 0.3, SEED),
+                   Matrix::random_binomial_with_rng(3, 4, 10, 0.3, &mut rng()));
+        assert_eq!(Matrix::<f64>::random_orthogonal_seeded(4, SEED), Matrix::random_orthogonal_with_rng(4, &mut rng()));
+        assert_eq!(Matrix::<f64>::random_spd_seeded(4, SEED), Matrix::random_spd_with_rng(4, &mut rng()));
+        assert_eq!(Matrix::<f64>::random_goe_seeded(4, SEED), Matrix::random_goe_with_rng(4, &mut rng()));
+        let scale = Matrix::<f64>::identity(3);
+        assert_eq!(Matrix::random_wishart_seeded(&scale, 5, SEED), Matrix::random_wishart_with_rng(&scale, 5, &mut rng()));
+
+        assert_eq!(Matrix::<f64>::random_normal_seeded(3, 3, 0.0, 1.0, SEED), Matrix::random_normal_seeded(3, 3, 0.0, 1.0, SEED));
+        assert_ne!(Matrix::<f64>::random_normal_seeded(3, 3, 0.0, 1.0, SEED), Matrix::random_normal_seeded(3, 3, 0.0, 1.0, SEED + 1));
+    }
+}
+
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+////////////// Testing serde, NumPy .npy and binary serialization for: i8, i32, u16, f64 ///////////
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+
+#[cfg(test)]
+mod serialization {
+    use MyMatrixLib::Matrix;
+
+    /// Builds a version 1.0 .npy file by hand, as written by NumPy.
+    fn npy_bytes(header: &str, data: &[u8]) -> Vec<u8> {
+        let mut bytes = b"\x93NUMPY\x01\x00".to_vec();
+        bytes.extend_from_slice(&(header.len() as u16).to_le_bytes());
+        bytes.extend_from_slice(header.as_bytes());
+        bytes.extend_from_slice(data);
+        bytes
+    }
+
+    #[test]
+    fn test_matrix_npy_round_trip()
+    {
+        for rows in 0..6 {
+            for cols in 0..6 {
+                let mat = Matrix::<f64>::random_uniform(rows.max(1), cols.max(1), -1e6, 1e6);
+                let mut bytes = Vec::new();
+                mat.write_npy(&mut bytes).unwrap();
+                assert_eq!((bytes.len() - (rows.max(1) * cols.max(1) * 8)) % 64, 0);
+                assert_eq!(Matrix::<f64>::read_npy(&bytes[..]).unwrap(), mat);
+            }
+        }
+
+        let mat = Matrix::<i8>::from_vec(2, 3, vec![-128, -1, 0, 1, 2, 127]);
+        let mut bytes = Vec::new();
+        mat.write_npy(&mut bytes).unwrap();
+        let header = String::from_utf8_lossy(&bytes[10..bytes.len() - 6]).to_string();
+        assert!(header.starts_with("{'descr': '|i1', 'fortran_order': False, 'shape': (2, 3), }"));
+        assert!(header.ends_with('\n'));
+        assert_eq!(Matrix::<i8>::read_npy(&bytes[..]).unwrap(), mat);
+    }
+
+    #[test]
+    fn test_matrix_npy_read_numpy_layouts()
+    {
+        let data: Vec<u8> = [1_i32, 2, 3, 4, 5, 6].iter().flat_map(|x| x.to_le_bytes()).collect();
+
+        let c_order = npy_bytes("{'descr': '<i4', 'fortran_order': False, 'shape': (2, 3), }   \n", &data);
+        assert_eq!(Matrix::<i32>::read_npy(&c_order[..]).unwrap(), Matrix::from_vec(2, 3, vec![1, 2, 3, 4, 5, 6]));
+
+        let f_order = npy_bytes("{'descr': '<i4', 'fortran_order': True, 'shape': (2, 3), }    \n", &data);
+        assert_eq!(Matrix::<i32>::read_npy(&f_order[..]).unwrap(), Matrix::from_vec(2, 3, vec![1, 3, 5, 2, 4, 6]));
+
+        let one_dim = npy_bytes("{'descr': '<i4', 'fortran_order': False, 'shape': (6,), }     \n", &data);
+        assert_eq!(Matrix::<i32>::read_npy(&one_dim[..]).unwrap(), Matrix::from_vec(1, 6, vec![1, 2, 3, 4, 5, 6]));
+
+        let wrong_dtype = npy_bytes("{'descr': '<f8', 'fortran_order': False, 'shape': (3,), }     \n", &data);
+        let err = Matrix::<i32>::read_npy(&wrong_dtype[..]).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+
+        let three_dim = npy_bytes("{'descr': '<i4', 'fortran_order': False, 'shape': (1, 2, 3), }\n", &data);
+        assert!(Matrix::<i32>::read_npy(&three_dim[..]).is_err());
+
+        let truncated = npy_bytes("{'descr': '<i4', 'fortran_order': False, 'shape': (3, 3), }    \n", &data);
+        assert_eq!(Matrix::<i32>::read_npy(&truncated[..]).unwrap_err().kind(), std::io::ErrorKind::UnexpectedEof);
+
+        assert!(Matrix::<i32>::read_npy(&b"not a numpy file"[..]).is_err());
+    }
+
+    #[test]
+    fn test_matrix_binary_round_trip()
+    {
+        let mat = Matrix::<u16>::random_uniform(7, 5, u16::MIN, u16::MAX);
+        let mut bytes = Vec::new();
+        mat.write_binary(&mut bytes).unwrap();
+        assert_eq!(bytes.len(), 22 + 7 * 5 * 2);
+        assert_eq!(&bytes[..4], b"MMAT");
+        assert_eq!(Matrix::<u16>::read_binary(&bytes[..]).unwrap(), mat);
+
+        // Reading with the wrong element type is rejected.
+        assert_eq!(Matrix::<i16>::read_binary(&bytes[..]).unwrap_err().kind(), std::io::ErrorKind::InvalidData);
+
+        let empty = Matrix::<f64>::zeros(0, 4);
+        let mut bytes = Vec::new();
+        empty.write_binary(&mut bytes).unwrap();
+        assert_eq!(Matrix::<f64>::read_binary(&bytes[..]).unwrap(), empty);
+    }
+
+    #[test]
+    fn test_matrix_reading_untrusted_headers()
+    {
+        let binary_header = |rows: u64, cols: u64| {
+            let mut bytes = b"MMAT\x01\x0a".to_vec();
+            bytes.extend_from_slice(&rows.to_le_bytes());
+            bytes.extend_from_slice(&cols.to_le_bytes());
+            bytes
+        };
+        // Dimensions whose product overflows are rejected
+        let err = Matrix::<f64>::read_binary(&binary_header(u64::MAX, u64::MAX)[..]).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+        // Huge dimensions without the data fail on the missing bytes instead of allocating them
+        let mut bytes = binary_header(1 << 20, 1 << 20);
+        bytes.extend_from_slice(&1.0_f64.to_le_bytes());
+        assert_eq!(Matrix::<f64>::read_binary(&bytes[..]).unwrap_err().kind(), std::io::ErrorKind::UnexpectedEof);
+
+        let header = "{'descr': '<f8', 'fortran_order': False, 'shape': (4294967296, 4294967296), }";
+        assert_eq!(Matrix::<f64>::read_npy(&npy_bytes(header, &[])[..]).unwrap_err().kind(), std::io::ErrorKind::InvalidData);
+        let header = "{'descr': '<f8', 'fortran_order': False, 'shape': (100000, 100000), }";
+        assert_eq!(Matrix::<f64>::read_npy(&npy_bytes(header, &[])[..]).unwrap_err().kind(), std::io::ErrorKind::UnexpectedEof);
+        let header = "{'descr': '', 'fortran_order': False, 'shape': (1,), }";
+        assert_eq!(Matrix::<u8>::read_npy(&npy_bytes(header, &[0])[..]).unwrap_err().kind(), std::io::ErrorKind::InvalidData);
+        // A header length beyond the end of the file
+        let truncated = b"\x93NUMPY\x02\x00\xff\xff\xff\xff{".to_vec();
+        assert_eq!(Matrix::<f64>::read_npy(&truncated[..]).unwrap_err().kind(), std::io::ErrorKind::UnexpectedEof);
+
+        let json = format!(r#"{{"rows":{},"cols":2,"data":[1,2]}}"#, usize::MAX);
+        assert!(serde_json::from_str::<Matrix<i32>>(&json).is_err());
+    }
+
+    #[test]
+    fn test_matrix_save_and_load_files()
+    {
+        let dir = std::env::temp_dir();
+        let npy_path = dir.join(format!("unit_tests_{}.npy", std::process::id()));
+        let bin_path = dir.join(format!("unit_tests_{}.mmat", std::process::id()));
+
+        let mat = Matrix::<f64>::random_uniform(10, 4, -1.0, 1.0);
+        mat.save_npy(&npy_path).unwrap();
+        mat.save_binary(&bin_path).unwrap();
+        assert_eq!(Matrix::<f64>::load_npy(&npy_path).unwrap(), mat);
+        assert_eq!(Matrix::<f64>::load_binary(&bin_path).unwrap(), mat);
+
+        std::fs::remove_file(npy_path).unwrap();
+        std::fs::remove_file(bin_path).unwrap();
+        assert!(Matrix::<f64>::load_npy(dir.join("does_not_exist.npy")).is_err());
+    }
+
+    #[test]
+    fn test_matrix_serde_json()
+    {
+        let mat = Matrix::<i32>::from_vec(2, 2, vec![1, 2, 3, 4]);
+        let json = serde_json::to_string(&mat).unwrap();
+        assert_eq!(json, r#"{"rows":2,"cols":2,"data":[1,2,3,4]}"#);
+        assert_eq!(serde_json::from_str::<Matrix<i32>>(&json).unwrap(), mat);
+
+        let mat = Matrix::<f64>::random_uniform(3, 5, -1.0, 1.0);
+        let json = serde_json::to_string(&mat).unwrap();
+        assert_eq!(serde_json::from_str::<Matrix<f64>>(&json).unwrap(), mat);
+
+        assert!(serde_json::from_str::<Matrix<i32>>(r#"{"rows":2,"cols":3,"data":[1,2,3,4]}"#).is_err());
+        assert!(serde_json::from_str::<Matrix<i32>>(r#"{"rows":2,"data":[1,2]}"#).is_err());
+    }
+}
+
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+////////////// Testing CSV and Matrix Market reading and writing for types: i32, f64 ///////////////
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+
+#[cfg(test)]
+mod text_formats {
+    use MyMatrixLib::{Matrix, MatrixMarketElement, ParseError};
+
+    #[test]
+    fn test_matrix_csv_round_trip()
+    {
+        let mat = Matrix::<f64>::random_uniform(6, 4, -1e3, 1e3);
+        for delimiter in [',', ';', '\t'] {
+            let mut bytes = Vec::new();
+            mat.to_csv(&mut bytes, delimiter).unwrap();
+            assert_eq!(Matrix::<f64>::from_csv(&bytes[..], delimiter, false).unwrap(), mat);
+        }
+
+        let mat = Matrix::<i32>::from_vec(2, 3, vec![1, -2, 3, 4, 5, -6]);
+        let mut bytes = Vec::new();
+        mat.to_csv_with_header(&mut bytes, ',', &["a", "b", "c"]).unwrap();
+        assert_eq!(String::from_utf8(bytes.clone()).unwrap(), "a,b,c\n1,-2,3\n4,5,-6\n");
+        let (header, loaded) = Matrix::<i32>::from_csv_with_header(&bytes[..], ',').unwrap();
+        assert_eq!(header, vec!["a", "b", "c"]);
+        assert_eq!(loaded, mat);
+        assert_eq!(Matrix::<i32>::from_csv(&bytes[..], ',', true).unwrap(), mat);
+    }
+
+    #[test]
+    fn test_matrix_csv_whitespace_and_blank_lines()
+    {
+        let text = "\"x\", \"y\"\r\n 1.5 , 2\r\n\r\n3,4.25\r\n";
+        let (header, mat) = Matrix::<f64>::from_csv_with_header(text.as_bytes(), ',').unwrap();
+        assert_eq!(header, vec!["x", "y"]);
+        assert_eq!(mat, Matrix::from_vec(2, 2, vec![1.5, 2.0, 3.0, 4.25]));
+    }
+
+    #[test]
+    fn test_matrix_csv_errors()
+    {
+        let ragged = "1,2,3\n4,5\n";
+        match Matrix::<i32>::from_csv(ragged.as_bytes(), ',', false) {
+            Err(ParseError::RaggedRow { line, expected, found }) => assert_eq!((line, expected, found), (2, 3, 2)),
+            other => panic!("Expected RaggedRow, got {:?}", other),
+        }
+
+        let ragged_header = "a,b\n1,2,3\n";
+        assert!(matches!(Matrix::<i32>::from_csv(ragged_header.as_bytes(), ',', true),
+                         Err(ParseError::RaggedRow { line: 2, expected: 2, found: 3 })));
+
+        let invalid = "1,2\n\n3,abc\n";
+        let err = Matrix::<i32>::from_csv(invalid.as_bytes(), ',', false).unwrap_err();
+        assert!(matches!(&err, ParseError::InvalidValue { line: 3, column: 2, value } if value == "abc"));
+        assert_eq!(err.to_string(), "line 3, column 2: cannot parse 'abc'");
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_matrix_csv_header_length_panic()
+    {
+        let mat = Matrix::<i32>::zeros(2, 3);
+        mat.to_csv_with_header(Vec::new(), ',', &["a", "b"]).unwrap();
+    }
+
+    #[test]
+    fn test_matrix_market_round_trip()
+    {
+        let mut mat = Matrix::<f64>::random_uniform(5, 3, -1.0, 1.0);
+        mat[(1, 2)] = 0.0;
+        mat[(4, 0)] = 0.0;
+
+        let mut dense = Vec::new();
+        mat.write_matrix_market(&mut dense).unwrap();
+        assert!(String::from_utf8_lossy(&dense).starts_with("%%MatrixMarket matrix array real general\n5 3\n"));
+        assert_eq!(Matrix::<f64>::read_matrix_market(&dense[..]).unwrap(), mat);
+
+        let mut sparse = Vec::new();
+        mat.write_matrix_market_coordinate(&mut sparse).unwrap();
+        assert!(String::from_utf8_lossy(&sparse).starts_with("%%MatrixMarket matrix coordinate real general\n5 3 13\n"));
+        assert_eq!(Matrix::<f64>::read_matrix_market(&sparse[..]).unwrap(), mat);
+
+        let ints = Matrix::<i64>::from_vec(2, 2, vec![0, 7, -3, 0]);
+        let mut bytes = Vec::new();
+        ints.write_matrix_market_coordinate(&mut bytes).unwrap();
+        assert_eq!(String::from_utf8(bytes.clone()).unwrap(),
+                   "%%MatrixMarket matrix coordinate integer general\n2 2 2\n2 1 -3\n1 2 7\n");
+        assert_eq!(Matrix::<i64>::read_matrix_market(&bytes[..]).unwrap(), ints);
+
+        assert_eq!((<u8 as MatrixMarketElement>::MTX_FIELD, <f32 as MatrixMarketElement>::MTX_FIELD), ("integer", "real"));
+    }
+
+    #[test]
+    fn test_matrix_market_variants()
+    {
+        let symmetric = "%%MatrixMarket matrix coordinate real symmetric\n% comment\n3 3 3\n1 1 4.0\n2 1 -1.0\n3 3 2.5\n";
+        assert_eq!(Matrix::<f64>::read_matrix_market(symmetric.as_bytes()).unwrap(),
+                   Matrix::from_vec(3, 3, vec![4.0, -1.0, 0.0, -1.0, 0.0, 0.0, 0.0, 0.0, 2.5]));
+
+        let skew = "%%MatrixMarket matrix array integer skew-symmetric\n3 3\n1\n2\n3\n";
+        assert_eq!(Matrix::<i32>::read_matrix_market(skew.as_bytes()).unwrap(),
+                   Matrix::from_vec(3, 3, vec![0, -1, -2, 1, 0, -3, 2, 3, 0]));
+
+        let symmetric_array = "%%MatrixMarket matrix array real symmetric\n2 2\n1 2\n3\n";
+        assert_eq!(Matrix::<f64>::read_matrix_market(symmetric_array.as_bytes()).unwrap(),
+                   Matrix::from_vec(2, 2, vec![1.0, 2.0, 2.0, 3.0]));
+
+        let pattern = "%%MatrixMarket matrix coordinate pattern general\n2 3 2\n1 3\n2 1\n";
+        assert_eq!(Matrix::<u8>::read_matrix_market(pattern.as_bytes()).unwrap(),
+                   Matrix::from_vec(2, 3, vec![0, 0, 1, 1, 0, 0]));
+    }
+
+    #[test]
+    fn test_matrix_market_errors()
+    {
+        let bad_header = "%%MatrixMarket matrix coordinate complex general\n1 1 1\n1 1 1 0\n";
+        assert!(matches!(Matrix::<f64>::read_matrix_market(bad_header.as_bytes()),
+                         Err(ParseError::InvalidFormat { line: 1, .. })));
+
+        let out_of_bounds = "%%MatrixMarket matrix coordinate real general\n2 2 1\n3 1 1.0\n";
+        assert!(matches!(Matrix::<f64>::read_matrix_market(out_of_bounds.as_bytes()),
+                         Err(ParseError::InvalidFormat { line: 3, .. })));
+
+        let missing = "%%MatrixMarket matrix coordinate real general\n2 2 2\n1 1 1.0\n";
+        assert!(matches!(Matrix::<f64>::read_matrix_market(missing.as_bytes()),
+                         Err(ParseError::InvalidFormat { line: 3, .. })));
+
+        let invalid = "%%MatrixMarket matrix array real general\n1 2\n1.0\nx\n";
+        assert!(matches!(Matrix::<f64>::read_matrix_market(invalid.as_bytes()),
+                         Err(ParseError::InvalidValue { line: 4, column: 1, .. })));
+
+        // Sizes whose product overflows, or whose dense matrix cannot be allocated
+        let overflowing = "%%MatrixMarket matrix coordinate real general\n99999999999 99999999999 0\n";
+        assert!(matches!(Matrix::<f64>::read_matrix_market(overflowing.as_bytes()),
+                         Err(ParseError::InvalidFormat { line: 2, .. })));
+        let oversized = format!("%%MatrixMarket matrix coordinate real general\n% comment\n{} {} 1\n1 1 1.0\n", usize::MAX / 16, 2);
+        assert!(matches!(Matrix::<f64>::read_matrix_market(oversized.as_bytes()),
+                         Err(ParseError::InvalidFormat { line: 3, .. })));
+    }
+
+    #[test]
+    fn test_matrix_text_files()
+    {
+        let dir = std::env::temp_dir();
+        let csv_path = dir.join(format!("unit_tests_{}.csv", std::process::id()));
+        let mtx_path = dir.join(format!("unit_tests_{}.mtx", std::process::id()));
+
+        let mat = Matrix::<f32>::random_uniform(4, 6, -1.0, 1.0);
+        mat.save_csv(&csv_path, ',').unwrap();
+        mat.save_matrix_market(&mtx_path).unwrap();
+        assert_eq!(Matrix::<f32>::load_csv(&csv_path, ',', false).unwrap(), mat);
+        assert_eq!(Matrix::<f32>::load_matrix_market(&mtx_path).unwrap(), mat);
+
+        std::fs::remove_file(csv_path).unwrap();
+        std::fs::remove_file(mtx_path).unwrap();
+        assert!(matches!(Matrix::<f32>::load_csv(dir.join("does_not_exist.csv"), ',', false), Err(ParseError::Io(_))));
+    }
+}
+
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+//////////////////// Testing aligned, elided, NumPy and LaTeX matrix formatting ////////////////////
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+
+#[cfg(test)]
+mod formatting {
+    use MyMatrixLib::{Matrix, PrintOptions, PrintStyle};
+
+    #[test]
+    fn test_matrix_display_aligned()
+    {
+        let mat = Matrix::from_vec(2, 3, vec![1, -2, 300, 40, 5, 6]);
+        assert_eq!(format!("{}", mat), " 1 -2 300\n40  5   6\n");
+        assert_eq!(format!("{:4}", mat), "   1   -2  300\n  40    5    6\n");
+        assert_eq!(format!("{}", Matrix::<i32>::zeros(0, 3)), "");
+    }
+
+    #[test]
+    fn test_matrix_display_precision()
+    {
+        let mat = Matrix::from_vec(2, 2, vec![1.0, -2.5, 1.0 / 3.0, 10.0]);
+        assert_eq!(format!("{:.3}", mat), "1.000 -2.500\n0.333 10.000\n");
+
+        let options = PrintOptions { precision: Some(1), ..PrintOptions::default() };
+        assert_eq!(format!("{:.3}", mat.display_with(options)), "1.0 -2.5\n0.3 10.0\n");
+    }
+
+    #[test]
+    fn test_matrix_display_alternate_numpy()
+    {
+        let mat = Matrix::from_vec(2, 2, vec![1, -2, 30, 4]);
+        assert_eq!(format!("{:#}", mat), "[[ 1 -2]\n [30  4]]");
+
+        let options = PrintOptions { style: PrintStyle::NumPy, ..PrintOptions::default() };
+        assert_eq!(format!("{}", mat.display_with(options)), format!("{:#}", mat));
+        assert_eq!(format!("{:#}", Matrix::<i32>::zeros(0, 0)), "[]");
+    }
+
+    #[test]
+    fn test_matrix_display_latex()
+    {
+        let mat = Matrix::from_vec(2, 2, vec![1.0, -2.5, 30.0, 4.0]);
+        let options = PrintOptions { style: PrintStyle::LaTeX, ..PrintOptions::default() };
+        assert_eq!(format!("{:.1}", mat.display_with(options)),
+                   "\\begin{bmatrix}\n 1.0 & -2.5 \\\\\n30.0 &  4.0\n\\end{bmatrix}");
+    }
+
+    #[test]
+    fn test_matrix_display_elided()
+    {
+        let mat = Matrix::from_vec(40, 40, (0..1600).collect());
+        let text = format!("{}", mat);
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines.len(), 7);
+        assert_eq!(lines[0], "   0    1    2 ...   37   38   39");
+        assert_eq!(lines[3], "...");
+        assert_eq!(lines[6], "1560 1561 1562 ... 1597 1598 1599");
+
+        // Small matrices are never elided.
+        assert_eq!(format!("{}", Matrix::<i32>::ones(10, 10)).lines().count(), 10);
+
+        let options = PrintOptions { style: PrintStyle::LaTeX, edge_items: 1, threshold: 4, ..PrintOptions::default() };
+        let mat = Matrix::from_vec(3, 3, (1..10).collect());
+        assert_eq!(format!("{}", mat.display_with(options)),
+                   "\\begin{bmatrix}\n     1 & \\cdots &      3 \\\\\n\\vdots & \\ddots & \\vdots \\\\\n     7 & \\cdots &      9\n\\end{bmatrix}");
+
+        let options = PrintOptions { style: PrintStyle::NumPy, edge_items: 1, threshold: 4, ..PrintOptions::default() };
+        assert_eq!(format!("{}", mat.display_with(options)), "[[  1 ...   3]\n ...\n [  7 ...   9]]");
+    }
+}
+
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+//////////////////////////// Testing matrix! and vector! literal macros ////////////////////////////
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+
+#[cfg(test)]
+mod literals {
+    use MyMatrixLib::{matrix, vector, Matrix, Vector};
+
+    #[test]
+    fn test_matrix_macro()
+    {
+        let mat = matrix![1, 2, 3;
+                          4, 5, 6];
+        assert_eq!(mat, Matrix::from_vec(2, 3, vec![1, 2, 3, 4, 5, 6]));
+        assert_eq!(matrix![1.5, -2.0], Matrix::from_vec(1, 2, vec![1.5, -2.0]));
+        assert_eq!(matrix![1_u8; 2; 3;], Matrix::from_vec(3, 1, vec![1, 2, 3]));
+        assert_eq!(matrix![1, 2,; 3, 4,], Matrix::from_vec(2, 2, vec![1, 2, 3, 4]));
+
+        let x = 2.0_f32;
+        assert_eq!(matrix![x * x, x + 1.0; -x, 0.0], Matrix::from_vec(2, 2, vec![4.0, 3.0, -2.0, 0.0]));
+
+        let empty: Matrix<i32> = matrix![];
+        assert_eq!(empty, Matrix::from_vec(0, 0, Vec::new()));
+    }
+
+    #[test]
+    fn test_matrix_from_array()
+    {
+        let mat = Matrix::from_array([[1, 2], [3, 4], [5, 6]]);
+        assert_eq!(mat, Matrix::from_vec(3, 2, vec![1, 2, 3, 4, 5, 6]));
+        assert_eq!(Matrix::<i64>::from_array::<2, 0>([[], []]), Matrix::from_vec(2, 0, Vec::new()));
+    }
+
+    #[test]
+    fn test_vector_macro()
+    {
+        assert_eq!(vector![1, 2, 3], Vector::from_vec(vec![1, 2, 3]));
+        assert_eq!(vector![0.5, 1.5,], Vector::from_vec(vec![0.5, 1.5]));
+        let empty: Vector<f64> = vector![];
+        assert!(empty.is_empty());
+    }
+}
+
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+/////////////////// Testing generic code over the Scalar, Field and Real traits ////////////////////
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+
+#[cfg(test)]
+mod scalar_traits {
+    use MyMatrixLib::{matrix, Matrix, Real, Scalar};
+
+    fn scaled_trace<T: Scalar + 'static>(m: &Matrix<T>, factor: T) -> T {
+        m.scale(factor).trace()
+    }
+
+    fn residual_norm<T: Real + std::fmt::Display>(a: &Matrix<T>, x: &Matrix<T>, b: &Matrix<T>) -> T {
+        let residual = a.clone() * x.clone() - b.clone();
+        (residual.transpose() * residual)[(0, 0)].sqrt()
+    }
+
+    #[test]
+    fn test_scalar_generic_functions()
+    {
+        assert_eq!(scaled_trace(&matrix![1, 2; 3, 4], 3), 15);
+        assert_eq!(scaled_trace(&matrix![1_u8, 2; 3, 4], 2), 10);
+        assert_eq!(scaled_trace(&matrix![0.5, 2.0; 3.0, 0.25], 2.0), 1.5);
+    }
+
+    #[test]
+    fn test_real_generic_functions()
+    {
+        let a = matrix![4.0, 1.0; 1.0, 3.0];
+        let b = matrix![1.0; 2.0];
+        let x = a.solve(&b);
+        assert!(residual_norm(&a, &x, &b) < 1e-12);
+
+        let a = matrix![4.0_f32, 1.0; 1.0, 3.0];
+        let b = matrix![1.0_f32; 2.0];
+        assert!(residual_norm(&a, &a.solve(&b), &b) < 1e-5);
+    }
+}
+
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+///////////////// Testing approximate equality and proptest/quickcheck generators //////////////////
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+
+#[cfg(test)]
+mod properties {
+    use MyMatrixLib::{arbitrary_matrix, matrix, matrix_strategy, Matrix};
+    use approx::{assert_abs_diff_eq, assert_relative_eq, assert_ulps_eq};
+    use proptest::prelude::*;
+
+    /// Strategy for a pair of matrices `(A, B)` of dims `m x k` and `k x n`.
+    fn compatible_pair(max_dim: usize) -> impl Strategy<Value=(Matrix<f64>, Matrix<f64>)> {
+        (1..=max_dim, 1..=max_dim, 1..=max_dim).prop_flat_map(|(m, k, n)| {
+            (matrix_strategy(m..=m, k..=k, -100.0..100.0), matrix_strategy(k..=k, n..=n, -100.0..100.0))
+        })
+    }
+
+    #[test]
+    fn test_matrix_approx_eq()
+    {
+        let a = matrix![1.0, 1e6; 0.0, -3.0];
+        let b = matrix![1.0 + 1e-12, 1e6 + 1e-4; 1e-10, -3.0];
+        assert!(a.approx_eq(&b, 1e-9, 1e-9));
+        assert!(!a.approx_eq(&b, 1e-9, 0.0));
+        assert!(!a.approx_eq(&b, 1e-11, 1e-9));
+        assert!(!a.approx_eq(&matrix![1.0, 1e6, 0.0, -3.0], 1.0, 1.0));
+
+        let special = matrix![f64::INFINITY, f64::NAN];
+        assert!(!special.approx_eq(&special, 1.0, 1.0));
+        assert!(matrix![f64::INFINITY].approx_eq(&matrix![f64::INFINITY], 0.0, 0.0));
+        assert!(!matrix![f64::INFINITY].approx_eq(&matrix![f64::NEG_INFINITY], 1.0, 1.0));
+    }
+
+    #[test]
+    fn test_matrix_approx_traits()
+    {
+        let a = matrix![1.0_f32, 2.0; 3.0, 4.0];
+        let b = matrix![1.0_f32 + 1e-7, 2.0; 3.0, 4.0 - 1e-7];
+        assert_abs_diff_eq!(a, b, epsilon = 1e-6);
+        assert_relative_eq!(a, b, max_relative = 1e-6);
+        assert_ulps_eq!(a, b, max_ulps = 4);
+        assert!(approx::abs_diff_ne!(a, matrix![1.0_f32, 2.0, 3.0, 4.0]));
+
+        let c = matrix![1.0, 2.0; 3.0, 4.0];
+        assert_relative_eq!(c.inverse().inverse(), c, epsilon = 1e-12);
+    }
+
+    proptest! {
+        #[test]
+        fn prop_transpose_of_product((a, b) in compatible_pair(8)) {
+            let lhs = (a.clone() * b.clone()).transpose();
+            let rhs = b.transpose() * a.transpose();
+            prop_assert!(lhs.approx_eq(&rhs, 1e-9, 1e-12));
+        }
+
+        #[test]
+        fn prop_transpose_is_involution(a in any::<Matrix<i64>>()) {
+            prop_assert_eq!(a.transpose().transpose(), a);
+        }
+
+        #[test]
+        fn prop_matrix_strategy_dims(a in matrix_strategy(2..5, 3..=3, 0_u8..10)) {
+            prop_assert_eq!(a.view_row(0).len(), 3);
+            prop_assert!((2..5).contains(&a.transpose().view_row(0).len()));
+            prop_assert!(a.view_row(0).iter().all(|&x| x < 10));
+        }
+
+        #[test]
+        fn prop_addition_commutes(a in matrix_strategy(1..6, 1..6, -1000_i32..1000)) {
+            let b = a.scale(3);
+            prop_assert_eq!(a.clone() + b.clone(), b + a);
+        }
+    }
+
+    #[test]
+    fn test_quickcheck_properties()
+    {
+        fn transpose_is_involution(a: Matrix<i32>) -> bool {
+            a.transpose().transpose() == a
+        }
+        quickcheck::quickcheck(transpose_is_involution as fn(Matrix<i32>) -> bool);
+
+        let mut g = quickcheck::Gen::new(100);
+        for _ in 0..50 {
+            let a: Matrix<i16> = arbitrary_matrix(&mut g, 2..=4, 3..=3);
+            let t = a.transpose();
+            assert!((2..=4).contains(&t.view_row(0).len()));
+            assert_eq!(a.view_row(0).len(), 3);
+        }
+    }
+}
+
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+//////////////////////// Testing exact integer and modular arithmetic methods //////////////////////
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+
+#[cfg(test)]
+mod exact_integer {
+    use MyMatrixLib::{matrix, Matrix, ModInt};
+    const MAX_SIZE: usize = 8;
+    // The unimodular transforms of the normal forms grow quickly with the dimensions
+    const TRANSFORM_SIZE: usize = 6;
+
+    type F7 = ModInt<7>;
+    type F2 = ModInt<2>;
+    // The largest prime below 2^63
+    type Big = ModInt<9223372036854775783>;
+
+    /// Determinant by cofactor expansion along the first row.
+    fn cofactor_determinant(m: &Matrix<i64>, n: usize) -> i64 {
+        if n == 1 {
+            return m[(0, 0)];
+        }
+        (0..n).map(|j| {
+            let minor_data = (1..n).flat_map(|i| (0..n).filter(move |&c| c != j).map(move |c| (i, c)))
+                .map(|(i, c)| m[(i, c)]).collect();
+            let minor = Matrix::from_vec(n - 1, n - 1, minor_data);
+            let sign = if j % 2 == 0 { 1 } else { -1 };
+            sign * m[(0, j)] * cofactor_determinant(&minor, n - 1)
+        }).sum()
+    }
+
+    /// Checks that the determinant is ±1 modulo two large primes, which avoids the overflow of
+    /// computing it exactly for transforms with large entries.
+    fn is_unimodular(m: &Matrix<i64>) -> bool {
+        let det_1 = m.map_mod::<1_000_000_007>().modular_determinant();
+        let det_2 = m.map_mod::<998_244_353>().modular_determinant();
+        (det_1 == ModInt::new(1) || det_1 == ModInt::new(-1)) && (det_2 == ModInt::new(1) || det_2 == ModInt::new(-1))
+    }
+
+    fn widen(m: &Matrix<i64>) -> Matrix<i128> {
+        let rows = m.transpose().view_row(0).len();
+        let data = (0..rows).flat_map(|i| m.view_row(i).iter().map(|&x| x as i128).collect::<Vec<_>>()).collect();
+        Matrix::from_vec(rows, m.view_row(0).len(), data)
+    }
+
+    #[test]
+    fn test_modint_arithmetic()
+    {
+        assert_eq!(F7::new(-1).value(), 6);
+        assert_eq!(F7::new(3) + F7::new(5), F7::new(1));
+        assert_eq!(F7::new(3) - F7::new(5), F7::new(5));
+        assert_eq!(F7::new(3) * F7::new(5), F7::new(1));
+        assert_eq!(F7::new(1) / F7::new(3), F7::new(5));
+        assert_eq!(-F7::new(2), F7::new(5));
+        assert_eq!(F7::new(3).pow(6), F7::new(1));
+        assert_eq!(ModInt::<6>::new(4).inverse(), None);
+        assert_eq!(ModInt::<6>::new(5).inverse(), Some(ModInt::new(5)));
+        assert_eq!(format!("{}", F7::from(10)), "3");
+
+        // Products of large residues do not overflow
+        let a = Big::new(-2);
+        assert_eq!(a * a, Big::new(4));
+        assert_eq!(a / a, Big::new(1));
+        assert_eq!(a.pow(9223372036854775782), Big::new(1));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_modint_division_by_zero()
+    {
+        let _ = F7::new(1) / F7::new(0);
+    }
+
+    #[test]
+    fn test_matrix_modular_elimination()
+    {
+        let a = matrix![1, 2; 3, 4].map_mod::<5>();
+        assert_eq!(a.modular_determinant(), ModInt::new(-2));
+        assert_eq!(a.clone() * a.modular_inverse().unwrap(), Matrix::identity(2));
+
+        let singular = matrix![1, 2, 3; 4, 5, 6; 7, 8, 9].map_mod::<7>();
+        assert_eq!(singular.modular_rank(), 2);
+        assert_eq!(singular.modular_determinant(), F7::new(0));
+        assert_eq!(singular.modular_inverse(), None);
+        assert_eq!(singular.modular_rref(), matrix![1, 0, -1; 0, 1, 2; 0, 0, 0].map_mod::<7>());
+
+        // Singular over GF(3) but not over the rationals (det = -3)
+        let a = matrix![1, 2; 2, 1].map_mod::<3>();
+        assert_eq!(a.modular_rank(), 1);
+        assert_eq!(matrix![1, 2; 2, 1].map_mod::<5>().modular_rank(), 2);
+
+        // The empty system has the empty solution
+        let empty = Matrix::<i64>::zeros(0, 0).map_mod::<7>();
+        assert_eq!(empty.modular_inverse(), Some(Matrix::zeros(0, 0)));
+        assert_eq!(empty.modular_solve(&Matrix::zeros(0, 3)), Some(Matrix::zeros(0, 3)));
+        assert_eq!(empty.modular_determinant(), F7::new(1));
+
+        for size in 1..MAX_SIZE {
+            let a = Matrix::<i64>::random_uniform(size, size, -50, 50).map_mod::<101>();
+            let x = Matrix::<i64>::random_uniform(size, 2, -50, 50).map_mod::<101>();
+            let b = a.clone() * x.clone();
+            match a.modular_solve(&b) {
+                Some(solution) => {
+                    assert_eq!(solution, x);
+                    assert_ne!(a.modular_determinant(), ModInt::new(0));
+                }
+                None => assert_eq!(a.modular_determinant(), ModInt::new(0)),
+            }
+        }
+    }
+
+    #[test]
+    fn test_matrix_bareiss_determinant()
+    {
+        assert_eq!(matrix![2_i64, 4; 6, 8].determinant_bareiss(), -8);
+        assert_eq!(matrix![0_i32, 1; 1, 0].determinant_bareiss(), -1);
+        assert_eq!(matrix![1_i8, 2; 2, 4].determinant_bareiss(), 0);
+        assert_eq!(Matrix::<i64>::zeros(0, 0).determinant_bareiss(), 1);
+
+        for size in 1..MAX_SIZE {
+            let a = Matrix::<i64>::random_uniform(size, size, -9, 9);
+            assert_eq!(a.determinant_bareiss(), cofactor_determinant(&a, size));
+        }
+
+        // Hilbert-like integer matrix with a large determinant, exact in i128
+        let n = 12;
+        let data = (0..n * n).map(|k| ((k / n + k % n + 1) as i128).pow(2)).collect();
+        let a = Matrix::from_vec(n, n, data);
+        assert_eq!(a.determinant_bareiss(), 0);
+        assert_eq!(Matrix::<i128>::identity(20).scale(3).determinant_bareiss(), 3_i128.pow(20));
+    }
+
+    #[test]
+    fn test_matrix_hermite_normal_form()
+    {
+        let a = matrix![2_i64, 3, 6, 2; 5, 6, 1, 6; 8, 3, 1, 1];
+        let (h, u) = a.hermite_normal_form();
+        assert_eq!(u.clone() * a.clone(), h);
+        assert!(is_unimodular(&u));
+        assert_eq!(h, matrix![1, 0, 50, -11; 0, 3, 28, -2; 0, 0, 61, -13]);
+
+        for rows in 1..TRANSFORM_SIZE {
+            for cols in 1..TRANSFORM_SIZE {
+                let a = Matrix::<i64>::random_uniform(rows, cols, -4, 4);
+                let (h, u) = a.hermite_normal_form();
+                assert_eq!(widen(&u) * widen(&a), widen(&h));
+                assert!(is_unimodular(&u));
+
+                // Row echelon form with positive pivots and reduced entries above them
+                let mut last_pivot = None;
+                for i in 0..rows {
+                    match h.view_row(i).iter().position(|&x| x != 0) {
+                        Some(col) => {
+                            assert!(last_pivot.is_none_or(|last| col > last));
+                            assert!(h[(i, col)] > 0);
+                            for k in 0..i {
+                                assert!(h[(k, col)] >= 0 && h[(k, col)] < h[(i, col)]);
+                            }
+                            last_pivot = Some(col);
+                        }
+                        None => assert!(h.view_row(i).iter().all(|&x| x == 0)),
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_matrix_smith_normal_form()
+    {
+        let a = matrix![2_i64, 4, 4; -6, 6, 12; 10, -4, -16];
+        let (u, s, v) = a.smith_normal_form();
+        assert_eq!(s, matrix![2, 0, 0; 0, 6, 0; 0, 0, 12]);
+        assert_eq!(u * a * v, s);
+
+        for rows in 1..TRANSFORM_SIZE {
+            for cols in 1..TRANSFORM_SIZE {
+                let a = Matrix::<i64>::random_uniform(rows, cols, -4, 4);
+                let (u, s, v) = a.smith_normal_form();
+                assert_eq!(widen(&u) * widen(&a) * widen(&v), widen(&s));
+                assert!(is_unimodular(&u) && is_unimodular(&v));
+
+                let diagonal: Vec<i64> = (0..rows.min(cols)).map(|i| s[(i, i)]).collect();
+                for i in 0..rows {
+                    for j in 0..cols {
+                        if i != j { assert_eq!(s[(i, j)], 0); }
+                    }
+                }
+                for pair in diagonal.windows(2) {
+                    assert!(pair[0] >= 0);
+                    assert!(if pair[0] == 0 { pair[1] == 0 } else { pair[1] % pair[0] == 0 });
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_matrix_rank_gf2()
+    {
+        let hamming = Matrix::from_vec(3, 7, vec![1_u8, 0, 1, 0, 1, 0, 1,
+                                                  0, 1, 1, 0, 0, 1, 1,
+                                                  0, 0, 0, 1, 1, 1, 1]);
+        assert_eq!(hamming.rank_gf2(), 3);
+        assert_eq!(matrix![1, 1; 1, 1].rank_gf2(), 1);
+        assert_eq!(matrix![2, 4; 6, 8].rank_gf2(), 0);
+        assert_eq!(matrix![1_i32, 1, 0; 0, 1, 1; 1, 0, 1].rank_gf2(), 2);
+
+        // Wide matrices span several words; compare with elimination over ModInt<2>
+        for _ in 0..20 {
+            let a = Matrix::<i64>::random_uniform(12, 150, 0, 2);
+            assert_eq!(a.rank_gf2(), a.map_mod::<2>().modular_rank());
+            assert_eq!(a.transpose().rank_gf2(), a.rank_gf2());
+        }
+        let identity = Matrix::<u64>::identity(130);
+        assert_eq!(identity.rank_gf2(), 130);
+        assert_eq!(Matrix::<u8>::zeros(0, 5).rank_gf2(), 0);
+        let _: F2 = F2::new(3);
+    }
+}
+
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+/////////////// Testing exact rational elimination for types: Rational64, BigRational //////////////
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+
+#[cfg(test)]
+mod exact_rational {
+    use MyMatrixLib::{matrix, Matrix};
+    use num_bigint::BigInt;
+    use num_rational::{BigRational, Ratio, Rational64};
+    const MAX_SIZE: usize = 7;
+
+    fn r(numer: i64, denom: i64) -> Rational64 {
+        Ratio::new(numer, denom)
+    }
+
+    fn big(numer: i64, denom: i64) -> BigRational {
+        Ratio::new(BigInt::from(numer), BigInt::from(denom))
+    }
+
+    /// The `n x n` Hilbert matrix, `h_ij = 1 / (i + j + 1)`.
+    fn big_hilbert(n: usize) -> Matrix<BigRational> {
+        Matrix::from_vec(n, n, (0..n * n).map(|k| big(1, (k / n + k % n + 1) as i64)).collect())
+    }
+
+    /// The product `a * b` of `n x n` matrices, computed by hand since `*` requires `Copy`
+    /// elements.
+    fn big_product(a: &Matrix<BigRational>, b: &Matrix<BigRational>, n: usize) -> Matrix<BigRational> {
+        let mut product: Matrix<BigRational> = Matrix::zeros(n, n);
+        for i in 0..n {
+            for j in 0..n {
+                for k in 0..n {
+                    product[(i, j)] = product[(i, j)].clone() + a[(i, k)].clone() * b[(k, j)].clone();
+                }
+            }
+        }
+        product
+    }
+
+    #[test]
+    fn test_rational_inverse_and_determinant()
+    {
+        let a = matrix![4_i64, 7; 2, 6].to_rational();
+        assert_eq!(a.determinant(), r(10, 1));
+        assert_eq!(a.inverse(), matrix![r(3, 5), r(-7, 10); r(-1, 5), r(2, 5)]);
+
+        // The Hilbert matrix is badly conditioned, but exact arithmetic does not care
+        let n = 6;
+        let hilbert = Matrix::from_vec(n, n, (0..n * n).map(|k| r(1, (k / n + k % n + 1) as i64)).collect());
+        let inverse = hilbert.inverse();
+        assert_eq!(hilbert.clone() * inverse.clone(), Matrix::identity(n));
+        assert_eq!(inverse[(0, 0)], r(36, 1));
+        assert_eq!(inverse[(5, 5)], r(698544, 1));
+        assert_eq!(hilbert.determinant(), r(1, 186313420339200000));
+
+        for size in 1..MAX_SIZE {
+            let a = Matrix::<i64>::random_uniform(size, size, -9, 9).to_rational();
+            assert_eq!(a.determinant(), r(a.clone().transpose().determinant().to_integer(), 1));
+            if a.determinant() != r(0, 1) {
+                assert_eq!(a.clone() * a.inverse(), Matrix::identity(size));
+                assert_eq!(a.inverse().determinant(), r(1, 1) / a.determinant());
+            }
+        }
+    }
+
+    #[test]
+    fn test_rational_determinant_matches_bareiss()
+    {
+        for size in 1..MAX_SIZE {
+            let a = Matrix::<i64>::random_uniform(size, size, -20, 20);
+            assert_eq!(a.to_rational().determinant(), r(a.determinant_bareiss(), 1));
+        }
+        assert_eq!(matrix![0_i64, 1; 1, 0].to_rational().determinant(), r(-1, 1));
+        assert_eq!(matrix![1_i64, 2; 2, 4].to_rational().determinant(), r(0, 1));
+    }
+
+    #[allow(non_snake_case)]
+    #[test]
+    fn test_rational_LU_decompositions()
+    {
+        let a = matrix![2_i64, 1, 1; 4, -6, 0; -2, 7, 2].to_rational();
+        let (L, U) = a.LU_decompose();
+        assert_eq!(L.clone() * U.clone(), a);
+        assert_eq!(L[(1, 0)], r(2, 1));
+
+        for size in 1..MAX_SIZE {
+            let a = Matrix::<i64>::random_uniform(size, size, -9, 9).to_rational();
+            let (L, U, P) = a.LUP_decompose();
+            assert_eq!(P * a.clone(), L * U);
+
+            let x = Matrix::<i64>::random_uniform(size, 2, -9, 9).to_rational();
+            if a.determinant() != r(0, 1) {
+                assert_eq!(a.solve(&(a.clone() * x.clone())), x);
+            }
+        }
+    }
+
+    #[test]
+    fn test_matrix_rref()
+    {
+        let a = matrix![1_i64, 2, -1, -4; 2, 3, -1, -11; -2, 0, -3, 22].to_rational();
+        assert_eq!(a.rref(), matrix![1_i64, 0, 0, -8; 0, 1, 0, 1; 0, 0, 1, -2].to_rational());
+
+        let singular = matrix![1_i64, 2, 3; 2, 4, 6; 1, 1, 1].to_rational();
+        assert_eq!(singular.rref(), matrix![1_i64, 0, -1; 0, 1, 2; 0, 0, 0].to_rational());
+
+        let a = matrix![1.0, 2.0, 3.0; 2.0, 4.0, 7.0];
+        assert_eq!(a.rref(), matrix![1.0, 2.0, 0.0; 0.0, 0.0, 1.0]);
+        assert_eq!(Matrix::<f64>::zeros(2, 3).rref(), Matrix::zeros(2, 3));
+    }
+
+    #[test]
+    fn test_rational_display()
+    {
+        let a = matrix![4_i64, 7; 2, 6].to_rational().inverse();
+        assert_eq!(format!("{}", a), " 3/5 -7/10\n-1/5   2/5\n");
+        assert_eq!(format!("{:#}", matrix![r(1, 2), r(3, 1)]), "[[1/2 3]]");
+    }
+
+    #[allow(non_snake_case)]
+    #[test]
+    fn test_big_rational_elimination()
+    {
+        // The entries of the inverse and the determinant overflow 64-bit integers
+        let n = 20;
+        let hilbert = big_hilbert(n);
+        let identity = Matrix::identity(n);
+
+        let inverse = hilbert.inverse();
+        assert_eq!(big_product(&hilbert, &inverse, n), identity);
+        assert_eq!(inverse[(0, 0)], big((n * n) as i64, 1));
+        assert!(inverse[(n - 1, n - 1)] > big(i64::MAX, 1));
+        assert_eq!(hilbert.solve(&identity), inverse);
+
+        // det(H_n) = c_n^4 / c_2n, with c_n = 1! * 2! * ... * (n - 1)!
+        let c = |n: usize| -> BigInt {
+            (1..n).map(|i| (1..=i).map(BigInt::from).product::<BigInt>()).product()
+        };
+        let c_n = c(n);
+        let determinant = hilbert.determinant();
+        assert_eq!(determinant, Ratio::new(c_n.clone() * c_n.clone() * c_n.clone() * c_n, c(2 * n)));
+        assert_eq!(inverse.determinant(), big(1, 1) / determinant);
+
+        let (L, U) = hilbert.LU_decompose();
+        assert_eq!(big_product(&L, &U, n), hilbert);
+        let (L, U, P) = hilbert.LUP_decompose();
+        assert_eq!(big_product(&P, &hilbert, n), big_product(&L, &U, n));
+
+        // [H | I] reduces to [I | H^-1]
+        let augmented = Matrix::from_vec(n, 2 * n, (0..2 * n * n).map(|k| {
+            let (i, j) = (k / (2 * n), k % (2 * n));
+            if j < n { hilbert[(i, j)].clone() } else { identity[(i, j - n)].clone() }
+        }).collect());
+        let reduced = augmented.rref();
+        for i in 0..n {
+            for j in 0..n {
+                assert_eq!(reduced[(i, j)], identity[(i, j)]);
+                assert_eq!(reduced[(i, n + j)], inverse[(i, j)]);
+            }
+        }
+        assert_eq!(augmented.rank(big(0, 1)), n);
+
+        let singular = Matrix::from_vec(2, 3, vec![big(1, 2), big(1, 3), big(1, 4), big(1, 1), big(2, 3), big(1, 2)]);
+        assert_eq!(singular.rank(big(0, 1)), 1);
+        assert_eq!(singular.null_space(big(0, 1)), Matrix::from_vec(3, 2, vec![
+            big(-2, 3), big(-1, 2), big(1, 1), big(0, 1), big(0, 1), big(1, 1)]));
+
+        let a = Matrix::from_vec(2, 2, vec![big(4, 1), big(7, 1), big(2, 1), big(6, 1)]);
+        assert_eq!(format!("{}", a.inverse()), " 3/5 -7/10\n-1/5   2/5\n");
+    }
+}
+
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+/////////////////////////// Testing rref, rank and fundamental subspaces ///////////////////////////
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+
+#[cfg(test)]
+mod subspaces {
+    use MyMatrixLib::{matrix, Matrix};
+    use num_rational::Rational64;
+    const MAX_SIZE: usize = 8;
+    const TOLERANCE: f64 = 1e-10;
+
+    /// A random `rows x cols` matrix of the given rank, as a product of random factors.
+    fn random_with_rank(rows: usize, cols: usize, rank: usize) -> Matrix<f64> {
+        Matrix::<f64>::random_uniform(rows, rank, -1.0, 1.0) * Matrix::<f64>::random_uniform(rank, cols, -1.0, 1.0)
+    }
+
+    #[test]
+    fn test_matrix_rref_with_tolerance()
+    {
+        let a = matrix![1.0, 2.0, 3.0; 2.0, 4.0, 6.0 + 1e-14; 1.0, 0.0, 1.0];
+        assert_eq!(a.rank(0.0), 3);
+        assert_eq!(a.rank(1e-10), 2);
+        assert_eq!(a.pivot_columns(1e-10), vec![0, 1]);
+        let reduced = a.rref_with_tolerance(1e-10);
+        assert!(reduced.approx_eq(&matrix![1.0, 0.0, 1.0; 0.0, 1.0, 1.0; 0.0, 0.0, 0.0], 1e-12, 0.0));
+        assert_eq!(reduced.view_row(2), &[0.0, 0.0, 0.0]);
+    }
+
+    #[test]
+    fn test_matrix_null_and_column_space()
+    {
+        for rows in 1..MAX_SIZE {
+            for cols in 1..MAX_SIZE {
+                for rank in 0..=rows.min(cols) {
+                    let a = random_with_rank(rows, cols, rank);
+                    assert_eq!(a.rank(TOLERANCE), rank);
+
+                    // Comparing with zero matrices also checks the dimensions
+                    let null = a.null_space(TOLERANCE);
+                    assert_eq!(null.scale(0.0), Matrix::zeros(cols, cols - rank));
+                    assert!((a.clone() * null).approx_eq(&Matrix::zeros(rows, cols - rank), 1e-8, 0.0));
+
+                    let column = a.column_space(TOLERANCE);
+                    assert_eq!(column.scale(0.0), Matrix::zeros(rows, rank));
+                    assert_eq!(column.rank(TOLERANCE), rank);
+                    if rank > 0 {
+                        // Every column of A lies in the column space
+                        let projector = {
+                            let q = a.column_space_orthonormal(TOLERANCE);
+                            q.clone() * q.transpose()
+                        };
+                        assert!((projector * a.clone()).approx_eq(&a, 1e-8, 1e-8));
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_matrix_orthonormal_bases()
+    {
+        let a = random_with_rank(5, 7, 3);
+        let null = a.null_space_orthonormal(TOLERANCE);
+        let column = a.column_space_orthonormal(TOLERANCE);
+        assert!((null.transpose() * null.clone()).approx_eq(&Matrix::identity(4), 1e-10, 0.0));
+        assert!((column.transpose() * column).approx_eq(&Matrix::identity(3), 1e-10, 0.0));
+        assert!((a * null).approx_eq(&Matrix::zeros(5, 4), 1e-10, 0.0));
+    }
+
+    #[test]
+    fn test_matrix_solve_homogeneous()
+    {
+        let a = matrix![1.0, 1.0, 0.0; 0.0, 0.0, 1.0];
+        let x = a.solve_homogeneous(TOLERANCE).unwrap();
+        let expected = matrix![1.0; -1.0; 0.0].scale(1.0 / 2.0_f64.sqrt());
+        assert!(x.approx_eq(&expected, 1e-12, 0.0) || x.approx_eq(&expected.scale(-1.0), 1e-12, 0.0));
+
+        assert_eq!(Matrix::<f64>::identity(3).solve_homogeneous(TOLERANCE), None);
+        let x = matrix![1.0, 2.0; 2.0, 4.0].solve_homogeneous(TOLERANCE).unwrap();
+        assert!((x.transpose() * x)[(0, 0)] - 1.0 < 1e-12);
+    }
+
+    #[test]
+    fn test_rational_subspaces()
+    {
+        let a = matrix![1_i64, 2, 3, 4; 2, 4, 6, 8; 1, 0, 1, 0].to_rational();
+        let zero = Rational64::from_integer(0);
+        assert_eq!(a.rank(zero), 2);
+        assert_eq!(a.null_space(zero), matrix![-1_i64, 0; -1, -2; 1, 0; 0, 1].to_rational());
+        assert_eq!(a.column_space(zero), matrix![1_i64, 2; 2, 4; 1, 0].to_rational());
+        assert_eq!(a.clone() * a.null_space(zero), Matrix::zeros(3, 2));
+    }
+}
+
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+///////////////// Testing vectorized element-wise kernels for types: i32, f32, f64 /////////////////
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+
+#[cfg(test)]
+mod elementwise_kernels {
+    use MyMatrixLib::{Matrix, Scalar};
+    use num_rational::Rational64;
+    const MAX_SIZE: usize = 9;
+
+    /// Checks `+`, `-`, `scale`, `sum` and `dot` against indexed loops for every shape up to
+    /// `MAX_SIZE x MAX_SIZE`, so both full vectors and remainders are covered. `close` compares
+    /// the reductions, which may be summed in a different order.
+    fn check_kernels<T, R, C>(random: R, close: C)
+        where
+            T: Scalar + std::fmt::Display + std::fmt::Debug + PartialOrd + 'static,
+            R: Fn(usize, usize) -> Matrix<T>,
+            C: Fn(T, T) -> bool
+    {
+        for rows in 1..=MAX_SIZE {
+            for cols in 1..=MAX_SIZE {
+                let (a, b) = (random(rows, cols), random(rows, cols));
+                let factor = b[(0, 0)];
+                let mut sum = Matrix::zeros(rows, cols);
+                let mut difference = Matrix::zeros(rows, cols);
+                let mut scaled = Matrix::zeros(rows, cols);
+                let (mut total, mut dot) = (T::zero(), T::zero());
+                for i in 0..rows {
+                    for j in 0..cols {
+                        sum[(i, j)] = a[(i, j)] + b[(i, j)];
+                        difference[(i, j)] = a[(i, j)] - b[(i, j)];
+                        scaled[(i, j)] = a[(i, j)] * factor;
+                        total = total + a[(i, j)];
+                        dot = dot + a[(i, j)] * b[(i, j)];
+                    }
+                }
+                assert_eq!(a.clone() + b.clone(), sum);
+                assert_eq!(a.clone() - b.clone(), difference);
+                assert_eq!(a.scale(factor), scaled);
+                assert!(close(a.sum(), total));
+
+                let flatten = |m: &Matrix<T>| Matrix::from_vec(1, rows * cols, (0..rows).flat_map(|i| m.view_row(i).to_vec()).collect());
+                assert!(close(flatten(&a).dot(&flatten(&b)), dot));
+            }
+        }
+    }
+
+    #[test]
+    fn test_f64_kernels()
+    {
+        check_kernels(|rows, cols| Matrix::<f64>::random_uniform(rows, cols, -10.0, 10.0),
+                      |x, y| (x - y).abs() <= 1e-12 * (1.0 + y.abs()));
+    }
+
+    #[test]
+    fn test_f32_kernels()
+    {
+        check_kernels(|rows, cols| Matrix::<f32>::random_uniform(rows, cols, -10.0, 10.0),
+                      |x, y| (x - y).abs() <= 1e-4 * (1.0 + y.abs()));
+    }
+
+    #[test]
+    fn test_i32_kernels()
+    {
+        check_kernels(|rows, cols| Matrix::<i32>::random_uniform(rows, cols, -1000, 1000), |x, y| x == y);
+    }
+
+    #[test]
+    fn test_generic_kernels()
+    {
+        // Types without vectorized kernels use the plain loops
+        check_kernels(|rows, cols| Matrix::<i64>::random_uniform(rows, cols, -1000, 1000), |x, y| x == y);
+        check_kernels(|rows, cols| Matrix::<i64>::random_uniform(rows, cols, -1000, 1000).to_rational(),
+                      |x: Rational64, y| x == y);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic]
+    fn test_i32_overflow_panics_in_debug()
+    {
+        let _ = Matrix::from_vec(1, 9, vec![i32::MAX; 9]) + Matrix::ones(1, 9);
+    }
+
+    #[test]
+    fn test_float_special_values()
+    {
+        let a = Matrix::from_vec(1, 5, vec![f64::INFINITY, 1.0, f64::NAN, -0.0, 2.0]);
+        let b = Matrix::from_vec(1, 5, vec![1.0, f64::NEG_INFINITY, 1.0, 0.0, 3.0]);
+        let c = a.clone() + b.clone();
+        assert_eq!(c[(0, 0)], f64::INFINITY);
+        assert_eq!(c[(0, 1)], f64::NEG_INFINITY);
+        assert!(c[(0, 2)].is_nan());
+        assert!(a.dot(&b).is_nan());
+        assert!(b.sum().is_infinite());
+    }
+}
+
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+///////////////////////// Testing Strassen-Winograd matrix multiplication //////////////////////////
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+
+#[cfg(test)]
+mod strassen {
+    use MyMatrixLib::{strassen_threshold, with_strassen_threshold, Matrix, ModInt};
+    use num_rational::Rational64;
+    const MAX_SIZE: usize = 40;
+
+    #[test]
+    fn test_strassen_integer_exact()
+    {
+        for size in 1..=MAX_SIZE {
+            let a = Matrix::<i64>::random_uniform(size, size, -100, 100);
+            let b = Matrix::<i64>::random_uniform(size, size, -100, 100);
+            let expected = a.clone() * b.clone();
+            for threshold in [0, 1, 2, 3, 8, 16] {
+                assert_eq!(a.mul_strassen(&b, threshold), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_strassen_exact_types()
+    {
+        let a = Matrix::<i64>::random_uniform(13, 13, -10, 10);
+        let b = Matrix::<i64>::random_uniform(13, 13, -10, 10);
+        let (a_rational, b_rational) = (a.to_rational(), b.to_rational());
+        assert_eq!(a_rational.mul_strassen(&b_rational, 2), a_rational * b_rational);
+        let (a_mod, b_mod) = (a.map_mod::<1_000_000_007>(), b.map_mod::<1_000_000_007>());
+        assert_eq!(a_mod.mul_strassen(&b_mod, 2), a_mod * b_mod);
+
+        let half = Rational64::new(1, 2);
+        let c = Matrix::from_vec(3, 3, vec![half; 9]);
+        assert_eq!(c.mul_strassen(&c, 1), Matrix::from_vec(3, 3, vec![Rational64::new(3, 4); 9]));
+        let d = Matrix::from_vec(2, 2, vec![ModInt::<7>::new(3); 4]);
+        assert_eq!(d.mul_strassen(&d, 1), Matrix::from_vec(2, 2, vec![ModInt::new(4); 4]));
+    }
+
+    #[test]
+    fn test_strassen_float_error()
+    {
+        for size in [17, 64, 100] {
+            let a = Matrix::<f64>::random_uniform(size, size, -1.0, 1.0);
+            let b = Matrix::<f64>::random_uniform(size, size, -1.0, 1.0);
+            let expected = a.clone() * b.clone();
+            for threshold in [1, 4, 16] {
+                // Normwise bound c(n) u max|A| max|B| with a generous c(n)
+                let n0 = threshold as f64;
+                let bound = (size as f64 / n0).powf(18_f64.log2()) * (n0 * n0 + 6.0 * n0) * f64::EPSILON;
+                let difference = a.mul_strassen(&b, threshold) - expected.clone();
+                let error = difference.max().max(-difference.min());
+                assert!(error <= bound, "error {} above bound {} for n = {}, n0 = {}", error, bound, size, threshold);
+                assert!(error < 1e-10);
+            }
+        }
+    }
+
+    #[test]
+    fn test_strassen_threshold_in_mul()
+    {
+        assert_eq!(strassen_threshold(), None);
+        let a = Matrix::<i64>::random_uniform(150, 150, -100, 100);
+        let b = Matrix::<i64>::random_uniform(150, 150, -100, 100);
+        let expected = a.clone() * b.clone();
+
+        let (result, rectangular) = with_strassen_threshold(128, || {
+            assert_eq!(strassen_threshold(), Some(128));
+            assert_eq!(with_strassen_threshold(0, strassen_threshold), Some(1));
+            assert_eq!(strassen_threshold(), Some(128));
+            // The setting is local to the current thread
+            assert_eq!(std::thread::spawn(strassen_threshold).join().unwrap(), None);
+            (a.clone() * b.clone(), a.get_row(0) * b.clone())
+        });
+        assert_eq!(strassen_threshold(), None);
+        assert_eq!(result, expected);
+        assert_eq!(rectangular, expected.get_row(0));
+
+        let panicked = std::panic::catch_unwind(|| with_strassen_threshold(16, || panic!("restored on unwind")));
+        assert!(panicked.is_err());
+        assert_eq!(strassen_threshold(), None);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_strassen_rectangular_panics()
+    {
+        let a = Matrix::<i64>::ones(2, 3);
+        a.mul_strassen(&a.transpose(), 1);
+    }
+}
+
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+//////////////////// Testing cache-oblivious, buffered and in-place transposes /////////////////////
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+
+#[cfg(test)]
+mod transposes {
+    use MyMatrixLib::{matrix, Matrix};
+    const MAX_SIZE: usize = 40;
+
+    /// The transpose computed element by element.
+    fn naive_transpose(a: &Matrix<i64>, rows: usize, cols: usize) -> Matrix<i64> {
+        let mut result = Matrix::zeros(cols, rows);
+        for i in 0..rows {
+            for j in 0..cols {
+                result[(j, i)] = a[(i, j)];
+            }
+        }
+        result
+    }
+
+    #[test]
+    fn test_transpose_variants()
+    {
+        let mut target = Matrix::zeros(1, 1);
+        for rows in 1..=MAX_SIZE {
+            for cols in 1..=MAX_SIZE {
+                let a = Matrix::<i64>::random_uniform(rows, cols, -1000, 1000);
+                let expected = naive_transpose(&a, rows, cols);
+                assert_eq!(a.transpose(), expected);
+
+                a.transpose_into(&mut target);
+                assert_eq!(target, expected);
+
+                let mut b = a.clone();
+                b.transpose_in_place();
+                assert_eq!(b, expected);
+                b.transpose_in_place();
+                assert_eq!(b, a);
+            }
+        }
+    }
+
+    #[test]
+    fn test_transpose_large()
+    {
+        // Larger than the blocks and tiles, with sizes that do not divide evenly
+        for (rows, cols) in [(100, 100), (257, 3), (3, 257), (130, 77), (64, 1000)] {
+            let a = Matrix::<i64>::random_uniform(rows, cols, -1000, 1000);
+            let expected = naive_transpose(&a, rows, cols);
+            assert_eq!(a.transpose(), expected);
+            let mut b = a.clone();
+            b.transpose_in_place();
+            assert_eq!(b, expected);
+        }
+    }
+
+    #[test]
+    fn test_transpose_in_place_shapes()
+    {
+        let mut row = matrix![1, 2, 3];
+        row.transpose_in_place();
+        assert_eq!(row, matrix![1; 2; 3]);
+        row.transpose_in_place();
+        assert_eq!(row, matrix![1, 2, 3]);
+
+        let mut single = matrix![7.5];
+        single.transpose_in_place();
+        assert_eq!(single, matrix![7.5]);
+
+        let mut empty = Matrix::<f64>::zeros(0, 3);
+        empty.transpose_in_place();
+        assert_eq!(empty, Matrix::zeros(3, 0));
+    }
+
+    #[test]
+    fn test_transpose_into_reuses_target()
+    {
+        let a = matrix![1.0, 2.0; 3.0, 4.0; 5.0, 6.0];
+        let mut target = Matrix::zeros(10, 10);
+        a.transpose_into(&mut target);
+        assert_eq!(target, matrix![1.0, 3.0, 5.0; 2.0, 4.0, 6.0]);
+        a.transpose().transpose_into(&mut target);
+        assert_eq!(target, a);
+    }
+}
+
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+/////////////////// Testing the BLAS/LAPACK backend against the native routines ////////////////////
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+
+#[cfg(all(test, feature = "blas-reference"))]
+mod blas_backend {
+    use MyMatrixLib::{with_native, Matrix};
+    const MAX_SIZE: usize = 20;
+
+    // The reference routines accumulate in the same order as the Rust code, so the results agree
+    // exactly rather than up to rounding
+    macro_rules! cross_check {
+        ($name:ident, $t:ty) => {
+            #[test]
+            fn $name()
+            {
+                for n in 0..=MAX_SIZE {
+                    let a = Matrix::<$t>::random_uniform(n, n, -1.0, 1.0);
+                    let b = Matrix::<$t>::random_uniform(n, 3, -1.0, 1.0);
+                    let c = Matrix::<$t>::random_uniform(3, n, -1.0, 1.0);
+                    assert_eq!(a.clone() * b.clone(), with_native(|| a.clone() * b.clone()));
+                    assert_eq!(c.clone() * a.clone(), with_native(|| c.clone() * a.clone()));
+                    assert_eq!(b.clone() * c.clone(), with_native(|| b.clone() * c.clone()));
+                    assert_eq!(a.LUP_decompose(), with_native(|| a.LUP_decompose()));
+                    assert_eq!(a.solve(&b), with_native(|| a.solve(&b)));
+                    assert_eq!(a.inverse(), with_native(|| a.inverse()));
+                    assert_eq!(a.determinant(), with_native(|| a.determinant()));
+
+                    let spd = a.clone() * a.transpose() + Matrix::identity(n).scale(n as $t);
+                    assert_eq!(spd.cholesky(), with_native(|| spd.cholesky()));
+
+                    // QR and the eigen decomposition use different algorithms, so they only
+                    // agree up to rounding
+                    let tolerance = 1e3 * <$t>::EPSILON * (n as $t + 1.0);
+                    for m in [&a, &b, &c] {
+                        let ((q, r), (q_native, r_native)) = (m.QR_decompose(), with_native(|| m.QR_decompose()));
+                        assert!(q.approx_eq(&q_native, tolerance, tolerance));
+                        assert!(r.approx_eq(&r_native, tolerance, tolerance));
+                    }
+                    let symmetric = a.clone() + a.transpose();
+                    let (values, vectors) = symmetric.symmetric_eigen();
+                    let (native_values, _) = with_native(|| symmetric.symmetric_eigen());
+                    let diagonal = Matrix::from_diag(&Matrix::from_vec(1, n, values.clone()));
+                    assert!(Matrix::from_vec(1, n, values).approx_eq(&Matrix::from_vec(1, n, native_values), tolerance, tolerance));
+                    assert!((vectors.clone() * diagonal * vectors.transpose()).approx_eq(&symmetric, tolerance, tolerance));
+                    assert!((vectors.transpose() * vectors).approx_eq(&Matrix::identity(n), tolerance, tolerance));
+                }
+            }
+        };
+    }
+
+    cross_check!(test_blas_matches_native_f32, f32);
+    cross_check!(test_blas_matches_native_f64, f64);
+
+    #[test]
+    fn test_blas_known_results()
+    {
+        let a = Matrix::from_vec(2, 3, vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+        let b = Matrix::from_vec(3, 2, vec![7.0, 8.0, 9.0, 10.0, 11.0, 12.0]);
+        assert_eq!(a * b, Matrix::from_vec(2, 2, vec![58.0, 64.0, 139.0, 154.0]));
+
+        let a = Matrix::from_vec(3, 3, vec![0.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 10.0]);
+        let (l, u, p) = a.LUP_decompose();
+        assert_eq!(p.clone() * a.clone(), l * u);
+        assert_eq!(p, Matrix::from_vec(3, 3, vec![0.0, 0.0, 1.0, 1.0, 0.0, 0.0, 0.0, 1.0, 0.0]));
+
+        let a = Matrix::from_vec(2, 2, vec![4.0_f32, 2.0, 2.0, 3.0]);
+        assert_eq!(a.cholesky(), Matrix::from_vec(2, 2, vec![2.0, 0.0, 1.0, 2.0_f32.sqrt()]));
+
+        let a = Matrix::from_vec(2, 2, vec![3.0_f64, 4.0, 4.0, 3.0]);
+        let (q, r) = a.QR_decompose();
+        assert!(q.approx_eq(&Matrix::from_vec(2, 2, vec![-0.6, -0.8, -0.8, 0.6]), 1e-12, 1e-12));
+        assert!(r.approx_eq(&Matrix::from_vec(2, 2, vec![-5.0, -4.8, 0.0, -1.4]), 1e-12, 1e-12));
+        let (values, vectors) = a.symmetric_eigen();
+        assert!(Matrix::from_vec(1, 2, values).approx_eq(&Matrix::from_vec(1, 2, vec![7.0, -1.0]), 1e-12, 1e-12));
+        assert!((vectors[(0, 0)] - vectors[(1, 0)]).abs() < 1e-12);
+        assert!((vectors[(0, 1)] + vectors[(1, 1)]).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_with_native_restores_after_panic()
+    {
+        let a = Matrix::<f64>::random_uniform(5, 5, -1.0, 1.0);
+        let result = std::panic::catch_unwind(|| with_native(|| panic!("inside with_native")));
+        assert!(result.is_err());
+        // Nested calls keep the outer setting
+        let nested = with_native(|| {
+            with_native(|| ());
+            a.clone() * a.clone()
+        });
+        assert_eq!(a.clone() * a, nested);
+    }
+
+    #[test]
+    #[should_panic(expected = "matrix is singular")]
+    fn test_blas_solve_singular_panics()
+    {
+        let a = Matrix::from_vec(2, 2, vec![1.0, 2.0, 2.0, 4.0]);
+        a.solve(&Matrix::ones(2, 1));
+    }
+
+    #[test]
+    #[should_panic(expected = "not positive definite")]
+    fn test_blas_cholesky_not_positive_definite_panics()
+    {
+        let a = Matrix::from_vec(2, 2, vec![1.0_f32, 2.0, 2.0, 1.0]);
+        a.cholesky();
+    }
+}
+
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+//////////////////// Testing row- and column-major layouts and strided buffers /////////////////////
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+
+#[cfg(test)]
+mod layouts {
+    use MyMatrixLib::{matrix, Layout, Matrix};
+    const MAX_SIZE: usize = 8;
+
+    fn column_major(a: &Matrix<i64>) -> Matrix<i64> {
+        a.to_layout(Layout::ColumnMajor)
+    }
+
+    #[test]
+    fn test_layout_storage()
+    {
+        let a = Matrix::from_vec_with_layout(2, 3, vec![1, 4, 2, 5, 3, 6], Layout::ColumnMajor);
+        let b = matrix![1, 2, 3; 4, 5, 6];
+        assert_eq!(a, b);
+        assert_eq!((a.layout(), b.layout()), (Layout::ColumnMajor, Layout::RowMajor));
+        assert_eq!((a.strides(), b.strides()), ((1, 2), (3, 1)));
+        assert_eq!(a[(1, 0)], 4);
+        assert_eq!(a.as_slice(), &[1, 4, 2, 5, 3, 6]);
+        assert_eq!(b.clone().into_layout(Layout::ColumnMajor).into_vec(), vec![1, 4, 2, 5, 3, 6]);
+        assert_ne!(a, matrix![1, 2, 3; 4, 5, 7]);
+        assert_eq!(Layout::default(), Layout::RowMajor);
+        assert_eq!(Layout::RowMajor.transposed(), Layout::ColumnMajor);
+
+        let mut c = a.clone();
+        c[(0, 2)] = 9;
+        assert_eq!(c.as_slice(), &[1, 4, 2, 5, 9, 6]);
+    }
+
+    #[test]
+    fn test_layout_conversions()
+    {
+        for rows in 0..=MAX_SIZE {
+            for cols in 0..=MAX_SIZE {
+                let a = Matrix::<i64>::random_uniform(rows, cols, -100, 100);
+                let b = column_major(&a);
+                assert_eq!(b, a);
+                assert_eq!(b.clone().into_layout(Layout::RowMajor).as_slice(), a.as_slice());
+                assert_eq!(a.clone().into_layout(Layout::ColumnMajor).as_slice(), b.as_slice());
+                for i in 0..rows {
+                    for j in 0..cols {
+                        assert_eq!(b.as_slice()[j * rows + i], a[(i, j)]);
+                    }
+                }
+
+                // The transpose moves no elements, and keeps the layout otherwise
+                let t = a.clone().into_transposed();
+                assert_eq!((t.layout(), t.as_slice()), (Layout::ColumnMajor, a.as_slice()));
+                assert_eq!(t, a.transpose());
+                assert_eq!(b.clone().into_transposed().layout(), Layout::RowMajor);
+                assert_eq!(b.transpose().layout(), Layout::ColumnMajor);
+                assert_eq!(b.transpose(), a.transpose());
+                let mut target = Matrix::zeros(1, 1);
+                b.transpose_into(&mut target);
+                assert_eq!((target.layout(), &target), (Layout::ColumnMajor, &a.transpose()));
+                let mut c = b.clone();
+                c.transpose_in_place();
+                assert_eq!((c.layout(), &c), (Layout::ColumnMajor, &a.transpose()));
+            }
+        }
+    }
+
+    #[test]
+    fn test_layout_strided_slice()
+    {
+        let buffer: Vec<i64> = (1..=12).collect();
+        // Rows 1..3 and columns 1..3 of a row-major 3 x 4 buffer
+        assert_eq!(Matrix::from_strided_slice(2, 2, &buffer[5..], 4, 1), matrix![6, 7; 10, 11]);
+        // A column-major 3 x 4 buffer, and its transpose
+        let a = Matrix::from_strided_slice(3, 4, &buffer, 1, 3);
+        assert_eq!(a, Matrix::from_vec_with_layout(3, 4, buffer.clone(), Layout::ColumnMajor));
+        assert_eq!(Matrix::from_strided_slice(4, 3, &buffer, 3, 1), a.transpose());
+        // Zero strides repeat elements
+        assert_eq!(Matrix::from_strided_slice(2, 3, &buffer, 0, 0), Matrix::from_vec(2, 3, vec![1; 6]));
+        assert_eq!(Matrix::<i64>::from_strided_slice(0, 3, &[], 5, 5), Matrix::zeros(0, 3));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_layout_strided_slice_too_short()
+    {
+        let buffer = [1, 2, 3, 4, 5];
+        Matrix::from_strided_slice(2, 2, &buffer, 4, 1);
+    }
+
+    #[test]
+    fn test_layout_rows_and_columns()
+    {
+        let a = matrix![1, 2, 3; 4, 5, 6];
+        let b = column_major(&a);
+        assert_eq!(b.view_col(1), &[2, 5]);
+        assert_eq!(a.view_row(1), &[4, 5, 6]);
+        assert_eq!(b.get_row(1), a.get_row(1));
+        assert_eq!(b.get_col(2), a.get_col(2));
+        assert_eq!(b.row_vector(0), a.row_vector(0));
+        assert_eq!(b.col_vector(0), a.col_vector(0));
+        for axis in 0..2 {
+            assert_eq!(b.sum_axis(axis), a.sum_axis(axis));
+            assert_eq!(b.max_axis(axis), a.max_axis(axis));
+            assert_eq!(b.arg_min_axis(axis), a.arg_min_axis(axis));
+        }
+        assert_eq!(b.arg_max(), (1, 2));
+        assert_eq!(column_major(&matrix![1, 9; 9, 1]).arg_max(), (0, 1));
+        // A single row or column is contiguous in both layouts
+        assert_eq!(column_major(&a.get_row(0)).view_row(0), &[1, 2, 3]);
+        assert_eq!(a.get_col(0).view_col(0), &[1, 4]);
+    }
+
+    #[test]
+    #[should_panic(expected = "not contiguous")]
+    fn test_layout_view_row_of_column_major_panics()
+    {
+        column_major(&matrix![1, 2; 3, 4]).view_row(0);
+    }
+
+    #[test]
+    #[should_panic(expected = "not contiguous")]
+    fn test_layout_view_col_of_row_major_panics()
+    {
+        matrix![1, 2; 3, 4].view_col(0);
+    }
+
+    #[test]
+    fn test_layout_arithmetic()
+    {
+        for n in [1, 3, 7, 12] {
+            let a = Matrix::<i64>::random_uniform(n, n + 1, -100, 100);
+            let b = Matrix::<i64>::random_uniform(n + 1, n, -100, 100);
+            let c = Matrix::<i64>::random_uniform(n, n + 1, -100, 100);
+            let (a_col, b_col, c_col) = (column_major(&a), column_major(&b), column_major(&c));
+            let product = a.clone() * b.clone();
+            assert_eq!(a_col.clone() * b.clone(), product);
+            assert_eq!(a.clone() * b_col.clone(), product);
+            assert_eq!(a_col.clone() * b_col.clone(), product);
+            assert_eq!(a_col.clone() + c.clone(), a.clone() + c.clone());
+            assert_eq!((a.clone() - c_col.clone()).layout(), Layout::RowMajor);
+            assert_eq!((a_col.clone() - c.clone()).layout(), Layout::ColumnMajor);
+            assert_eq!(a_col.clone() - c.clone(), a.clone() - c.clone());
+            assert_eq!(a_col.scale(3), a.scale(3));
+            assert_eq!(a_col.sum(), a.sum());
+            let square = column_major(&(a.clone() * b.clone()));
+            assert_eq!(square.mul_strassen(&square, 2), product.clone() * product.clone());
+            let small = Matrix::<i64>::random_uniform(n, n, -5, 5);
+            assert_eq!(column_major(&small).determinant_bareiss(), small.determinant_bareiss());
+        }
+    }
+
+    #[test]
+    fn test_layout_float_operations()
+    {
+        for n in [1, 4, 9] {
+            let a = Matrix::<f64>::random_uniform(n, n, -1.0, 1.0) + Matrix::identity(n).scale(n as f64);
+            let b = Matrix::<f64>::random_uniform(n, 2, -1.0, 1.0);
+            let (a_col, b_col) = (a.to_layout(Layout::ColumnMajor), b.to_layout(Layout::ColumnMajor));
+            assert_eq!(a_col.clone() * b_col.clone(), a.clone() * b.clone());
+            assert_eq!(b_col.transpose() * a_col.clone(), b.transpose() * a.clone());
+            assert_eq!(a_col.solve(&b_col), a.solve(&b));
+            assert_eq!(a_col.determinant(), a.determinant());
+            assert_eq!(a_col.LUP_decompose(), a.LUP_decompose());
+            assert_eq!(a_col.QR_decompose(), a.QR_decompose());
+            let spd = a.clone() * a.transpose();
+            assert_eq!(spd.to_layout(Layout::ColumnMajor).cholesky(), spd.cholesky());
+            assert!(a_col.approx_eq(&a, 0.0, 0.0));
+        }
+    }
+
+    #[test]
+    fn test_layout_io()
+    {
+        let a = matrix![1.0, 2.0, 3.0; 4.0, 5.0, 6.0];
+        let b = a.to_layout(Layout::ColumnMajor);
+
+        // .npy files store the buffer as is, with the matching order
+        let mut bytes = Vec::new();
+        b.write_npy(&mut bytes).unwrap();
+        assert!(String::from_utf8_lossy(&bytes).contains("'fortran_order': True"));
+        let loaded = Matrix::<f64>::read_npy(&bytes[..]).unwrap();
+        assert_eq!((loaded.layout(), &loaded), (Layout::ColumnMajor, &a));
+
+        // The other formats do not depend on the layout
+        let (mut a_bytes, mut b_bytes) = (Vec::new(), Vec::new());
+        a.write_binary(&mut a_bytes).unwrap();
+        b.write_binary(&mut b_bytes).unwrap();
+        assert_eq!(a_bytes, b_bytes);
+        assert_eq!(serde_json::to_string(&b).unwrap(), serde_json::to_string(&a).unwrap());
+        assert_eq!(b.to_string(), a.to_string());
+    }
+}
+
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+////////////////////////// Testing reverse-mode automatic differentiation //////////////////////////
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+
+#[cfg(test)]
+mod autodiff {
+    use MyMatrixLib::{Matrix, Tape, Var};
+
+    /// Compares the gradients of `f` with respect to each input with central differences.
+    fn check_gradients<F>(inputs: &[(usize, usize, Matrix<f64>)], f: F)
+        where
+            F: for<'t> Fn(&[Var<'t, f64>]) -> Var<'t, f64>
+    {
+        let evaluate = |values: &[Matrix<f64>]| {
+            let tape = Tape::new();
+            let vars: Vec<_> = values.iter().map(|value| tape.var(value.clone())).collect();
+            f(&vars).value()[(0, 0)]
+        };
+
+        let tape = Tape::new();
+        let vars: Vec<_> = inputs.iter().map(|(_, _, value)| tape.var(value.clone())).collect();
+        let gradients = f(&vars).backward();
+
+        let h = 1e-6;
+        let values: Vec<_> = inputs.iter().map(|(_, _, value)| value.clone()).collect();
+        for (k, (rows, cols, value)) in inputs.iter().enumerate() {
+            let mut numeric = Matrix::zeros(*rows, *cols);
+            for i in 0..*rows {
+                for j in 0..*cols {
+                    let (mut plus, mut minus) = (values.clone(), values.clone());
+                    plus[k][(i, j)] = value[(i, j)] + h;
+                    minus[k][(i, j)] = value[(i, j)] - h;
+                    numeric[(i, j)] = (evaluate(&plus) - evaluate(&minus)) / (2.0 * h);
+                }
+            }
+            let analytic = gradients.wrt(vars[k]);
+            assert!(analytic.approx_eq(&numeric, 1e-6, 1e-6), "input {}: {} vs {}", k, analytic, numeric);
+        }
+    }
+
+    fn random(rows: usize, cols: usize) -> (usize, usize, Matrix<f64>) {
+        (rows, cols, Matrix::random_uniform(rows, cols, -1.0, 1.0))
+    }
+
+    #[test]
+    fn test_autodiff_linear_operations()
+    {
+        check_gradients(&[random(3, 4), random(3, 4), random(3, 4)], |v| {
+            ((v[0] - v[1] + v[0]).scale(2.0) - -v[2]).hadamard(v[2]).sum()
+        });
+        check_gradients(&[random(2, 3), random(3, 4), random(4, 2)], |v| (v[0] * v[1] * v[2]).sum());
+        check_gradients(&[random(3, 2), random(4, 2)], |v| (v[0] * v[1].transpose()).tanh().sum());
+        check_gradients(&[random(5, 3), random(1, 3)], |v| v[0].add_row(v[1]).sigmoid().mean());
+    }
+
+    #[test]
+    fn test_autodiff_element_wise_maps()
+    {
+        check_gradients(&[random(3, 3)], |v| v[0].exp().sum());
+        check_gradients(&[(2, 3, Matrix::random_uniform(2, 3, 0.5, 2.0))], |v| v[0].ln().sum());
+        check_gradients(&[random(4, 2)], |v| v[0].tanh().hadamard(v[0].sigmoid()).sum());
+        check_gradients(&[random(4, 4)], |v| v[0].relu().hadamard(v[0]).sum());
+        check_gradients(&[random(2, 2)], |v| v[0].map(|x| x.sin(), |x| x.cos()).sum());
+        check_gradients(&[random(3, 4), random(3, 4)], |v| v[0].softmax().hadamard(v[1]).sum());
+    }
+
+    #[test]
+    fn test_autodiff_reductions()
+    {
+        check_gradients(&[random(3, 4), random(1, 4)], |v| v[0].sum_axis(0).hadamard(v[1]).sum());
+        check_gradients(&[random(3, 4), random(3, 1)], |v| v[0].sum_axis(1).hadamard(v[1]).sum());
+        check_gradients(&[random(3, 4)], |v| (v[0].mean().exp() + v[0].sum()).sum());
+        // A small network with a squared error loss
+        check_gradients(&[random(4, 2), random(2, 3), random(1, 3), random(3, 1), random(4, 1)], |v| {
+            let hidden = (v[0] * v[1]).add_row(v[2]).tanh();
+            let error = hidden * v[3] - v[4];
+            error.hadamard(error).mean()
+        });
+    }
+
+    #[test]
+    fn test_autodiff_known_gradients()
+    {
+        let tape = Tape::new();
+        let x = tape.var(Matrix::from_vec(2, 2, vec![1.0, 2.0, 3.0, 4.0]));
+        let w = tape.var(Matrix::from_vec(2, 1, vec![0.5, -1.0]));
+        let unused = tape.var(Matrix::ones(2, 3));
+        let f = (x * w).sum();
+        assert_eq!(f.value(), Matrix::from_vec(1, 1, vec![-4.0]));
+        let gradients = f.backward();
+        // d sum(X W) / dW = X^T 1 and d / dX = 1 W^T
+        assert_eq!(gradients.wrt(w), &Matrix::from_vec(2, 1, vec![4.0, 6.0]));
+        assert_eq!(gradients.wrt(x), &Matrix::from_vec(2, 2, vec![0.5, -1.0, 0.5, -1.0]));
+        assert_eq!(gradients.wrt(unused), &Matrix::zeros(2, 3));
+        assert_eq!(gradients.wrt(f), &Matrix::ones(1, 1));
+
+        // A variable used twice accumulates both contributions, and backward can be repeated
+        let g = (x + x).hadamard(x).sum();
+        assert_eq!(g.backward().wrt(x), &x.value().scale(4.0));
+        assert_eq!(g.backward().wrt(x), &x.value().scale(4.0));
+
+        // backward_with propagates any output gradient
+        let y = x * w;
+        let seed = Matrix::from_vec(2, 1, vec![1.0, 0.0]);
+        assert_eq!(y.backward_with(seed).wrt(w), &Matrix::from_vec(2, 1, vec![1.0, 2.0]));
+
+        let tape = Tape::<f32>::new();
+        let a = tape.var(Matrix::from_vec(1, 2, vec![1.0, -2.0]));
+        assert_eq!(a.relu().sum().backward().wrt(a), &Matrix::from_vec(1, 2, vec![1.0, 0.0]));
+    }
+
+    #[test]
+    #[should_panic(expected = "1 x 1")]
+    fn test_autodiff_backward_requires_scalar()
+    {
+        let tape = Tape::new();
+        tape.var(Matrix::<f64>::ones(2, 2)).exp().backward();
+    }
+
+    #[test]
+    #[should_panic(expected = "same tape")]
+    fn test_autodiff_different_tapes_panic()
+    {
+        let (first, second) = (Tape::new(), Tape::new());
+        let _ = first.var(Matrix::<f64>::ones(2, 2)) + second.var(Matrix::ones(2, 2));
+    }
+}
+
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+//////////////////////////// Testing neural network layers and training ////////////////////////////
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+
+#[cfg(test)]
+mod nn {
+    use MyMatrixLib::nn::{one_hot, Activation, Adam, Dense, Initialization, Loss, Mlp, Optimizer, Sgd};
+    use MyMatrixLib::{Layout, Matrix, Tape};
+    use rand::SeedableRng;
+
+    const SEED: u64 = 42;
+
+    fn xor() -> (Matrix<f64>, Matrix<f64>) {
+        (Matrix::from_vec(4, 2, vec![0.0, 0.0, 0.0, 1.0, 1.0, 0.0, 1.0, 1.0]),
+         Matrix::from_vec(4, 1, vec![0.0, 1.0, 1.0, 0.0]))
+    }
+
+    fn layer(inputs: usize, outputs: usize, activation: Activation, rng: &mut rand::rngs::StdRng) -> Dense<f64> {
+        Dense::with_rng(inputs, outputs, activation, Initialization::Xavier, rng)
+    }
+
+    #[test]
+    fn test_nn_xor_with_adam()
+    {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(SEED);
+        let (inputs, targets) = xor();
+        let mut mlp = Mlp::new(vec![layer(2, 8, Activation::Tanh, &mut rng), layer(8, 1, Activation::Sigmoid, &mut rng)]);
+        let losses = mlp.fit(&inputs, &targets, Loss::MeanSquaredError, &mut Adam::new(0.05), 1000, 4);
+        assert_eq!(losses.len(), 1000);
+        assert!(losses[999] < 0.01 && losses[999] < losses[0], "loss {} after {}", losses[999], losses[0]);
+        let predictions = mlp.predict(&inputs);
+        for i in 0..4 {
+            assert!((predictions[(i, 0)] - targets[(i, 0)]).abs() < 0.2, "{}", predictions);
+        }
+    }
+
+    #[test]
+    fn test_nn_softmax_classifier_from_csv()
+    {
+        // XOR as two classes, with the label in the first column as in MNIST CSV files
+        let csv = "label,x,y\n0,0,0\n1,0,1\n1,1,0\n0,1,1\n";
+        let data = Matrix::<f64>::from_csv(csv.as_bytes(), ',', true).unwrap();
+        let labels = data.get_col(0);
+        let inputs = Matrix::from_vec(4, 2, (0..4).flat_map(|i| [data[(i, 1)], data[(i, 2)]]).collect());
+        let targets = one_hot(&labels, 2);
+
+        let mut rng = rand::rngs::StdRng::seed_from_u64(SEED);
+        let mut mlp = Mlp::new(vec![
+            Dense::with_rng(2, 8, Activation::Relu, Initialization::He, &mut rng),
+            layer(8, 2, Activation::Softmax, &mut rng),
+        ]);
+        let mut optimizer = Sgd::with_momentum(0.1, 0.9);
+        let losses = mlp.fit(&inputs, &targets, Loss::CrossEntropy, &mut optimizer, 500, 2);
+        assert!(losses[499] < 0.05, "loss {}", losses[499]);
+
+        let probabilities = mlp.predict(&inputs);
+        assert!(probabilities.sum_axis(1).approx_eq(&Matrix::ones(4, 1), 1e-12, 1e-12));
+        assert_eq!(probabilities.arg_max_axis(1), Matrix::from_vec(4, 1, vec![0, 1, 1, 0]));
+    }
+
+    #[test]
+    fn test_nn_cross_entropy_and_dense_gradients()
+    {
+        let tape = Tape::new();
+        let predictions = tape.var(Matrix::from_vec(2, 2, vec![0.25, 0.75, 0.5, 0.5]));
+        let targets = tape.var(Matrix::from_vec(2, 2, vec![0.0, 1.0, 1.0, 0.0]));
+        let loss = Loss::CrossEntropy.apply(predictions, targets);
+        let expected = -(0.75f64.ln() + 0.5f64.ln()) / 2.0;
+        assert!((loss.value()[(0, 0)] - expected).abs() < 1e-12);
+        let gradient = loss.backward().wrt(predictions).clone();
+        assert!(gradient.approx_eq(&Matrix::from_vec(2, 2, vec![0.0, -1.0 / 1.5, -1.0, 0.0]), 1e-12, 1e-12));
+
+        // Against a manual computation of X W + b
+        let dense = Dense {
+            weights: Matrix::from_vec(2, 1, vec![1.0, -1.0]),
+            bias: Matrix::from_vec(1, 1, vec![0.5]),
+            activation: Activation::Identity,
+        };
+        let tape = Tape::new();
+        let x = tape.var(Matrix::from_vec(2, 2, vec![1.0, 2.0, 3.0, 5.0]));
+        let (output, [weights, bias]) = dense.forward(&tape, x);
+        assert_eq!(output.value(), Matrix::from_vec(2, 1, vec![-0.5, -1.5]));
+        let gradients = output.sum().backward();
+        assert_eq!(gradients.wrt(weights), &Matrix::from_vec(2, 1, vec![4.0, 7.0]));
+        assert_eq!(gradients.wrt(bias), &Matrix::from_vec(1, 1, vec![2.0]));
+    }
+
+    #[test]
+    fn test_nn_optimizer_steps()
+    {
+        let mut parameter = Matrix::from_vec(1, 2, vec![1.0, -1.0]);
+        let gradient = Matrix::from_vec(1, 2, vec![0.5, -2.0]);
+
+        let mut sgd = Sgd::with_momentum(0.1, 0.5);
+        sgd.step(vec![&mut parameter], std::slice::from_ref(&gradient));
+        assert!(parameter.approx_eq(&Matrix::from_vec(1, 2, vec![0.95, -0.8]), 1e-12, 1e-12));
+        // The velocity carries half of the previous step
+        sgd.step(vec![&mut parameter], std::slice::from_ref(&gradient));
+        assert!(parameter.approx_eq(&Matrix::from_vec(1, 2, vec![0.875, -0.5]), 1e-12, 1e-12));
+
+        // The first Adam step moves each parameter by the learning rate against its gradient
+        let mut parameter = Matrix::from_vec(1, 2, vec![1.0, -1.0]);
+        Adam::new(0.01).step(vec![&mut parameter], &[gradient]);
+        assert!(parameter.approx_eq(&Matrix::from_vec(1, 2, vec![0.99, -0.99]), 1e-9, 1e-9));
+    }
+
+    #[test]
+    fn test_nn_adam_column_major_parameters()
+    {
+        let mut row_major = Matrix::from_vec(2, 2, vec![1.0, 2.0, 3.0, 4.0]);
+        let mut column_major = row_major.to_layout(Layout::ColumnMajor);
+        let gradients = [Matrix::from_vec(2, 2, vec![1.0, -1.0, 1.0, -1.0]), Matrix::from_vec(2, 2, vec![0.5, 2.0, -3.0, 0.1])];
+        let (mut adam_row, mut adam_column) = (Adam::new(0.01), Adam::new(0.01));
+        for gradient in &gradients {
+            adam_row.step(vec![&mut row_major], std::slice::from_ref(gradient));
+            adam_column.step(vec![&mut column_major], &[gradient.to_layout(Layout::ColumnMajor)]);
+            assert_eq!(column_major.layout(), Layout::ColumnMajor);
+            assert!(column_major.approx_eq(&row_major, 1e-12, 1e-12));
+        }
+        // The first step moved each element by the learning rate against its own gradient
+        let mut parameter = Matrix::from_vec(2, 2, vec![1.0, 2.0, 3.0, 4.0]).to_layout(Layout::ColumnMajor);
+        Adam::new(0.01).step(vec![&mut parameter], std::slice::from_ref(&gradients[0]));
+        assert!(parameter.approx_eq(&Matrix::from_vec(2, 2, vec![0.99, 2.01, 2.99, 4.01]), 1e-9, 1e-9));
+    }
+
+    #[test]
+    fn test_nn_initialization_and_one_hot()
+    {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(SEED);
+        let xavier = Dense::<f64>::with_rng(20, 40, Activation::Tanh, Initialization::Xavier, &mut rng);
+        let he = Dense::<f64>::with_rng(20, 40, Activation::Relu, Initialization::He, &mut rng);
+        assert_eq!((xavier.weights.layout(), xavier.bias), (MyMatrixLib::Layout::RowMajor, Matrix::zeros(1, 40)));
+        assert!(xavier.weights.as_slice().iter().all(|w| w.abs() <= 0.1f64.sqrt()));
+        assert!(he.weights.as_slice().iter().all(|w| w.abs() <= 0.3f64.sqrt()));
+        assert!(he.weights.as_slice().iter().any(|w| w.abs() > 0.1f64.sqrt()));
+
+        let labels = Matrix::from_vec(3, 1, vec![2.0, 0.0, 1.0]);
+        assert_eq!(one_hot(&labels, 3), Matrix::from_vec(3, 3, vec![0.0, 0.0, 1.0, 1.0, 0.0, 0.0, 0.0, 1.0, 0.0]));
+    }
+
+    #[test]
+    #[should_panic(expected = "not a class index")]
+    fn test_nn_one_hot_invalid_label()
+    {
+        one_hot(&Matrix::from_vec(2, 1, vec![0.0, 1.5]), 3);
+    }
+
+    #[test]
+    #[should_panic(expected = "Layer 0 has 3 outputs, but layer 1 has 4 inputs")]
+    fn test_nn_mismatched_layers()
+    {
+        Mlp::new(vec![Dense::<f64>::new(2, 3, Activation::Relu), Dense::new(4, 1, Activation::Sigmoid)]);
+    }
+}
+
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+/////////////////////////////// Testing linear and ridge regression ////////////////////////////////
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+
+#[cfg(test)]
+mod regression {
+    use MyMatrixLib::regression::{ols, ridge, wls, with_intercept};
+    use MyMatrixLib::Matrix;
+
+    fn close(a: f64, b: f64) -> bool {
+        (a - b).abs() <= 1e-9 * (1.0 + b.abs())
+    }
+
+    #[test]
+    fn test_regression_simple_ols_matches_closed_form()
+    {
+        let xs = [1.0, 2.0, 3.0, 4.0, 5.0, 6.0];
+        let ys = [2.9, 5.1, 7.2, 8.7, 11.3, 12.8];
+        let fit = ols(&with_intercept(&Matrix::from_vec(6, 1, xs.to_vec())), &Matrix::from_vec(6, 1, ys.to_vec()));
+
+        // slope = Sxy / Sxx, intercept = mean(y) - slope * mean(x), se(slope) = s / sqrt(Sxx)
+        let (mean_x, mean_y) = (xs.iter().sum::<f64>() / 6.0, ys.iter().sum::<f64>() / 6.0);
+        let sxx: f64 = xs.iter().map(|x| (x - mean_x).powi(2)).sum();
+        let sxy: f64 = xs.iter().zip(&ys).map(|(x, y)| (x - mean_x) * (y - mean_y)).sum();
+        let syy: f64 = ys.iter().map(|y| (y - mean_y).powi(2)).sum();
+        let slope = sxy / sxx;
+        let intercept = mean_y - slope * mean_x;
+        let rss: f64 = xs.iter().zip(&ys).map(|(x, y)| (y - intercept - slope * x).powi(2)).sum();
+        let s = (rss / 4.0).sqrt();
+
+        assert!(close(fit.coefficients[(0, 0)], intercept) && close(fit.coefficients[(1, 0)], slope), "{}", fit.coefficients);
+        assert!(close(fit.standard_errors[(1, 0)], s / sxx.sqrt()));
+        assert!(close(fit.standard_errors[(0, 0)], s * (1.0 / 6.0 + mean_x * mean_x / sxx).sqrt()));
+        assert!(close(fit.r_squared, 1.0 - rss / syy));
+        for i in 0..6 {
+            assert!(close(fit.residuals[(i, 0)], ys[i] - intercept - slope * xs[i]));
+        }
+        let predictions = fit.predict(&with_intercept(&Matrix::from_vec(2, 1, vec![0.0, 10.0])));
+        assert!(close(predictions[(0, 0)], intercept) && close(predictions[(1, 0)], intercept + 10.0 * slope));
+    }
+
+    #[test]
+    fn test_regression_exact_fit_and_residual_orthogonality()
+    {
+        let x = Matrix::random_uniform(30, 4, -1.0, 1.0);
+        let beta = Matrix::from_vec(4, 1, vec![1.0, -2.0, 0.5, 3.0]);
+        let exact = ols(&x, &(x.clone() * beta.clone()));
+        assert!(exact.coefficients.approx_eq(&beta, 1e-9, 1e-9));
+        assert!(exact.standard_errors.as_slice().iter().all(|&se| se < 1e-6));
+
+        // The residuals of a noisy fit are orthogonal to every regressor
+        let y = x.clone() * beta + Matrix::random_uniform(30, 1, -0.1, 0.1);
+        let fit = ols(&x, &y);
+        assert!((x.transpose() * fit.residuals).approx_eq(&Matrix::zeros(4, 1), 1e-9, 1e-9));
+    }
+
+    #[test]
+    fn test_regression_ridge()
+    {
+        // Without intercept and with one regressor, b = x^T y / (x^T x + lambda)
+        let x = Matrix::from_vec(4, 1, vec![1.0, 2.0, 3.0, 4.0]);
+        let y = Matrix::from_vec(4, 1, vec![1.5, 1.9, 3.2, 4.1]);
+        let fit = ridge(&x, &y, 2.0);
+        assert!(close(fit.coefficients[(0, 0)], 31.3 / 32.0));
+        assert_eq!(ridge(&x, &y, 0.0).coefficients, ols(&x, &y).coefficients);
+
+        // The penalty shrinks the coefficients and makes collinear designs solvable
+        let collinear = Matrix::from_vec(3, 2, vec![1.0, 2.0, 2.0, 4.0, 3.0, 6.0]);
+        let y = Matrix::from_vec(3, 1, vec![1.0, 2.0, 3.0]);
+        let (weak, strong) = (ridge(&collinear, &y, 0.1), ridge(&collinear, &y, 10.0));
+        let norm = |b: &Matrix<f64>| b.as_slice().iter().map(|v| v * v).sum::<f64>();
+        assert!(norm(&strong.coefficients) < norm(&weak.coefficients));
+        assert!(close(weak.coefficients[(1, 0)], 2.0 * weak.coefficients[(0, 0)]));
+        assert!(weak.standard_errors.as_slice().iter().all(|se| se.is_finite()));
+    }
+
+    #[test]
+    fn test_regression_weighted()
+    {
+        let x = with_intercept(&Matrix::from_vec(5, 1, vec![0.0, 1.0, 2.0, 3.0, 4.0]));
+        let y = Matrix::from_vec(5, 1, vec![1.0, 2.5, 2.9, 4.2, 20.0]);
+
+        // A weight of two counts an observation twice, and a weight of zero drops it
+        let weighted = wls(&x, &y, &Matrix::from_vec(5, 1, vec![1.0, 2.0, 1.0, 1.0, 0.0]));
+        let duplicated = ols(&with_intercept(&Matrix::from_vec(5, 1, vec![0.0, 1.0, 1.0, 2.0, 3.0])),
+                             &Matrix::from_vec(5, 1, vec![1.0, 2.5, 2.5, 2.9, 4.2]));
+        assert!(weighted.coefficients.approx_eq(&duplicated.coefficients, 1e-9, 1e-9));
+        assert!(close(weighted.r_squared, duplicated.r_squared));
+        // Residuals are not weighted, so the dropped outlier keeps its large residual
+        assert!(weighted.residuals[(4, 0)] > 10.0);
+
+        let uniform = wls(&x, &y, &Matrix::ones(1, 5));
+        let plain = ols(&x, &y);
+        assert!(uniform.coefficients.approx_eq(&plain.coefficients, 1e-12, 1e-12));
+        assert!(uniform.standard_errors.approx_eq(&plain.standard_errors, 1e-12, 1e-12));
+    }
+
+    #[test]
+    #[should_panic(expected = "linearly dependent")]
+    fn test_regression_collinear_ols_panics()
+    {
+        let x = Matrix::from_vec(3, 2, vec![1.0, 2.0, 2.0, 4.0, 3.0, 6.0]);
+        ols(&x, &Matrix::from_vec(3, 1, vec![1.0, 2.0, 3.0]));
+    }
+
+    #[test]
+    #[should_panic(expected = "linearly dependent")]
+    fn test_regression_nearly_collinear_ols_panics()
+    {
+        // The Gram matrix is still numerically positive definite, so only the rank check fails
+        let x = Matrix::from_vec(4, 2, vec![1.0, 3.0 + 1e-9, 2.0, 6.0, 3.0, 9.0 - 1e-9, 4.0, 12.0]);
+        ols(&with_intercept(&x), &Matrix::from_vec(4, 1, vec![1.0, 2.0, 3.0, 5.0]));
+    }
+
+    #[test]
+    fn test_regression_rank_check()
+    {
+        // Moderately collinear regressors still fit
+        let x = Matrix::from_vec(4, 2, vec![1.0, 3.001, 2.0, 6.0, 3.0, 8.999, 4.0, 12.0]);
+        let coefficients = Matrix::from_vec(2, 1, vec![2.0, -1.0]);
+        let fit = ols(&x, &(x.clone() * coefficients.clone()));
+        assert!(fit.coefficients.approx_eq(&coefficients, 1e-6, 1e-6));
+
+        // Observations with zero weight do not count, and a penalty makes any design well posed
+        let x = Matrix::from_vec(3, 2, vec![1.0, 2.0, 2.0, 4.0, 1.0, 0.0]);
+        let y = Matrix::from_vec(3, 1, vec![1.0, 2.0, 3.0]);
+        let dependent = std::panic::catch_unwind(|| wls(&x, &y, &Matrix::from_vec(3, 1, vec![1.0, 1.0, 0.0])));
+        assert!(dependent.is_err());
+        assert!(close(wls(&x, &y, &Matrix::from_vec(3, 1, vec![1.0, 0.0, 1.0])).r_squared, 1.0));
+        assert!(ridge(&x, &y, 0.1).coefficients.as_slice().iter().all(|c| c.is_finite()));
+        assert!(ridge(&Matrix::zeros(3, 2), &y, 0.1).coefficients.as_slice().iter().all(|&c| c == 0.0));
+    }
+
+    #[test]
+    #[should_panic(expected = "Weights must be non-negative")]
+    fn test_regression_negative_weight_panics()
+    {
+        let x = Matrix::from_vec(2, 1, vec![1.0, 2.0]);
+        wls(&x, &x, &Matrix::from_vec(2, 1, vec![1.0, -1.0]));
+    }
+}
+
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+///////////////////////////////////// Testing graph algorithms /////////////////////////////////////
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+
+#[cfg(test)]
+mod graph {
+    use MyMatrixLib::graph::{bfs, boolean_product, connected_components, degree_matrix, dfs, floyd_warshall,
+                             laplacian, pagerank, transitive_closure};
+    use MyMatrixLib::Matrix;
+
+    /// The undirected graph with edges 0-1, 0-2, 1-3, 2-3, 3-4 and 5-6.
+    fn undirected() -> Matrix<i32> {
+        let mut a = Matrix::zeros(7, 7);
+        for (i, j) in [(0, 1), (0, 2), (1, 3), (2, 3), (3, 4), (5, 6)] {
+            a[(i, j)] = 1;
+            a[(j, i)] = 1;
+        }
+        a
+    }
+
+    #[test]
+    fn test_graph_traversals_and_components()
+    {
+        let a = undirected();
+        assert_eq!(bfs(&a, 0), vec![0, 1, 2, 3, 4]);
+        assert_eq!(dfs(&a, 0), vec![0, 1, 3, 2, 4]);
+        assert_eq!(bfs(&a, 6), vec![6, 5]);
+        assert_eq!(connected_components(&a), vec![0, 0, 0, 0, 0, 1, 1]);
+
+        // Directed edges are followed one way by traversals, but connect components both ways
+        let directed = Matrix::from_vec(3, 3, vec![0, 0, 0, 1, 0, 0, 0, 1, 0]);
+        assert_eq!(bfs(&directed, 0), vec![0]);
+        assert_eq!(dfs(&directed, 2), vec![2, 1, 0]);
+        assert_eq!(connected_components(&directed), vec![0, 0, 0]);
+    }
+
+    #[test]
+    fn test_graph_transitive_closure_matches_reachability()
+    {
+        for seed in 0..5 {
+            let a = Matrix::<u8>::random_bernoulli_seeded(20, 20, 0.08, seed);
+            let closure = transitive_closure(&a);
+            for i in 0..20 {
+                let reachable = bfs(&a, i);
+                for j in 0..20 {
+                    // Vertex i is in its own closure only through a cycle
+                    let expected = if i == j {
+                        (0..20).any(|k| a[(i, k)] != 0 && bfs(&a, k).contains(&i))
+                    } else {
+                        reachable.contains(&j)
+                    };
+                    assert_eq!(closure[(i, j)] == 1, expected, "seed {}, ({}, {})", seed, i, j);
+                }
+            }
+        }
+
+        let a = Matrix::from_vec(2, 3, vec![2.0, 0.0, 0.0, 0.0, 0.0, -1.0]);
+        let b = Matrix::from_vec(3, 2, vec![0.0, 5.0, 1.0, 1.0, 3.0, 0.0]);
+        assert_eq!(boolean_product(&a, &b), Matrix::from_vec(2, 2, vec![0.0, 1.0, 1.0, 0.0]));
+    }
+
+    #[test]
+    fn test_graph_floyd_warshall()
+    {
+        let inf = f64::INFINITY;
+        let weights = Matrix::from_vec(4, 4, vec![0.0, 5.0, 0.0, 10.0,
+                                                  0.0, 0.0, 3.0, 0.0,
+                                                  0.0, -2.0, 0.0, 1.0,
+                                                  0.0, 0.0, 0.0, 0.0]);
+        let distances = floyd_warshall(&weights).unwrap();
+        assert_eq!(distances, Matrix::from_vec(4, 4, vec![0.0, 5.0, 8.0, 9.0,
+                                                          inf, 0.0, 3.0, 4.0,
+                                                          inf, -2.0, 0.0, 1.0,
+                                                          inf, inf, inf, 0.0]));
+
+        // On an unweighted graph the distances count edges, as found by breadth-first search
+        let a = Matrix::<f64>::random_bernoulli_seeded(15, 15, 0.15, 7);
+        let distances = floyd_warshall(&a).unwrap();
+        let hops = |start: usize, target: usize| {
+            let mut level = vec![start];
+            for d in 0..15 {
+                if level.contains(&target) {
+                    return d as f64;
+                }
+                level = (0..15).filter(|&j| level.iter().any(|&i| a[(i, j)] != 0.0)).collect();
+            }
+            inf
+        };
+        for i in 0..15 {
+            for j in 0..15 {
+                assert_eq!(distances[(i, j)], hops(i, j), "({}, {})", i, j);
+            }
+        }
+
+        let negative_cycle = Matrix::from_vec(2, 2, vec![0.0, 1.0, -2.0, 0.0]);
+        assert_eq!(floyd_warshall(&negative_cycle), None);
+    }
+
+    #[test]
+    fn test_graph_degree_and_laplacian()
+    {
+        let a = undirected();
+        assert_eq!(degree_matrix::<_, i32>(&a).diag(), Matrix::from_vec(7, 1, vec![2, 2, 2, 3, 1, 1, 1]));
+        let l: Matrix<i32> = laplacian(&a);
+        assert_eq!(l.sum_axis(1), Matrix::zeros(7, 1));
+        assert_eq!((l[(3, 3)], l[(3, 4)], l[(4, 5)]), (3, -1, 0));
+
+        // The multiplicity of the eigenvalue zero is the number of components
+        let values: Vec<f64> = l.as_slice().iter().map(|&x| x as f64).collect();
+        let (eigenvalues, _) = Matrix::from_vec(7, 7, values).symmetric_eigen();
+        assert_eq!(eigenvalues.iter().filter(|x| x.abs() < 1e-9).count(), 2);
+    }
+
+    #[test]
+    fn test_graph_unsigned_laplacian()
+    {
+        // 0/1 matrices from random_bernoulli are unsigned, so the Laplacian is computed in a
+        // signed type
+        let a = Matrix::<u8>::random_bernoulli_seeded(30, 30, 0.2, 11);
+        let l: Matrix<i32> = laplacian(&a);
+        let signed = Matrix::from_vec(30, 30, a.as_slice().iter().map(|&x| x as i32).collect());
+        assert_eq!(l, laplacian(&signed));
+        assert_eq!(l.sum_axis(1), Matrix::zeros(30, 1));
+        assert_eq!(laplacian::<u8, i32>(&Matrix::from_vec(2, 2, vec![0, 1, 1, 0])), Matrix::from_vec(2, 2, vec![1, -1, -1, 1]));
+
+        // Degrees that do not fit in the element type of the adjacency matrix
+        let complete = Matrix::<u8>::ones(300, 300);
+        assert_eq!(degree_matrix::<u8, u32>(&complete)[(0, 0)], 300);
+        assert_eq!(laplacian::<u8, f64>(&complete)[(299, 299)], 299.0);
+    }
+
+    #[test]
+    fn test_graph_pagerank()
+    {
+        // A directed cycle has the uniform distribution as its stationary distribution
+        let cycle = Matrix::from_vec(3, 3, vec![0.0, 1.0, 0.0, 0.0, 0.0, 1.0, 1.0, 0.0, 0.0]);
+        let ranks = pagerank(&cycle, 0.85, 1e-12, 1000);
+        assert!(ranks.approx_eq(&Matrix::from_vec(3, 1, vec![1.0 / 3.0; 3]), 1e-9, 1e-9));
+
+        // The ranks sum to one and satisfy the PageRank equations, including a dangling vertex
+        let a = Matrix::from_vec(4, 4, vec![0.0, 1.0, 1.0, 0.0,
+                                            0.0, 0.0, 2.0, 0.0,
+                                            1.0, 0.0, 0.0, 0.0,
+                                            0.0, 0.0, 0.0, 0.0]);
+        let (d, r) = (0.85_f64, pagerank(&a, 0.85, 1e-14, 1000));
+        let total: f64 = r.sum();
+        assert!((total - 1.0).abs() < 1e-12);
+        let dangling = r[(3, 0)];
+        let expected = [
+            (1.0 - d) / 4.0 + d * (r[(2, 0)] + dangling / 4.0),
+            (1.0 - d) / 4.0 + d * (r[(0, 0)] / 2.0 + dangling / 4.0),
+            (1.0 - d) / 4.0 + d * (r[(0, 0)] / 2.0 + r[(1, 0)] + dangling / 4.0),
+            (1.0 - d) / 4.0 + d * dangling / 4.0,
+        ];
+        for (i, value) in expected.iter().enumerate() {
+            assert!((r[(i, 0)] - value).abs() < 1e-12, "{} vs {}", r, value);
+        }
+        assert_eq!(r.arg_max(), (2, 0));
+    }
+
+    #[test]
+    #[should_panic(expected = "must be square")]
+    fn test_graph_requires_square_matrix()
+    {
+        bfs(&Matrix::<i32>::zeros(2, 3), 0);
+    }
 }